@@ -1,6 +1,9 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use readability::{extract, ExtractOptions, ScorerOptions};
-use std::{fs::File, str::FromStr};
+use readability::{extract, extract_from_str, ExtractOptions, Extractor, Preset, ScorerOptions};
+use std::{
+    fs::{self, File},
+    str::FromStr,
+};
 use url::Url;
 
 fn extract_options<'a>(max_candidate_parents: usize) -> ExtractOptions<'a> {
@@ -10,6 +13,7 @@ fn extract_options<'a>(max_candidate_parents: usize) -> ExtractOptions<'a> {
             max_candidate_parents,
             ..Default::default()
         },
+        ..Default::default()
     }
 }
 
@@ -34,5 +38,47 @@ fn bench_extract(c: &mut Criterion) {
     });
 }
 
-criterion_group!(name = benches; config = Criterion::default().sample_size(10); targets = bench_extract);
+fn bench_extractor(c: &mut Criterion) {
+    let html = fs::read_to_string("benches/wikipedia.html").unwrap();
+    let url = Url::from_str("https://en.wikipedia.org/wiki/Particle_physics").unwrap();
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            legacy_candidate_matching: true,
+            ..ExtractOptions::preset(Preset::News).scorer_options
+        },
+        ..ExtractOptions::preset(Preset::News)
+    };
+
+    // The free function prepares the scorer for every document, the extractor
+    // only once.
+    c.bench_function("extract_from_str news", |b| {
+        b.iter(|| extract_from_str(&html, &url, options.clone()));
+    });
+
+    let extractor = Extractor::new(options.clone()).unwrap();
+    c.bench_function("extractor news", |b| {
+        b.iter(|| extractor.extract_from_str(&html, &url));
+    });
+}
+
+fn bench_parallel(c: &mut Criterion) {
+    let html = fs::read_to_string("benches/wikipedia.html").unwrap();
+    let url = Url::from_str("https://en.wikipedia.org/wiki/Particle_physics").unwrap();
+
+    for parallel in [false, true] {
+        let options = ExtractOptions {
+            scorer_options: ScorerOptions {
+                parallel,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        c.bench_function(&format!("extract parallel {parallel}"), |b| {
+            b.iter(|| extract_from_str(&html, &url, options.clone()));
+        });
+    }
+}
+
+criterion_group!(name = benches; config = Criterion::default().sample_size(10); targets = bench_extract, bench_extractor, bench_parallel);
 criterion_main!(benches);
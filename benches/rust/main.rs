@@ -11,6 +11,7 @@ fn extract_options<'a>(max_candidate_parents: usize) -> ExtractOptions<'a> {
             max_candidate_parents,
             ..Default::default()
         },
+        ..Default::default()
     }
 }
 
@@ -25,7 +26,7 @@ fn main() {
 
     let start = Instant::now();
 
-    let content = extract_content(&mut dom, &url, opts);
+    let content = extract_content(&mut dom, &url, opts).unwrap();
 
     let mut bytes = vec![];
 
@@ -0,0 +1,56 @@
+#ifndef READABILITY_H
+#define READABILITY_H
+
+/* This file is generated by cbindgen. Do not edit manually. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Extract the readable content from an HTML document.
+ *
+ * Returns a JSON object with exactly the fields `title`, `content`, `text`,
+ * `byline`, `lang`, and `detected_language`, which must be freed with
+ * `readability_string_free`.
+ * Returns null on error, and the error message can be retrieved with
+ * `readability_last_error`.
+ *
+ * # Safety
+ *
+ * `html` must point to `len` readable bytes. `url` must be a null-terminated
+ * string. `options_json` must be null or a null-terminated JSON object with
+ * the optional fields `strict`, `errorOnNoContent`, `minCandidateLength`, and
+ * `maxCandidateParents`.
+ */
+char *readability_extract(const uint8_t *html,
+                          size_t len,
+                          const char *url,
+                          const char *options_json);
+
+/**
+ * Return the message of the last error on the current thread, or null if no
+ * error occurred.
+ *
+ * The returned string must be freed with `readability_string_free`.
+ */
+char *readability_last_error(void);
+
+/**
+ * Free a string returned by this library.
+ *
+ * # Safety
+ *
+ * `string` must be null or a string returned by this library, which wasn't
+ * freed before.
+ */
+void readability_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* READABILITY_H */
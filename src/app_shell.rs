@@ -0,0 +1,95 @@
+//! Detect app shells, i.e. pages whose content is rendered by JavaScript, so
+//! the url can be fetched again with a headless browser.
+
+use crate::dom::{self, Handle, NodeData};
+
+/// The maximal number of characters of the visible text of an app shell.
+const MAX_SHELL_TEXT_LENGTH: usize = 200;
+/// The ids of the root elements of common frameworks, e.g. React, Vue,
+/// Next.js, Nuxt, or Gatsby.
+const APP_ROOT_IDS: [&str; 5] = ["root", "app", "__next", "__nuxt", "___gatsby"];
+/// The messages of `noscript` elements asking to enable JavaScript.
+const NOSCRIPT_MESSAGES: [&str; 4] = [
+    "enable javascript",
+    "javascript is required",
+    "requires javascript",
+    "javascript enabled",
+];
+
+/// Check if the document is an app shell, i.e. its body has almost no
+/// visible text, and it has an empty root element of a framework with
+/// scripts, the data of Next.js, or a `noscript` message asking to enable
+/// JavaScript.
+///
+/// Must be called before preprocessing removes the scripts.
+pub(crate) fn is_app_shell(document: &Handle) -> bool {
+    let Some(body) = dom::find_by_tag(document, "body").into_iter().next() else {
+        return false;
+    };
+
+    if visible_text_len(&body) > MAX_SHELL_TEXT_LENGTH {
+        return false;
+    }
+
+    let scripts = dom::find_by_tag(document, "script");
+    let has_next_data = scripts
+        .iter()
+        .any(|script| dom::get_attr(script, "id").as_deref() == Some("__NEXT_DATA__"));
+    let has_app_root = !scripts.is_empty()
+        && dom::descendants(&body).any(|node| {
+            dom::get_attr(&node, "id").is_some_and(|id| APP_ROOT_IDS.contains(&id.as_str()))
+        });
+    let has_noscript_message = dom::find_by_tag(&body, "noscript").iter().any(|noscript| {
+        let text = dom::text_content(noscript).to_lowercase();
+        NOSCRIPT_MESSAGES
+            .iter()
+            .any(|message| text.contains(message))
+    });
+
+    has_next_data || has_app_root || has_noscript_message
+}
+
+/// Count the characters of the text, excluding scripts, styles, templates, and
+/// `noscript` elements.
+fn visible_text_len(handle: &Handle) -> usize {
+    match handle.data {
+        NodeData::Text { ref contents } => contents.borrow().trim().chars().count(),
+        NodeData::Element { ref name, .. }
+            if dom::matches_any_tag(name, &["script", "style", "template", "noscript"]) =>
+        {
+            0
+        }
+        _ => handle.children.borrow().iter().map(visible_text_len).sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    fn is_shell(html: &str) -> bool {
+        let dom = parse_document(RcDom::default(), Default::default()).one(html);
+        is_app_shell(&dom.document)
+    }
+
+    #[test]
+    fn test_is_app_shell() {
+        assert!(is_shell(
+            r#"<body><div id="root"></div><script src="main.js"></script></body>"#
+        ));
+        assert!(is_shell(
+            r#"<body><noscript>You need to enable JavaScript to run this app.</noscript></body>"#
+        ));
+        assert!(is_shell(
+            r#"<body><div id="__next"></div><script id="__NEXT_DATA__" type="application/json">{}</script></body>"#
+        ));
+        assert!(!is_shell(r#"<body><div id="root"></div></body>"#));
+
+        let article = "This is a server-rendered article. ".repeat(10);
+        assert!(!is_shell(&format!(
+            r#"<body><div id="__next"><p>{article}</p></div><script id="__NEXT_DATA__">{{}}</script></body>"#
+        )));
+    }
+}
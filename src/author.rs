@@ -0,0 +1,125 @@
+//! Detect the author bio of an article.
+
+use crate::dom::{self, Handle};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// The pattern for the `class` or `id` of author bio boxes.
+pub const AUTHOR_BIOS: &str = concat!(
+    r"(?i)(\b|_|-)(author-?(bio|box|info|card|profile|details|description)",
+    r"|about-?(the-?)?authors?|bio-?box|contributor-?bio)(\b|_|-)"
+);
+
+/// The minimal and maximal text length of an author bio, which excludes bylines
+/// and wrappers of the whole article.
+const AUTHOR_BIO_LENGTH: std::ops::RangeInclusive<usize> = 50..=1500;
+
+lazy_static! {
+    static ref AUTHOR_BIO: Regex = Regex::new(AUTHOR_BIOS).unwrap();
+    static ref ABOUT_THE_AUTHOR: Regex = Regex::new(r"(?i)^about the authors?\b").unwrap();
+}
+
+/// What to do with the author bio of an article, e.g. an "About the author"
+/// box with an avatar and a short bio.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuthorBio {
+    /// Leave the author bio to the scorer, which usually removes it.
+    #[default]
+    Remove,
+    /// Move the author bio to the end of the content, and set
+    /// [`Readable::author_bio`](crate::Readable::author_bio).
+    Preserve,
+    /// Remove the author bio from the content, and set
+    /// [`Readable::author_bio`](crate::Readable::author_bio).
+    Extract,
+}
+
+/// Find the author bio in the document, i.e. the first element
+///
+/// - whose `class` or `id` matches [`AUTHOR_BIOS`], or
+/// - with an `itemscope` and an `itemprop` containing `author`, or
+/// - whose first child starts with "About the author",
+///
+/// and whose text has a length between 50 and 1500 characters.
+pub(crate) fn find_author_bio(document: &Handle) -> Option<Handle> {
+    dom::descendants(document).find(|node| {
+        match dom::tag_name(node) {
+            None | Some("html" | "head" | "body" | "article" | "main" | "script" | "style") => {
+                return false
+            }
+            _ => (),
+        }
+
+        let is_author_bio = ["class", "id"]
+            .iter()
+            .any(|name| dom::get_attr(node, name).is_some_and(|value| AUTHOR_BIO.is_match(&value)))
+            || (dom::get_attr(node, "itemscope").is_some()
+                && dom::get_attr(node, "itemprop")
+                    .is_some_and(|itemprop| itemprop.contains("author")))
+            || starts_with_about_the_author(node);
+
+        is_author_bio && AUTHOR_BIO_LENGTH.contains(&text(node).chars().count())
+    })
+}
+
+/// Get the text of the author bio with collapsed whitespace.
+pub(crate) fn text(handle: &Handle) -> String {
+    dom::text_content(handle)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn starts_with_about_the_author(handle: &Handle) -> bool {
+    if !matches!(
+        dom::tag_name(handle),
+        Some("div" | "section" | "aside" | "footer")
+    ) {
+        return false;
+    }
+
+    let first_child = handle
+        .children
+        .borrow()
+        .iter()
+        .find(|child| dom::tag_name(child).is_some())
+        .cloned();
+
+    first_child.is_some_and(|child| ABOUT_THE_AUTHOR.is_match(&text(&child)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    fn parse(html: &str) -> RcDom {
+        parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_find_author_bio() {
+        let bio = "Jane Doe is a science reporter who writes about physics and space.";
+
+        for html in [
+            format!(
+                r#"<article><p>Text</p><div class="post-author-bio"><p>{bio}</p></div></article>"#
+            ),
+            format!(
+                r#"<div itemscope itemprop="author" itemtype="https://schema.org/Person">{bio}</div>"#
+            ),
+            format!(r#"<aside><h3>About the author</h3><p>{bio}</p></aside>"#),
+        ] {
+            let dom = parse(&html);
+            let author_bio = find_author_bio(&dom.document).unwrap();
+            assert!(text(&author_bio).ends_with(bio));
+        }
+
+        let dom = parse(r#"<p class="author-info">By Jane Doe</p>"#);
+        assert!(find_author_bio(&dom.document).is_none());
+    }
+}
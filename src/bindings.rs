@@ -0,0 +1,73 @@
+//! Options and results shared by the language bindings.
+
+use crate::{ExtractOptions, FallbackPolicy, ParseOptions, Readable, ScorerOptions};
+
+/// The subset of [`ExtractOptions`] which can be passed from other languages.
+#[derive(Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(default, rename_all = "camelCase")
+)]
+pub(crate) struct BindingOptions {
+    pub strict: bool,
+    pub error_on_no_content: bool,
+    pub min_candidate_length: Option<usize>,
+    pub max_candidate_parents: Option<usize>,
+}
+
+impl BindingOptions {
+    pub fn to_extract_options(&self) -> ExtractOptions<'static> {
+        let default_scorer_options = ScorerOptions::default();
+
+        ExtractOptions {
+            parse_options: ParseOptions {
+                strict: self.strict,
+                ..Default::default()
+            },
+            scorer_options: ScorerOptions {
+                min_candidate_length: self
+                    .min_candidate_length
+                    .unwrap_or(default_scorer_options.min_candidate_length),
+                max_candidate_parents: self
+                    .max_candidate_parents
+                    .unwrap_or(default_scorer_options.max_candidate_parents),
+                ..default_scorer_options
+            },
+            fallback_policy: if self.error_on_no_content {
+                FallbackPolicy::Error
+            } else {
+                FallbackPolicy::Body
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// The subset of [`Readable`] which is returned to other languages.
+///
+/// Fields added to [`Readable`] are not returned unless they are added here,
+/// so that the output of the bindings stays stable.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub(crate) struct BindingReadable<'a> {
+    pub title: &'a str,
+    pub content: &'a str,
+    pub text: &'a str,
+    pub byline: Option<&'a str>,
+    pub lang: Option<&'a str>,
+    pub detected_language: Option<&'a str>,
+}
+
+impl<'a> From<&'a Readable> for BindingReadable<'a> {
+    fn from(readable: &'a Readable) -> Self {
+        Self {
+            title: &readable.title,
+            content: &readable.content,
+            text: &readable.text,
+            byline: readable.byline.as_deref(),
+            lang: readable.lang.as_deref(),
+            detected_language: readable.detected_language.as_deref(),
+        }
+    }
+}
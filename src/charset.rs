@@ -0,0 +1,76 @@
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+use lazy_static::lazy_static;
+use regex::bytes::Regex;
+use std::borrow::Cow;
+
+/// The number of bytes which are scanned for a `meta` charset declaration.
+const PRESCAN_LENGTH: usize = 1024;
+
+lazy_static! {
+    static ref META_CHARSET: Regex =
+        Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?\s*([a-zA-Z0-9_:.-]+)"#).unwrap();
+}
+
+/// Detect the character encoding of an HTML document.
+///
+/// The encoding is determined by the byte order mark, a `meta` charset
+/// declaration in the first 1024 bytes, or whether the input is valid UTF-8,
+/// falling back to windows-1252.
+pub fn detect(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+
+    let prescan = &bytes[..bytes.len().min(PRESCAN_LENGTH)];
+
+    if let Some(encoding) = META_CHARSET
+        .captures(prescan)
+        .and_then(|captures| captures.get(1))
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+    {
+        // A document which was decoded as bytes can't be UTF-16.
+        return encoding.output_encoding();
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        UTF_8
+    } else {
+        WINDOWS_1252
+    }
+}
+
+/// Decode an HTML document to UTF-8.
+pub fn decode(bytes: &[u8]) -> Cow<'_, str> {
+    let (html, _, _) = detect(bytes).decode(bytes);
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{SHIFT_JIS, UTF_16LE};
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(b"<p>caf\xc3\xa9</p>"), UTF_8);
+        assert_eq!(detect(b"<p>caf\xe9</p>"), WINDOWS_1252);
+        assert_eq!(detect(b"\xff\xfe<\0p\0>\0"), UTF_16LE);
+        assert_eq!(
+            detect(b"<head><meta charset=\"Shift_JIS\"></head><p>\x93\xfa\x96\x7b</p>"),
+            SHIFT_JIS
+        );
+        assert_eq!(
+            detect(b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=utf-16\">"),
+            UTF_8
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(b"<p>caf\xe9</p>"), "<p>café</p>");
+        assert_eq!(
+            decode(b"<meta charset=\"Shift_JIS\"><p>\x93\xfa\x96\x7b</p>"),
+            "<meta charset=\"Shift_JIS\"><p>日本</p>"
+        );
+    }
+}
@@ -0,0 +1,956 @@
+//! Heuristics to remove boilerplate from the content during
+//! [`Scorer::clean`](crate::Scorer::clean).
+
+use crate::{
+    dom::{get_attr, matches_any_tag, text_content, Handle, NodeData, RcDom},
+    html,
+    render::BLOCK_ELEMENTS,
+    scorer::get_link_density,
+};
+use html5ever::{
+    local_name,
+    tree_builder::{NodeOrText, TreeSink},
+    Attribute,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+use url::Url;
+
+/// The default pattern for [`CleanOptions::share_elements`].
+pub const SHARE_ELEMENTS: &str = r"(\b|_)(share|sharedaddy|social)(\b|_)";
+/// The default pattern for [`CleanOptions::share_links`].
+pub const SHARE_LINKS: &str = concat!(
+    r"facebook\.com/sharer|(twitter|x)\.com/(intent|share)|t\.me/share",
+    r"|linkedin\.com/(shareArticle|sharing)|pinterest\.com/pin/create|reddit\.com/submit",
+    r"|wa\.me/|api\.whatsapp\.com/send|^mailto:.*[?&]subject="
+);
+/// The default pattern for [`CleanOptions::related_sections`].
+pub const RELATED_SECTIONS: &str = concat!(
+    r"(?i)^(related|more from|more stories|recommended|read more",
+    r"|read next|you may also like|you might also like|see also|popular)"
+);
+/// The default pattern for [`CleanOptions::signup_elements`].
+pub const SIGNUP_ELEMENTS: &str = r"(?i)subscribe|newsletter|sign up|signup|inbox|mailing list";
+/// The default query parameters which are stripped from links if
+/// [`CleanOptions::strip_tracking_params`] is enabled.
+///
+/// A trailing `*` matches any parameter with the given prefix.
+pub const TRACKING_PARAMS: [&str; 12] = [
+    "utm_*", "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "mc_cid", "mc_eid",
+    "igshid", "yclid", "_hsenc",
+];
+/// The default pattern for [`CleanOptions::tracking_images`].
+pub const TRACKING_IMAGES: &str =
+    r"(?i)/(spacer|pixel|blank|transparent|clear|tracking|beacon|1x1)(\.(gif|png))?$";
+/// The default hosts of tracking pixels which are removed if
+/// [`CleanOptions::remove_tracking_images`] is enabled.
+///
+/// Subdomains of the hosts are matched as well.
+pub const TRACKING_HOSTS: [&str; 10] = [
+    "pixel.wp.com",
+    "stats.wp.com",
+    "google-analytics.com",
+    "doubleclick.net",
+    "scorecardresearch.com",
+    "quantserve.com",
+    "bat.bing.com",
+    "px.ads.linkedin.com",
+    "ct.pinterest.com",
+    "facebook.com/tr",
+];
+/// The attributes of lazy-loaded images which contain the real source.
+const LAZY_SOURCES: [&str; 4] = ["data-src", "data-original", "data-lazy-src", "data-url"];
+lazy_static! {
+    static ref TRACKING_IMAGE: Regex = Regex::new(TRACKING_IMAGES).unwrap();
+    static ref SIGNUP_ELEMENT: Regex = Regex::new(SIGNUP_ELEMENTS).unwrap();
+    static ref SHARE_ELEMENT: Regex = Regex::new(SHARE_ELEMENTS).unwrap();
+    static ref SHARE_LINK: Regex = Regex::new(SHARE_LINKS).unwrap();
+    static ref RELATED_SECTION: Regex = Regex::new(RELATED_SECTIONS).unwrap();
+}
+
+/// What to do with images whose source is a `data:` url.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum DataUriPolicy {
+    /// Keep all images.
+    #[default]
+    Keep,
+    /// Remove all images.
+    Drop,
+    /// Keep images whose `data:` url is shorter than this number of bytes.
+    MaxBytes(usize),
+}
+
+/// The kind of element whose url is rewritten by a [`UrlRewriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UrlKind {
+    /// The `src` of an `img` element.
+    Image,
+    /// The `href` of an `a` element.
+    Link,
+}
+
+/// Rewrite the urls of images and links of the content, e.g. to route images
+/// through a caching proxy or to block hosts.
+///
+/// The url is already resolved against the url of the document. Closures
+/// implement the trait:
+///
+/// ```
+/// use readability::{CleanOptions, UrlKind};
+/// use url::Url;
+///
+/// let block_ads =
+///     |url: Url, _kind: UrlKind| (url.host_str() != Some("ads.example.com")).then_some(url);
+/// let options = CleanOptions {
+///     url_rewriter: Some(&block_ads),
+///     ..Default::default()
+/// };
+/// ```
+pub trait UrlRewriter: Send + Sync {
+    /// Rewrite the url, or return `None` to remove the image or the `href` of
+    /// the link.
+    fn rewrite(&self, url: Url, kind: UrlKind) -> Option<Url>;
+}
+
+impl<F> UrlRewriter for F
+where
+    F: Fn(Url, UrlKind) -> Option<Url> + Send + Sync,
+{
+    fn rewrite(&self, url: Url, kind: UrlKind) -> Option<Url> {
+        self(url, kind)
+    }
+}
+
+impl fmt::Debug for dyn UrlRewriter + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("UrlRewriter")
+    }
+}
+
+/// What to do with an element of the content, see [`CleanPredicate`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CleanDecision {
+    /// Apply the next predicate, or the built-in rules.
+    #[default]
+    Continue,
+    /// Remove the element, including its descendants.
+    Remove,
+    /// Keep the element, although the built-in rules would remove it. Its
+    /// descendants are still cleaned.
+    Keep,
+}
+
+/// An element of the content during cleaning, see [`CleanPredicate`].
+#[derive(Debug, Clone, Copy)]
+pub struct NodeView<'a> {
+    /// The lowercase tag name.
+    pub tag: &'a str,
+    /// The element in the document which is cleaned.
+    pub handle: &'a Handle,
+}
+
+impl NodeView<'_> {
+    /// The value of an attribute, if any.
+    pub fn attr(&self, name: &str) -> Option<String> {
+        get_attr(self.handle, name)
+    }
+
+    /// The text of the element and its descendants.
+    pub fn text(&self) -> String {
+        text_content(self.handle)
+    }
+}
+
+/// Decide whether to remove or keep an element of the content before the
+/// built-in rules of [`Scorer::clean`](crate::Scorer::clean) are applied,
+/// e.g. to remove promotions of a specific site. Closures implement the
+/// trait:
+///
+/// ```
+/// use readability::{CleanDecision, CleanOptions, NodeView};
+///
+/// let remove_promos = |node: &NodeView| match node.attr("data-testid").as_deref() {
+///     Some("promo") => CleanDecision::Remove,
+///     _ => CleanDecision::Continue,
+/// };
+/// let options = CleanOptions {
+///     clean_predicates: &[&remove_promos],
+///     ..Default::default()
+/// };
+/// ```
+pub trait CleanPredicate: Send + Sync {
+    /// Decide whether to remove or keep the element, or to continue with the
+    /// next predicate.
+    fn decide(&self, node: &NodeView) -> CleanDecision;
+}
+
+impl<F> CleanPredicate for F
+where
+    F: Fn(&NodeView) -> CleanDecision + Send + Sync,
+{
+    fn decide(&self, node: &NodeView) -> CleanDecision {
+        self(node)
+    }
+}
+
+impl fmt::Debug for dyn CleanPredicate + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CleanPredicate")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CleanOptions<'a> {
+    /// Remove share and social widgets.
+    pub remove_share_elements: bool,
+    /// The regex for the `id` or `class` of share elements.
+    pub share_elements: &'a Regex,
+    /// The regex for the `href` of share links.
+    pub share_links: &'a Regex,
+    /// Elements with at least this text length are never removed as share
+    /// elements.
+    pub share_element_threshold: usize,
+    /// Remove sections of related articles at the end of the content.
+    pub remove_related_sections: bool,
+    /// The regex for the heading of related sections, e.g. "Related" or
+    /// "More from".
+    pub related_sections: &'a Regex,
+    /// The minimal link density of related sections.
+    pub related_link_density: f32,
+    /// Remove newsletter and subscription forms.
+    pub remove_signup_elements: bool,
+    /// The regex for the text of signup elements.
+    pub signup_elements: &'a Regex,
+    /// Elements with at least this text length are never removed as signup
+    /// elements.
+    pub signup_element_threshold: usize,
+    /// Strip tracking parameters from links.
+    pub strip_tracking_params: bool,
+    /// The query parameters which are stripped from links, e.g. [`TRACKING_PARAMS`].
+    pub tracking_params: &'a [&'a str],
+    /// Keep links to fragments, e.g. `href="#note-1"`, relative instead of
+    /// resolving them against the url, and keep the `id` of their targets.
+    pub keep_fragment_links: bool,
+    /// Move footnotes which are referenced from the content, but live outside
+    /// of it, into a footnotes section at the end of the content. Requires
+    /// [`keep_fragment_links`](Self::keep_fragment_links).
+    pub preserve_footnotes: bool,
+    /// Remove tracking pixels and spacer images, which would otherwise count
+    /// as content images.
+    pub remove_tracking_images: bool,
+    /// Images with a `width` or `height` attribute below this size in pixels
+    /// are removed as tracking images.
+    pub min_image_size: u32,
+    /// The regex for the path of tracking images, e.g. `spacer.gif`.
+    pub tracking_images: &'a Regex,
+    /// The hosts of tracking images, e.g. [`TRACKING_HOSTS`]. An entry can
+    /// contain a path prefix, e.g. `facebook.com/tr`.
+    pub tracking_hosts: &'a [&'a str],
+    /// What to do with images whose source is a `data:` url. Placeholders of
+    /// lazy-loaded images are always replaced by the real source, e.g. from a
+    /// `data-src` attribute.
+    pub data_uri_policy: DataUriPolicy,
+    /// Remove inline SVG and MathML, e.g. for consumers which only need the
+    /// text. Otherwise, they are kept intact.
+    pub remove_svg_and_math: bool,
+    /// Remove paragraphs and wrappers which only contain whitespace or `br`
+    /// elements after cleaning.
+    pub remove_empty_paragraphs: bool,
+    /// Replace `div` and `section` wrappers with a single block element child
+    /// by the child after cleaning, e.g. `div > div > p` by `div > p`.
+    pub unwrap_wrappers: bool,
+    /// The attributes which are kept although they are usually removed, e.g.
+    /// `["class"]`.
+    ///
+    /// Only the `id`, `class`, and `style` attributes are removed from the
+    /// content, so this only affects them. Other attributes like `data-*` are
+    /// always kept.
+    pub preserved_attributes: &'a [&'a str],
+    /// Upgrade the `http` urls of images to `https` if the document is served
+    /// over `https`, which avoids mixed content.
+    pub upgrade_insecure: bool,
+    /// Rewrite the urls of images and links, which are rewritten for
+    /// absolute urls as well.
+    pub url_rewriter: Option<&'a dyn UrlRewriter>,
+    /// The predicates which are consulted in order for each element of the
+    /// content, before the built-in rules. The first decision other than
+    /// [`CleanDecision::Continue`] applies.
+    pub clean_predicates: &'a [&'a dyn CleanPredicate],
+}
+
+impl Default for CleanOptions<'_> {
+    fn default() -> Self {
+        Self {
+            remove_share_elements: true,
+            share_elements: &SHARE_ELEMENT,
+            share_links: &SHARE_LINK,
+            share_element_threshold: 500,
+            remove_related_sections: true,
+            related_sections: &RELATED_SECTION,
+            related_link_density: 0.5,
+            remove_signup_elements: true,
+            signup_elements: &SIGNUP_ELEMENT,
+            signup_element_threshold: 300,
+            strip_tracking_params: false,
+            tracking_params: &TRACKING_PARAMS,
+            keep_fragment_links: true,
+            preserve_footnotes: true,
+            remove_tracking_images: true,
+            min_image_size: 5,
+            tracking_images: &TRACKING_IMAGE,
+            tracking_hosts: &TRACKING_HOSTS,
+            data_uri_policy: DataUriPolicy::default(),
+            remove_svg_and_math: false,
+            remove_empty_paragraphs: true,
+            unwrap_wrappers: true,
+            preserved_attributes: &[],
+            upgrade_insecure: false,
+            url_rewriter: None,
+            clean_predicates: &[],
+        }
+    }
+}
+
+impl CleanOptions<'_> {
+    /// Consult the predicates in order, see [`CleanOptions::clean_predicates`].
+    pub(crate) fn decide(&self, tag: &str, handle: &Handle) -> CleanDecision {
+        let node = NodeView { tag, handle };
+
+        self.clean_predicates
+            .iter()
+            .map(|predicate| predicate.decide(&node))
+            .find(|decision| *decision != CleanDecision::Continue)
+            .unwrap_or_default()
+    }
+
+    /// Check if an element is a share widget, i.e. its `id` or `class` matches
+    /// the share elements, or its links are predominantly share links, and its
+    /// text is short.
+    pub(crate) fn is_share_element(&self, handle: &Handle) -> bool {
+        if !self.remove_share_elements || html::text_len(handle) >= self.share_element_threshold {
+            return false;
+        }
+
+        let match_string = [get_attr(handle, "id"), get_attr(handle, "class")]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if self.share_elements.is_match(&match_string) {
+            return true;
+        }
+
+        let mut links = vec![];
+        html::find_node(handle, "a", &mut links);
+        let share_links = links
+            .iter()
+            .filter(|link| {
+                get_attr(link, "href").is_some_and(|href| self.share_links.is_match(&href))
+            })
+            .count();
+
+        share_links > 0 && share_links * 2 >= links.len()
+    }
+
+    /// Check if an element is a newsletter or subscription form, i.e. it
+    /// contains an email input, or a form control and a text matching the
+    /// signup elements, and its text is short.
+    pub(crate) fn is_signup_element(&self, handle: &Handle) -> bool {
+        if !self.remove_signup_elements
+            || !is_any_tag(handle, &["div", "section", "aside", "form", "p"])
+            || html::text_len(handle) >= self.signup_element_threshold
+        {
+            return false;
+        }
+
+        let mut inputs = vec![];
+        html::find_node(handle, "input", &mut inputs);
+
+        if inputs.iter().any(|input| {
+            get_attr(input, "type")
+                .is_some_and(|input_type| input_type.eq_ignore_ascii_case("email"))
+        }) {
+            return true;
+        }
+
+        let has_form_control =
+            !inputs.is_empty() || html::has_nodes(handle, &["button", "form", "select"]);
+
+        has_form_control && self.signup_elements.is_match(&text_content(handle))
+    }
+
+    /// Strip the tracking parameters from the query of a url.
+    ///
+    /// Returns `true` if any parameter was stripped.
+    pub(crate) fn strip_tracking_params(&self, url: &mut Url) -> bool {
+        if !self.strip_tracking_params || url.query().is_none() {
+            return false;
+        }
+
+        let pairs = url
+            .query_pairs()
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect::<Vec<_>>();
+        let retained = pairs
+            .iter()
+            .filter(|(key, _)| !self.is_tracking_param(key))
+            .collect::<Vec<_>>();
+
+        if retained.len() == pairs.len() {
+            return false;
+        }
+
+        if retained.is_empty() {
+            url.set_query(None);
+        } else {
+            url.query_pairs_mut().clear().extend_pairs(retained);
+        }
+
+        true
+    }
+
+    /// Upgrade an `http` url to `https` if the document url is `https`.
+    ///
+    /// Returns `true` if the url was upgraded.
+    pub(crate) fn upgrade_insecure_url(&self, url: &mut Url, document_url: &Url) -> bool {
+        self.upgrade_insecure
+            && document_url.scheme() == "https"
+            && url.scheme() == "http"
+            && url.set_scheme("https").is_ok()
+    }
+
+    fn is_tracking_param(&self, key: &str) -> bool {
+        matches_any_name(self.tracking_params, key)
+    }
+
+    /// Remove the `id`, `class`, and `style` attributes unless they are
+    /// preserved. The `id` is kept anyway if `keep_id` is set, e.g. for the
+    /// targets of fragment links.
+    pub(crate) fn clean_attrs(&self, attrs: &mut Vec<Attribute>, keep_id: bool) {
+        for name in ["id", "class", "style"] {
+            if (name == "id" && keep_id) || matches_any_name(self.preserved_attributes, name) {
+                continue;
+            }
+            html::clean_attr(name, attrs);
+        }
+    }
+
+    /// Remove tracking pixels and spacer images, i.e. images which are smaller
+    /// than the minimal image size, or whose url matches the tracking images or
+    /// tracking hosts.
+    pub(crate) fn remove_tracking_images(
+        &self,
+        dom: &mut RcDom,
+        handle: &Handle,
+        url: &Url,
+    ) -> Vec<Handle> {
+        if !self.remove_tracking_images {
+            return vec![];
+        }
+
+        let mut images = vec![];
+        html::find_node(handle, "img", &mut images);
+        images.retain(|image| self.is_tracking_image(image, url));
+
+        for image in images.iter() {
+            dom.remove_from_parent(image);
+        }
+
+        images
+    }
+
+    fn is_tracking_image(&self, handle: &Handle, url: &Url) -> bool {
+        let is_tiny = ["width", "height"].iter().any(|name| {
+            get_attr(handle, name)
+                .and_then(|value| {
+                    let value = value.trim();
+                    value
+                        .strip_suffix("px")
+                        .unwrap_or(value)
+                        .parse::<u32>()
+                        .ok()
+                })
+                .is_some_and(|size| size < self.min_image_size)
+        });
+        if is_tiny {
+            return true;
+        }
+
+        let Some(src) = get_attr(handle, "src").and_then(|src| url.join(src.trim()).ok()) else {
+            return false;
+        };
+
+        if src.scheme() == "data" {
+            return false;
+        }
+
+        if self.tracking_images.is_match(src.path()) {
+            return true;
+        }
+
+        let Some(host) = src.host_str() else {
+            return false;
+        };
+
+        self.tracking_hosts.iter().any(|tracking_host| {
+            let (tracking_host, tracking_path) = match tracking_host.split_once('/') {
+                Some((tracking_host, tracking_path)) => (tracking_host, Some(tracking_path)),
+                None => (*tracking_host, None),
+            };
+
+            (host == tracking_host || host.ends_with(&format!(".{tracking_host}")))
+                && tracking_path.is_none_or(|tracking_path| {
+                    src.path()
+                        .trim_start_matches('/')
+                        .starts_with(tracking_path)
+                })
+        })
+    }
+
+    /// Replace the `data:` source of an image with the real source of a
+    /// lazy-loaded image, or apply the data uri policy.
+    ///
+    /// Returns `false` if the image should be removed.
+    pub(crate) fn fix_data_uri(&self, handle: &Handle) -> bool {
+        let Some(src) = get_attr(handle, "src").filter(|src| src.trim_start().starts_with("data:"))
+        else {
+            return true;
+        };
+
+        let lazy_source = LAZY_SOURCES
+            .iter()
+            .filter_map(|name| get_attr(handle, name))
+            .find(|source| !source.trim().is_empty() && !source.trim_start().starts_with("data:"));
+
+        if let Some(lazy_source) = lazy_source {
+            html::set_attr("src", lazy_source.trim(), handle);
+            return true;
+        }
+
+        match self.data_uri_policy {
+            DataUriPolicy::Keep => true,
+            DataUriPolicy::Drop => false,
+            DataUriPolicy::MaxBytes(max_bytes) => src.len() < max_bytes,
+        }
+    }
+
+    /// Remove empty paragraphs and unwrap redundant wrappers below the
+    /// content node.
+    ///
+    /// A wrapper is only unwrapped if its only child is a block element, so
+    /// that the children of adjacent wrappers stay on separate lines.
+    pub(crate) fn tidy(&self, dom: &mut RcDom, handle: &Handle) {
+        if !self.remove_empty_paragraphs && !self.unwrap_wrappers {
+            return;
+        }
+
+        for child in element_children(handle) {
+            self.tidy(dom, &child);
+
+            if self.remove_empty_paragraphs
+                && is_any_tag(&child, &["p", "div", "section"])
+                && is_blank(&child)
+            {
+                dom.remove_from_parent(&child);
+                continue;
+            }
+
+            if self.unwrap_wrappers
+                && is_any_tag(&child, &["div", "section"])
+                && get_attr(&child, "id").is_none()
+            {
+                if let Some(only_child) = only_element_child(&child)
+                    .filter(|only_child| is_any_tag(only_child, &BLOCK_ELEMENTS))
+                {
+                    dom.remove_from_parent(&only_child);
+                    dom.append_before_sibling(&child, NodeOrText::AppendNode(only_child));
+                    dom.remove_from_parent(&child);
+                }
+            }
+        }
+    }
+
+    /// Remove sections of related articles from the end of the content.
+    ///
+    /// A related section starts with a heading matching the related sections,
+    /// and consists mainly of links to the same host. The heading can also be
+    /// the preceding sibling of the section.
+    pub(crate) fn remove_related_sections(&self, dom: &mut RcDom, handle: &Handle, url: &Url) {
+        if !self.remove_related_sections {
+            return;
+        }
+
+        loop {
+            let children = element_children(handle);
+            let Some(last) = children.last() else {
+                return;
+            };
+
+            if self.is_link_section(last, url) {
+                if first_text(last).is_some_and(|text| self.related_sections.is_match(&text)) {
+                    dom.remove_from_parent(last);
+                    continue;
+                }
+
+                if let Some(heading) = children.len().checked_sub(2).map(|i| &children[i]) {
+                    if is_heading(heading)
+                        && first_text(heading)
+                            .is_some_and(|text| self.related_sections.is_match(&text))
+                    {
+                        dom.remove_from_parent(last);
+                        dom.remove_from_parent(heading);
+                        continue;
+                    }
+                }
+            }
+
+            // Continue with the tail of nested containers.
+            if let NodeData::Element { ref name, .. } = last.data {
+                if matches!(
+                    name.local,
+                    local_name!("div") | local_name!("section") | local_name!("article")
+                ) {
+                    self.remove_related_sections(dom, last, url);
+                }
+            }
+
+            return;
+        }
+    }
+
+    /// Check if the element consists mainly of links to the same host.
+    fn is_link_section(&self, handle: &Handle, url: &Url) -> bool {
+        let mut links = vec![];
+        html::find_node(handle, "a", &mut links);
+
+        let internal_links = links
+            .iter()
+            .filter_map(|link| get_attr(link, "href"))
+            .filter(|href| {
+                url.join(href)
+                    .is_ok_and(|href| href.host_str() == url.host_str())
+            })
+            .count();
+
+        internal_links > 0
+            && internal_links * 2 >= links.len()
+            && get_link_density(handle) >= self.related_link_density
+    }
+}
+
+/// Check if a name matches any of the names, where a trailing `*` matches any
+/// name with the given prefix.
+fn matches_any_name(names: &[&str], name: &str) -> bool {
+    names.iter().any(|pattern| match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == *pattern,
+    })
+}
+
+fn is_any_tag(handle: &Handle, tag_names: &[&str]) -> bool {
+    match handle.data {
+        NodeData::Element { ref name, .. } => matches_any_tag(name, tag_names),
+        _ => false,
+    }
+}
+
+fn element_children(handle: &Handle) -> Vec<Handle> {
+    handle
+        .children
+        .borrow()
+        .iter()
+        .filter(|child| matches!(child.data, NodeData::Element { .. }))
+        .cloned()
+        .collect()
+}
+
+/// Check if an element only contains whitespace and `br` elements.
+fn is_blank(handle: &Handle) -> bool {
+    handle
+        .children
+        .borrow()
+        .iter()
+        .all(|child| match child.data {
+            NodeData::Text { ref contents } => contents.borrow().trim().is_empty(),
+            NodeData::Element { .. } => is_any_tag(child, &["br"]),
+            _ => true,
+        })
+}
+
+/// Get the only element child, if the element contains no other text.
+fn only_element_child(handle: &Handle) -> Option<Handle> {
+    let mut only_child = None;
+
+    for child in handle.children.borrow().iter() {
+        match child.data {
+            NodeData::Text { ref contents } if !contents.borrow().trim().is_empty() => return None,
+            NodeData::Element { .. } if only_child.is_some() => return None,
+            NodeData::Element { .. } => only_child = Some(child.clone()),
+            _ => (),
+        }
+    }
+
+    only_child
+}
+
+fn is_heading(handle: &Handle) -> bool {
+    match handle.data {
+        NodeData::Element { ref name, .. } => matches!(
+            name.local,
+            local_name!("h1")
+                | local_name!("h2")
+                | local_name!("h3")
+                | local_name!("h4")
+                | local_name!("h5")
+                | local_name!("h6")
+        ),
+        _ => false,
+    }
+}
+
+/// Get the first non-empty text of an element.
+fn first_text(handle: &Handle) -> Option<String> {
+    for child in handle.children.borrow().iter() {
+        match child.data {
+            NodeData::Text { ref contents } => {
+                let text = contents.borrow();
+                if !text.trim().is_empty() {
+                    return Some(text.trim().to_owned());
+                }
+            }
+            NodeData::Element { .. } => {
+                if let Some(text) = first_text(child) {
+                    return Some(text);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::{find_by_tag, is_tag};
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    fn parse(html: &str) -> RcDom {
+        parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_is_share_element() {
+        let dom = parse(
+            r#"<div class="post-share">Share this</div>
+            <div><a href="https://www.facebook.com/sharer/sharer.php?u=x">Facebook</a>
+                <a href="https://twitter.com/intent/tweet?url=x">Twitter</a>
+                <a href="https://example.com/about">About</a></div>
+            <div><a href="https://example.com/a">A</a><a href="https://example.com/b">B</a></div>
+            <div class="shared-content">Content</div>"#,
+        );
+        let divs = find_by_tag(&dom.document, "div");
+        let options = CleanOptions::default();
+
+        assert!(options.is_share_element(&divs[0]));
+        assert!(options.is_share_element(&divs[1]));
+        assert!(!options.is_share_element(&divs[2]));
+        assert!(!options.is_share_element(&divs[3]));
+
+        let options = CleanOptions {
+            remove_share_elements: false,
+            ..Default::default()
+        };
+        assert!(!options.is_share_element(&divs[0]));
+        assert!(SHARE_LINK.is_match("https://t.me/share/url?url=x"));
+    }
+
+    #[test]
+    fn test_is_signup_element() {
+        let dom = parse(
+            r#"<div><p>Get our newsletter</p><input type="EMAIL"></div>
+            <div>Subscribe to our newsletter <button>Subscribe</button></div>
+            <div>The newsletter was founded in 1990.</div>"#,
+        );
+        let divs = find_by_tag(&dom.document, "div");
+        let options = CleanOptions::default();
+
+        assert!(options.is_signup_element(&divs[0]));
+        assert!(options.is_signup_element(&divs[1]));
+        assert!(!options.is_signup_element(&divs[2]));
+    }
+
+    #[test]
+    fn test_is_tracking_image() {
+        let dom = parse(
+            r#"<img src="a.png" width="1" height="1">
+            <img src="/img/spacer.gif">
+            <img src="https://pixel.wp.com/g.gif?v=1">
+            <img src="https://www.facebook.com/tr?id=1">
+            <img src="/img/pixel-art.png" width="640px">
+            <img src="https://www.facebook.com/photo.png">"#,
+        );
+        let images = find_by_tag(&dom.document, "img");
+        let url = Url::parse("https://example.com").unwrap();
+        let options = CleanOptions::default();
+
+        assert!(options.is_tracking_image(&images[0], &url));
+        assert!(options.is_tracking_image(&images[1], &url));
+        assert!(options.is_tracking_image(&images[2], &url));
+        assert!(options.is_tracking_image(&images[3], &url));
+        assert!(!options.is_tracking_image(&images[4], &url));
+        assert!(!options.is_tracking_image(&images[5], &url));
+    }
+
+    #[test]
+    fn test_fix_data_uri() {
+        let dom = parse(
+            r#"<img src="data:image/gif;base64,R0lGODlhAQABAAAAACw=" data-src="/a.png">
+            <img src="data:image/gif;base64,R0lGODlhAQABAAAAACw=">
+            <img src="/b.png">"#,
+        );
+        let images = find_by_tag(&dom.document, "img");
+        let options = CleanOptions {
+            data_uri_policy: DataUriPolicy::MaxBytes(32),
+            ..Default::default()
+        };
+
+        assert!(options.fix_data_uri(&images[0]));
+        assert_eq!(get_attr(&images[0], "src"), Some("/a.png".to_owned()));
+        assert!(!options.fix_data_uri(&images[1]));
+        assert!(options.fix_data_uri(&images[2]));
+
+        let options = CleanOptions::default();
+        assert!(options.fix_data_uri(&images[1]));
+    }
+
+    #[test]
+    fn test_tidy() {
+        let mut dom = parse(
+            r#"<article><div><div><p>Text</p></div></div><p> &nbsp;<br></p>
+            <div id="note"><p>Note</p></div><section><p>A</p><p>B</p></section></article>"#,
+        );
+        let article = find_by_tag(&dom.document, "article")[0].clone();
+
+        CleanOptions::default().tidy(&mut dom, &article);
+
+        let mut bytes = vec![];
+        html5ever::serialize(
+            &mut bytes,
+            &crate::dom::SerializableHandle::from(article),
+            Default::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "<p>Text</p>\n            \
+            <div id=\"note\"><p>Note</p></div><section><p>A</p><p>B</p></section>"
+        );
+    }
+
+    #[test]
+    fn test_tidy_inline_children() {
+        let mut dom = parse(
+            r#"<article><div><span>A</span></div><div><a href="/b">B</a></div><section><img src="/c.png"></section></article>"#,
+        );
+        let article = find_by_tag(&dom.document, "article")[0].clone();
+
+        CleanOptions::default().tidy(&mut dom, &article);
+
+        let mut bytes = vec![];
+        html5ever::serialize(
+            &mut bytes,
+            &crate::dom::SerializableHandle::from(article),
+            Default::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "<div><span>A</span></div><div><a href=\"/b\">B</a></div>\
+            <section><img src=\"/c.png\"></section>"
+        );
+    }
+
+    #[test]
+    fn test_clean_attrs() {
+        let clean_attrs = |preserved_attributes: &[&str]| {
+            let dom = parse(r#"<p id="a" class="b" style="c" title="d">Text</p>"#);
+            let p = find_by_tag(&dom.document, "p")[0].clone();
+            let NodeData::Element { ref attrs, .. } = p.data else {
+                unreachable!()
+            };
+            let options = CleanOptions {
+                preserved_attributes,
+                ..Default::default()
+            };
+
+            options.clean_attrs(&mut attrs.borrow_mut(), false);
+
+            let attrs = attrs.borrow();
+            attrs
+                .iter()
+                .map(|attr| attr.name.local.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(clean_attrs(&[]), ["title"]);
+        assert_eq!(clean_attrs(&["class"]), ["class", "title"]);
+        assert_eq!(clean_attrs(&["id", "style"]), ["id", "style", "title"]);
+    }
+
+    #[test]
+    fn test_strip_tracking_params() {
+        let options = CleanOptions {
+            strip_tracking_params: true,
+            ..Default::default()
+        };
+
+        let mut url = Url::parse("https://example.com/a?id=1&utm_source=x&fbclid=y#top").unwrap();
+        assert!(options.strip_tracking_params(&mut url));
+        assert_eq!(url.as_str(), "https://example.com/a?id=1#top");
+
+        let mut url = Url::parse("https://example.com/a?utm_medium=x").unwrap();
+        assert!(options.strip_tracking_params(&mut url));
+        assert_eq!(url.as_str(), "https://example.com/a");
+
+        let mut url = Url::parse("https://example.com/a?id=1").unwrap();
+        assert!(!options.strip_tracking_params(&mut url));
+
+        let mut url = Url::parse("https://example.com/a?utm_medium=x").unwrap();
+        assert!(!CleanOptions::default().strip_tracking_params(&mut url));
+    }
+
+    #[test]
+    fn test_remove_related_sections() {
+        let mut dom = parse(
+            r#"<article>
+                <p>Text with <a href="https://example.com/a">a link</a>.</p>
+                <div><h3>Related</h3><ul>
+                    <li><a href="https://example.com/b">The first related article</a></li>
+                    <li><a href="https://example.com/c">The second related article</a></li>
+                </ul></div>
+                <h2>More from Example</h2>
+                <ul><li><a href="/d">Another article</a></li></ul>
+            </article>"#,
+        );
+        let url = Url::parse("https://example.com").unwrap();
+        let article = find_by_tag(&dom.document, "article")[0].clone();
+
+        CleanOptions::default().remove_related_sections(&mut dom, &article, &url);
+
+        let children = element_children(&article);
+        assert_eq!(children.len(), 1);
+        assert!(is_tag(&children[0], "p"));
+        assert!(RELATED_SECTION.is_match("Read more"));
+    }
+}
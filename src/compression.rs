@@ -0,0 +1,127 @@
+//! Decompress gzip, zlib or zstd compressed input, which is detected by its
+//! magic bytes.
+//!
+//! The decoders are only available with the `gzip` and `zstd` features.
+//! Without them, compressed input is rejected as [`ReadabilityError::NotHtml`].
+
+use crate::error::ReadabilityError;
+use std::borrow::Cow;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use std::io::Read;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The compression format of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    Gzip,
+    Zlib,
+    Zstd,
+}
+
+impl Compression {
+    fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&GZIP_MAGIC) {
+            Some(Self::Gzip)
+        } else if bytes.starts_with(&ZSTD_MAGIC) {
+            Some(Self::Zstd)
+        } else if is_zlib(bytes) {
+            Some(Self::Zlib)
+        } else {
+            None
+        }
+    }
+}
+
+/// Check the zlib header, i.e. the deflate method with the default window size
+/// and the header checksum, since other methods are rare and the first bytes
+/// could be mistaken for text.
+fn is_zlib(bytes: &[u8]) -> bool {
+    match bytes {
+        [cmf @ 0x78, flg, ..] => (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0,
+        _ => false,
+    }
+}
+
+/// Decompress the input if it is compressed, or borrow it otherwise.
+pub(crate) fn decompress(bytes: &[u8]) -> Result<Cow<'_, [u8]>, ReadabilityError> {
+    let Some(compression) = Compression::detect(bytes) else {
+        return Ok(Cow::Borrowed(bytes));
+    };
+
+    match compression {
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => {
+            let mut decompressed = vec![];
+            // Concatenated gzip members are decompressed as a single stream.
+            flate2::bufread::MultiGzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+            Ok(Cow::Owned(decompressed))
+        }
+        #[cfg(feature = "gzip")]
+        Compression::Zlib => {
+            let mut decompressed = vec![];
+            flate2::bufread::ZlibDecoder::new(bytes).read_to_end(&mut decompressed)?;
+            Ok(Cow::Owned(decompressed))
+        }
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => {
+            let mut decompressed = vec![];
+            zstd::stream::read::Decoder::new(bytes)?.read_to_end(&mut decompressed)?;
+            Ok(Cow::Owned(decompressed))
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(ReadabilityError::NotHtml),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_compression() {
+        assert_eq!(Compression::detect(b"<html></html>"), None);
+        assert_eq!(Compression::detect(b"x"), None);
+        assert_eq!(
+            Compression::detect(&[0x1f, 0x8b, 8]),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(Compression::detect(&[0x78, 0x9c]), Some(Compression::Zlib));
+        assert_eq!(
+            Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd, 0]),
+            Some(Compression::Zstd)
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_decompress_gzip() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(b"<p>Hello</p>").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(decompress(&compressed).unwrap().as_ref(), b"<p>Hello</p>");
+        assert!(decompress(&compressed[..compressed.len() / 2]).is_err());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_decompress_zstd() {
+        let compressed = zstd::encode_all(&b"<p>Hello</p>"[..], 0).unwrap();
+
+        assert_eq!(decompress(&compressed).unwrap().as_ref(), b"<p>Hello</p>");
+    }
+
+    #[cfg(not(any(feature = "gzip", feature = "zstd")))]
+    #[test]
+    fn test_decompress_disabled() {
+        assert!(matches!(
+            decompress(&[0x1f, 0x8b, 8, 0]),
+            Err(ReadabilityError::NotHtml)
+        ));
+    }
+}
@@ -0,0 +1,508 @@
+//! Load [`ExtractOptions`] from a TOML or JSON config file, see
+//! [`ExtractConfig`].
+
+use crate::{
+    cleaner::{CleanOptions, DataUriPolicy},
+    error::ReadabilityError,
+    extractor::{ExtractOptions, FallbackPolicy},
+    preset::Preset,
+    scorer::{ClassWeightMode, EarlyTermination},
+};
+use regex::Regex;
+use serde::Deserialize;
+use std::{cell::OnceCell, collections::BTreeMap, fs, path::Path};
+
+/// The format of a config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Guess the format from the file extension, i.e. `.json` for JSON and
+    /// TOML otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Toml,
+        }
+    }
+}
+
+/// The options of a config file. Missing options fall back to the preset, or
+/// to the default options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    preset: Option<Preset>,
+    strict: Option<bool>,
+    fallback_policy: Option<FallbackPolicy>,
+    min_content_length: Option<usize>,
+    detect_app_shell: Option<bool>,
+    min_candidate_length: Option<usize>,
+    max_candidate_parents: Option<usize>,
+    punctuations: Option<String>,
+    unlikely_candidates: Option<String>,
+    likely_candidates: Option<String>,
+    positive_candidates: Option<String>,
+    positive_candidate_weight: Option<f32>,
+    negative_candidates: Option<String>,
+    negative_candidate_weight: Option<f32>,
+    class_weight_mode: Option<ClassWeightMode>,
+    class_weight_cap: Option<f32>,
+    byline_candidates: Option<String>,
+    block_child_tags: Option<Vec<String>>,
+    tag_scores: Option<BTreeMap<String, f32>>,
+    hash_link_coefficient: Option<f32>,
+    inline_iframe_srcdoc: Option<bool>,
+    hoist_shadow_roots: Option<bool>,
+    remove_overlays: Option<bool>,
+    overlay_elements: Option<String>,
+    remove_ad_slots: Option<bool>,
+    convert_divs_to_paragraphs: Option<bool>,
+    custom_elements_as_divs: Option<bool>,
+    legacy_candidate_matching: Option<bool>,
+    legacy_line_breaks: Option<bool>,
+    parallel: Option<bool>,
+    max_candidates: Option<usize>,
+    early_termination: Option<EarlyTermination>,
+    clean: CleanConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CleanConfig {
+    remove_share_elements: Option<bool>,
+    share_elements: Option<String>,
+    share_links: Option<String>,
+    share_element_threshold: Option<usize>,
+    remove_related_sections: Option<bool>,
+    related_sections: Option<String>,
+    related_link_density: Option<f32>,
+    remove_signup_elements: Option<bool>,
+    signup_elements: Option<String>,
+    signup_element_threshold: Option<usize>,
+    strip_tracking_params: Option<bool>,
+    tracking_params: Option<Vec<String>>,
+    keep_fragment_links: Option<bool>,
+    preserve_footnotes: Option<bool>,
+    remove_tracking_images: Option<bool>,
+    min_image_size: Option<u32>,
+    tracking_images: Option<String>,
+    tracking_hosts: Option<Vec<String>>,
+    data_uri_policy: Option<DataUriPolicy>,
+    remove_svg_and_math: Option<bool>,
+    remove_empty_paragraphs: Option<bool>,
+    unwrap_wrappers: Option<bool>,
+    preserved_attributes: Option<Vec<String>>,
+    upgrade_insecure: Option<bool>,
+}
+
+/// The regexes of a config, which are compiled on load.
+#[derive(Debug, Default)]
+struct ConfigRegexes {
+    punctuations: Option<Regex>,
+    unlikely_candidates: Option<Regex>,
+    likely_candidates: Option<Regex>,
+    positive_candidates: Option<Regex>,
+    negative_candidates: Option<Regex>,
+    byline_candidates: Option<Regex>,
+    overlay_elements: Option<Regex>,
+    share_elements: Option<Regex>,
+    share_links: Option<Regex>,
+    related_sections: Option<Regex>,
+    signup_elements: Option<Regex>,
+    tracking_images: Option<Regex>,
+}
+
+/// The lists of a config, which borrow the strings of the config.
+#[derive(Debug, Default)]
+struct ConfigLists<'a> {
+    block_child_tags: Option<Vec<&'a str>>,
+    tag_scores: Option<Vec<(&'a str, f32)>>,
+    tracking_params: Option<Vec<&'a str>>,
+    tracking_hosts: Option<Vec<&'a str>>,
+    preserved_attributes: Option<Vec<&'a str>>,
+}
+
+/// A config loaded from a TOML or JSON file, which owns the compiled regexes
+/// and the lists of the options.
+///
+/// The config is loaded once, e.g. at startup, and the options borrow from
+/// it. Reloading the config drops the regexes and lists of the previous one.
+///
+/// ```
+/// use readability::{ConfigFormat, ExtractConfig};
+///
+/// let config = ExtractConfig::from_str("min_candidate_length = 30", ConfigFormat::Toml).unwrap();
+/// let options = config.options();
+///
+/// assert_eq!(options.scorer_options.min_candidate_length, 30);
+/// ```
+#[derive(Debug)]
+pub struct ExtractConfig<'a> {
+    config: Config,
+    regexes: ConfigRegexes,
+    lists: OnceCell<ConfigLists<'a>>,
+}
+
+impl<'a> ExtractConfig<'a> {
+    /// Load a config file, see [`ExtractConfig::from_str`].
+    ///
+    /// The format is guessed from the file extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ReadabilityError> {
+        let path = path.as_ref();
+        let input = fs::read_to_string(path)?;
+
+        Self::from_str(&input, ConfigFormat::from_path(path))
+    }
+
+    /// Load a config string, e.g.
+    ///
+    /// ```toml
+    /// preset = "news"
+    /// min_candidate_length = 30
+    /// unlikely_candidates = "combx|community|disqus|sidebar"
+    ///
+    /// [clean]
+    /// strip_tracking_params = true
+    /// tracking_params = ["utm_*", "ref"]
+    /// ```
+    ///
+    /// Regex patterns are compiled on load.
+    pub fn from_str(input: &str, format: ConfigFormat) -> Result<Self, ReadabilityError> {
+        let config: Config = match format {
+            ConfigFormat::Toml => toml::from_str(input)
+                .map_err(|err| ReadabilityError::ParseConfig(err.to_string()))?,
+            ConfigFormat::Json => serde_json::from_str(input)
+                .map_err(|err| ReadabilityError::ParseConfig(err.to_string()))?,
+        };
+        let regexes = ConfigRegexes {
+            punctuations: regex(&config.punctuations)?,
+            unlikely_candidates: regex(&config.unlikely_candidates)?,
+            likely_candidates: regex(&config.likely_candidates)?,
+            positive_candidates: regex(&config.positive_candidates)?,
+            negative_candidates: regex(&config.negative_candidates)?,
+            byline_candidates: regex(&config.byline_candidates)?,
+            overlay_elements: regex(&config.overlay_elements)?,
+            share_elements: regex(&config.clean.share_elements)?,
+            share_links: regex(&config.clean.share_links)?,
+            related_sections: regex(&config.clean.related_sections)?,
+            signup_elements: regex(&config.clean.signup_elements)?,
+            tracking_images: regex(&config.clean.tracking_images)?,
+        };
+
+        Ok(Self {
+            config,
+            regexes,
+            lists: OnceCell::new(),
+        })
+    }
+
+    /// The options of the config, which borrow its regexes and lists. Missing
+    /// options fall back to the preset, or to the default options.
+    pub fn options(&'a self) -> ExtractOptions<'a> {
+        let config = &self.config;
+        let regexes = &self.regexes;
+        let mut options = match config.preset {
+            Some(preset) => ExtractOptions::preset(preset),
+            None => ExtractOptions::default(),
+        };
+        let lists = self.lists.get_or_init(|| ConfigLists {
+            block_child_tags: list(&config.block_child_tags),
+            tag_scores: tag_scores(options.scorer_options.tag_scores, &config.tag_scores),
+            tracking_params: list(&config.clean.tracking_params),
+            tracking_hosts: list(&config.clean.tracking_hosts),
+            preserved_attributes: list(&config.clean.preserved_attributes),
+        });
+        let scorer_options = &mut options.scorer_options;
+
+        set(&mut options.parse_options.strict, config.strict);
+        set(&mut options.fallback_policy, config.fallback_policy);
+        set(&mut options.min_content_length, config.min_content_length);
+        set(&mut options.detect_app_shell, config.detect_app_shell);
+        set(
+            &mut scorer_options.min_candidate_length,
+            config.min_candidate_length,
+        );
+        set(
+            &mut scorer_options.max_candidate_parents,
+            config.max_candidate_parents,
+        );
+        set(
+            &mut scorer_options.punctuations,
+            regexes.punctuations.as_ref(),
+        );
+        set(
+            &mut scorer_options.unlikely_candidates,
+            regexes.unlikely_candidates.as_ref(),
+        );
+        set(
+            &mut scorer_options.likely_candidates,
+            regexes.likely_candidates.as_ref(),
+        );
+        set(
+            &mut scorer_options.positive_candidates,
+            regexes.positive_candidates.as_ref(),
+        );
+        set(
+            &mut scorer_options.positive_candidate_weight,
+            config.positive_candidate_weight,
+        );
+        set(
+            &mut scorer_options.negative_candidates,
+            regexes.negative_candidates.as_ref(),
+        );
+        set(
+            &mut scorer_options.negative_candidate_weight,
+            config.negative_candidate_weight,
+        );
+        set(
+            &mut scorer_options.class_weight_mode,
+            config.class_weight_mode,
+        );
+        set(
+            &mut scorer_options.class_weight_cap,
+            config.class_weight_cap.map(Some),
+        );
+        set(
+            &mut scorer_options.byline_candidates,
+            regexes.byline_candidates.as_ref(),
+        );
+        set(
+            &mut scorer_options.block_child_tags,
+            lists.block_child_tags.as_deref(),
+        );
+        set(&mut scorer_options.tag_scores, lists.tag_scores.as_deref());
+        set(
+            &mut scorer_options.hash_link_coefficient,
+            config.hash_link_coefficient,
+        );
+        set(
+            &mut scorer_options.inline_iframe_srcdoc,
+            config.inline_iframe_srcdoc,
+        );
+        set(
+            &mut scorer_options.hoist_shadow_roots,
+            config.hoist_shadow_roots,
+        );
+        set(&mut scorer_options.remove_overlays, config.remove_overlays);
+        set(
+            &mut scorer_options.overlay_elements,
+            regexes.overlay_elements.as_ref(),
+        );
+        set(&mut scorer_options.remove_ad_slots, config.remove_ad_slots);
+        set(
+            &mut scorer_options.convert_divs_to_paragraphs,
+            config.convert_divs_to_paragraphs,
+        );
+        set(
+            &mut scorer_options.custom_elements_as_divs,
+            config.custom_elements_as_divs,
+        );
+        set(
+            &mut scorer_options.legacy_candidate_matching,
+            config.legacy_candidate_matching,
+        );
+        set(
+            &mut scorer_options.legacy_line_breaks,
+            config.legacy_line_breaks,
+        );
+        set(&mut scorer_options.parallel, config.parallel);
+        set(
+            &mut scorer_options.max_candidates,
+            config.max_candidates.map(Some),
+        );
+        set(
+            &mut scorer_options.early_termination,
+            config.early_termination.map(Some),
+        );
+        config
+            .clean
+            .apply(&mut scorer_options.clean_options, regexes, lists);
+
+        options
+    }
+}
+
+impl CleanConfig {
+    fn apply<'a>(
+        &self,
+        options: &mut CleanOptions<'a>,
+        regexes: &'a ConfigRegexes,
+        lists: &'a ConfigLists<'a>,
+    ) {
+        set(
+            &mut options.remove_share_elements,
+            self.remove_share_elements,
+        );
+        set(&mut options.share_elements, regexes.share_elements.as_ref());
+        set(&mut options.share_links, regexes.share_links.as_ref());
+        set(
+            &mut options.share_element_threshold,
+            self.share_element_threshold,
+        );
+        set(
+            &mut options.remove_related_sections,
+            self.remove_related_sections,
+        );
+        set(
+            &mut options.related_sections,
+            regexes.related_sections.as_ref(),
+        );
+        set(&mut options.related_link_density, self.related_link_density);
+        set(
+            &mut options.remove_signup_elements,
+            self.remove_signup_elements,
+        );
+        set(
+            &mut options.signup_elements,
+            regexes.signup_elements.as_ref(),
+        );
+        set(
+            &mut options.signup_element_threshold,
+            self.signup_element_threshold,
+        );
+        set(
+            &mut options.strip_tracking_params,
+            self.strip_tracking_params,
+        );
+        set(
+            &mut options.tracking_params,
+            lists.tracking_params.as_deref(),
+        );
+        set(&mut options.keep_fragment_links, self.keep_fragment_links);
+        set(&mut options.preserve_footnotes, self.preserve_footnotes);
+        set(
+            &mut options.remove_tracking_images,
+            self.remove_tracking_images,
+        );
+        set(&mut options.min_image_size, self.min_image_size);
+        set(
+            &mut options.tracking_images,
+            regexes.tracking_images.as_ref(),
+        );
+        set(&mut options.tracking_hosts, lists.tracking_hosts.as_deref());
+        set(&mut options.data_uri_policy, self.data_uri_policy);
+        set(&mut options.remove_svg_and_math, self.remove_svg_and_math);
+        set(
+            &mut options.remove_empty_paragraphs,
+            self.remove_empty_paragraphs,
+        );
+        set(&mut options.unwrap_wrappers, self.unwrap_wrappers);
+        set(
+            &mut options.preserved_attributes,
+            lists.preserved_attributes.as_deref(),
+        );
+        set(&mut options.upgrade_insecure, self.upgrade_insecure);
+    }
+}
+
+fn set<T>(option: &mut T, value: Option<T>) {
+    if let Some(value) = value {
+        *option = value;
+    }
+}
+
+/// Compile a regex.
+fn regex(pattern: &Option<String>) -> Result<Option<Regex>, ReadabilityError> {
+    pattern
+        .as_deref()
+        .map(|pattern| {
+            Regex::new(pattern).map_err(|err| ReadabilityError::ParseConfig(err.to_string()))
+        })
+        .transpose()
+}
+
+/// Borrow the strings of a list.
+fn list(values: &Option<Vec<String>>) -> Option<Vec<&str>> {
+    values
+        .as_ref()
+        .map(|values| values.iter().map(String::as_str).collect())
+}
+
+/// Merge the tag scores into the current table.
+fn tag_scores<'a>(
+    current: &[(&'a str, f32)],
+    values: &'a Option<BTreeMap<String, f32>>,
+) -> Option<Vec<(&'a str, f32)>> {
+    values.as_ref().map(|values| {
+        let mut tag_scores = current
+            .iter()
+            .filter(|(name, _)| !values.contains_key(*name))
+            .copied()
+            .collect::<Vec<_>>();
+        tag_scores.extend(values.iter().map(|(name, &score)| (name.as_str(), score)));
+        tag_scores
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        let toml = r#"
+            preset = "forum"
+            min_candidate_length = 30
+            unlikely_candidates = "sidebar|menu"
+            class_weight_mode = "per_element"
+            tag_scores = { section = 5.0, div = 3.0 }
+
+            [clean]
+            strip_tracking_params = true
+            tracking_params = ["ref"]
+            data_uri_policy = { max_bytes = 1024 }
+        "#;
+
+        let config = ExtractConfig::from_str(toml, ConfigFormat::Toml).unwrap();
+        let options = config.options();
+        let scorer_options = &options.scorer_options;
+        assert_eq!(scorer_options.min_candidate_length, 30);
+        assert_eq!(scorer_options.unlikely_candidates.as_str(), "sidebar|menu");
+        assert_eq!(
+            scorer_options.class_weight_mode,
+            ClassWeightMode::PerElement
+        );
+        let tag_score = |tag_name| {
+            scorer_options
+                .tag_scores
+                .iter()
+                .find(|(name, _)| *name == tag_name)
+                .map(|(_, score)| *score)
+        };
+        assert_eq!(tag_score("section"), Some(5.0));
+        assert_eq!(tag_score("div"), Some(3.0));
+        assert_eq!(tag_score("article"), Some(10.0));
+        assert!(!scorer_options.clean_options.remove_related_sections);
+        assert!(scorer_options.clean_options.strip_tracking_params);
+        assert_eq!(scorer_options.clean_options.tracking_params, ["ref"]);
+        assert_eq!(
+            scorer_options.clean_options.data_uri_policy,
+            DataUriPolicy::MaxBytes(1024)
+        );
+
+        let json = r#"{ "strict": true, "clean": { "preserved_attributes": ["id"] } }"#;
+        let config = ExtractConfig::from_str(json, ConfigFormat::Json).unwrap();
+        let options = config.options();
+        assert!(options.parse_options.strict);
+        assert_eq!(
+            options.scorer_options.clean_options.preserved_attributes,
+            ["id"]
+        );
+        // The options can be handed out again without compiling the config again.
+        let options = config.options();
+        assert!(options.parse_options.strict);
+        assert_eq!(
+            options.scorer_options.clean_options.preserved_attributes,
+            ["id"]
+        );
+
+        let result = ExtractConfig::from_str("unknown = 1", ConfigFormat::Toml);
+        assert!(matches!(result, Err(ReadabilityError::ParseConfig(_))));
+        let result = ExtractConfig::from_str(r#"punctuations = "(""#, ConfigFormat::Toml);
+        assert!(matches!(result, Err(ReadabilityError::ParseConfig(_))));
+    }
+}
@@ -0,0 +1,306 @@
+//! Run the extraction over a corpus of fixtures, and compare the results with
+//! the expected output or a previous report to detect scoring regressions.
+
+use crate::{
+    error::ReadabilityError,
+    extractor::{extract_from_bytes, ExtractOptions, Readable},
+};
+use std::{collections::HashMap, fmt, fs, path::Path};
+use url::Url;
+
+/// The url of fixtures without a `url.txt`.
+const DEFAULT_URL: &str = "https://example.com";
+
+/// The report of a page of the corpus.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PageReport {
+    /// The name of the fixture directory.
+    pub name: String,
+    /// The extracted title.
+    pub title: Option<String>,
+    /// Whether the title equals the expected title, if any.
+    pub title_matches: Option<bool>,
+    /// The number of characters of the extracted text.
+    pub text_length: usize,
+    /// The similarity of the extracted text to the expected text between `0.0`
+    /// and `1.0`, see [`text_similarity`].
+    pub similarity: Option<f32>,
+    /// The confidence of the extraction.
+    pub confidence: f32,
+    /// The error message if the extraction failed.
+    pub error: Option<String>,
+}
+
+/// The report of a corpus, ordered by page name.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CorpusReport {
+    pub pages: Vec<PageReport>,
+}
+
+/// A page whose report changed compared to a baseline report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageDiff<'a> {
+    pub name: &'a str,
+    /// The report of the baseline, or `None` if the page is new.
+    pub baseline: Option<&'a PageReport>,
+    pub current: &'a PageReport,
+}
+
+/// Extract the content of each fixture in a directory, and compare it with
+/// the expected output.
+///
+/// A fixture is a subdirectory containing
+///
+/// - `input.html`, the HTML of the page,
+/// - `url.txt`, the url of the page, which defaults to `https://example.com`,
+/// - `expected.txt`, the expected text, which is optional, and
+/// - `expected_title.txt`, the expected title, which is optional.
+///
+/// Other files and directories without `input.html` are skipped. Failed
+/// extractions are reported instead of aborting the run.
+pub fn run_corpus(
+    dir: impl AsRef<Path>,
+    options: &ExtractOptions,
+) -> Result<CorpusReport, ReadabilityError> {
+    let pages = read_fixtures(dir)?
+        .into_iter()
+        .map(|fixture| {
+            let result = fixture.extract(options);
+
+            PageReport::new(
+                fixture.name,
+                result,
+                fixture.expected_text.as_deref(),
+                fixture.expected_title.as_deref(),
+            )
+        })
+        .collect();
+
+    Ok(CorpusReport { pages })
+}
+
+/// A fixture of the corpus, see [`run_corpus`].
+pub(crate) struct Fixture {
+    pub name: String,
+    pub input: Vec<u8>,
+    pub url: Option<String>,
+    pub expected_text: Option<String>,
+    pub expected_title: Option<String>,
+}
+
+impl Fixture {
+    pub(crate) fn extract(&self, options: &ExtractOptions) -> Result<Readable, ReadabilityError> {
+        let url = Url::parse(self.url.as_deref().map_or(DEFAULT_URL, str::trim))?;
+
+        extract_from_bytes(&self.input, &url, options.clone())
+    }
+}
+
+/// Read the fixtures of a directory, ordered by name.
+pub(crate) fn read_fixtures(dir: impl AsRef<Path>) -> Result<Vec<Fixture>, ReadabilityError> {
+    let mut fixtures = vec![];
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let input_path = path.join("input.html");
+
+        if !input_path.is_file() {
+            continue;
+        }
+
+        fixtures.push(Fixture {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            input: fs::read(input_path)?,
+            url: read_optional(&path.join("url.txt"))?,
+            expected_text: read_optional(&path.join("expected.txt"))?,
+            expected_title: read_optional(&path.join("expected_title.txt"))?,
+        });
+    }
+
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(fixtures)
+}
+
+impl PageReport {
+    /// Report the result of an extraction, compared with the expected text and
+    /// title.
+    pub(crate) fn new(
+        name: String,
+        result: Result<Readable, ReadabilityError>,
+        expected_text: Option<&str>,
+        expected_title: Option<&str>,
+    ) -> Self {
+        match result {
+            Ok(readable) => Self {
+                name,
+                title_matches: expected_title
+                    .map(|expected_title| expected_title.trim() == readable.title.trim()),
+                text_length: readable.text.chars().count(),
+                similarity: expected_text
+                    .map(|expected_text| text_similarity(&readable.text, expected_text)),
+                title: Some(readable.title),
+                confidence: readable.confidence,
+                error: None,
+            },
+            Err(err) => Self::failed(name, err.to_string()),
+        }
+    }
+
+    /// Report a failed extraction.
+    pub(crate) fn failed(name: String, error: String) -> Self {
+        Self {
+            name,
+            title: None,
+            title_matches: None,
+            text_length: 0,
+            similarity: None,
+            confidence: 0.0,
+            error: Some(error),
+        }
+    }
+}
+
+pub(crate) fn read_optional(path: &Path) -> Result<Option<String>, ReadabilityError> {
+    if path.is_file() {
+        Ok(Some(fs::read_to_string(path)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Measure the similarity of two texts as the F1 score of their words, i.e.
+/// `1.0` if they contain the same words, regardless of whitespace and order.
+pub fn text_similarity(text: &str, expected: &str) -> f32 {
+    let mut words = HashMap::new();
+    for word in expected.split_whitespace() {
+        *words.entry(word).or_insert(0) += 1;
+    }
+    let expected_count = words.values().sum::<usize>();
+
+    let mut count = 0;
+    let mut common = 0;
+    for word in text.split_whitespace() {
+        count += 1;
+        if let Some(remaining) = words.get_mut(word).filter(|remaining| **remaining > 0) {
+            *remaining -= 1;
+            common += 1;
+        }
+    }
+
+    match (count, expected_count) {
+        (0, 0) => 1.0,
+        _ if common == 0 => 0.0,
+        _ => {
+            let precision = common as f32 / count as f32;
+            let recall = common as f32 / expected_count as f32;
+            2.0 * precision * recall / (precision + recall)
+        }
+    }
+}
+
+impl CorpusReport {
+    /// The mean similarity of the pages with an expected text.
+    pub fn mean_similarity(&self) -> Option<f32> {
+        let similarities = self
+            .pages
+            .iter()
+            .filter_map(|page| page.similarity)
+            .collect::<Vec<_>>();
+
+        (!similarities.is_empty())
+            .then(|| similarities.iter().sum::<f32>() / similarities.len() as f32)
+    }
+
+    /// Find the pages which regressed compared to a baseline report, i.e.
+    /// whose similarity dropped by more than the tolerance, whose title
+    /// stopped matching, or whose extraction started to fail.
+    pub fn regressions<'a>(
+        &'a self,
+        baseline: &'a CorpusReport,
+        tolerance: f32,
+    ) -> Vec<PageDiff<'a>> {
+        self.diff(baseline)
+            .into_iter()
+            .filter(|diff| match diff.baseline {
+                Some(baseline) => {
+                    let similarity_dropped = match (baseline.similarity, diff.current.similarity) {
+                        (Some(baseline), Some(current)) => baseline - current > tolerance,
+                        (Some(_), None) => true,
+                        _ => false,
+                    };
+
+                    similarity_dropped
+                        || (baseline.title_matches == Some(true)
+                            && diff.current.title_matches != Some(true))
+                        || (baseline.error.is_none() && diff.current.error.is_some())
+                }
+                None => false,
+            })
+            .collect()
+    }
+
+    /// Find the pages whose report differs from a baseline report, including
+    /// new pages.
+    pub fn diff<'a>(&'a self, baseline: &'a CorpusReport) -> Vec<PageDiff<'a>> {
+        self.pages
+            .iter()
+            .filter_map(|current| {
+                let baseline = baseline.pages.iter().find(|page| page.name == current.name);
+
+                (baseline != Some(current)).then_some(PageDiff {
+                    name: &current.name,
+                    baseline,
+                    current,
+                })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for CorpusReport {
+    /// Format the report as tab-separated values with a header line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "name\ttitle_matches\ttext_length\tsimilarity\tconfidence\terror"
+        )?;
+
+        for page in self.pages.iter() {
+            writeln!(
+                f,
+                "{}\t{}\t{}\t{}\t{:.3}\t{}",
+                page.name,
+                page.title_matches
+                    .map(|matches| matches.to_string())
+                    .unwrap_or_default(),
+                page.text_length,
+                page.similarity
+                    .map(|similarity| format!("{similarity:.3}"))
+                    .unwrap_or_default(),
+                page.confidence,
+                page.error.as_deref().unwrap_or_default(),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_similarity() {
+        assert_eq!(text_similarity("a b  c", "c b\na"), 1.0);
+        assert_eq!(text_similarity("", ""), 1.0);
+        assert_eq!(text_similarity("a b", "c d"), 0.0);
+        assert_eq!(text_similarity("a b", "a b c d"), 2.0 / 3.0);
+    }
+}
@@ -0,0 +1,260 @@
+//! Compare the content of a candidate before and after cleaning, e.g. to show
+//! what the cleaner removed in a debugging UI.
+
+use crate::dom::{self, Handle, Node, NodeData};
+use std::collections::{HashMap, HashSet};
+
+/// Why the cleaner removed a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum CleanReason {
+    /// A share or social widget, see [`CleanOptions::share_elements`].
+    ///
+    /// [`CleanOptions::share_elements`]: crate::CleanOptions::share_elements
+    ShareElement,
+    /// A newsletter or signup form, see [`CleanOptions::signup_elements`].
+    ///
+    /// [`CleanOptions::signup_elements`]: crate::CleanOptions::signup_elements
+    SignupElement,
+    /// A tracking pixel or spacer image.
+    TrackingImage,
+    /// An element which is never content, e.g. `script`, `header`, or `aside`.
+    UnlikelyTag,
+    /// A `div`, `form`, `table`, or list with a negative score, a high link
+    /// density, or too few paragraphs.
+    LowScore,
+    /// An image without a valid `src`.
+    InvalidImage,
+    /// A link without `href`.
+    InvalidLink,
+    /// An SVG or MathML element, see
+    /// [`CleanOptions::remove_svg_and_math`].
+    ///
+    /// [`CleanOptions::remove_svg_and_math`]: crate::CleanOptions::remove_svg_and_math
+    SvgOrMath,
+    /// A comment.
+    Comment,
+    /// An element without content after cleaning, or whitespace.
+    Empty,
+    /// An element removed by a predicate, see
+    /// [`CleanOptions::clean_predicates`].
+    ///
+    /// [`CleanOptions::clean_predicates`]: crate::CleanOptions::clean_predicates
+    Predicate,
+    /// A node removed after cleaning, e.g. a section of related articles, an
+    /// empty paragraph, or a redundant wrapper.
+    Postprocess,
+}
+
+/// The change of a node of the candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum NodeChange {
+    /// The node is kept unchanged.
+    Kept,
+    /// The node is kept, but its attributes or its text changed, e.g. the
+    /// `class` was removed or the `src` was resolved.
+    Modified,
+    /// The node is removed, including its descendants unless they are
+    /// reported as kept.
+    Removed(CleanReason),
+    /// The node was added by the cleaner, e.g. a paragraph converted from a
+    /// `div`, or a footnotes section.
+    Added,
+}
+
+/// A node of a candidate, and how it changed by cleaning.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NodeDiff {
+    /// The id of the node in the scored document, see
+    /// [`CandidateView::id`](crate::CandidateView::id), or `None` if the node
+    /// was added.
+    pub id: Option<String>,
+    /// The lowercase tag name of an element, or `None` for text and comments.
+    pub tag: Option<String>,
+    /// The text of the node before cleaning, or after cleaning if it was
+    /// added.
+    pub text: String,
+    pub change: NodeChange,
+    /// The CSS selector of the element in the original document, see
+    /// [`ScoredDocument::locator`](crate::ScoredDocument::locator), or `None`
+    /// for text, comments, added nodes, and elements created by
+    /// preprocessing, e.g. paragraphs converted from a `div`.
+    pub locator: Option<String>,
+}
+
+/// Compare the nodes of a candidate before and after cleaning in document
+/// order, followed by the added nodes.
+///
+/// The copy is the candidate before cleaning, with the same structure as the
+/// original, and the cleaned node is the copy after cleaning. Removed nodes
+/// are only reported if their parent is kept, and whitespace is skipped.
+pub(crate) fn diff_nodes(
+    id: &str,
+    original: &Handle,
+    copy: &[(Handle, Handle)],
+    cleaned: &Handle,
+    removed: &[(Handle, CleanReason)],
+) -> Vec<NodeDiff> {
+    let copies = copy
+        .iter()
+        .map(|(original, copy)| (key(original), copy))
+        .collect::<HashMap<_, _>>();
+    let copied = copy
+        .iter()
+        .map(|(_, copy)| key(copy))
+        .collect::<HashSet<_>>();
+    let reasons = removed
+        .iter()
+        .rev()
+        .map(|(node, reason)| (key(node), *reason))
+        .collect::<HashMap<_, _>>();
+
+    let mut kept = HashSet::new();
+    let mut added = vec![];
+    collect_cleaned(cleaned, &copied, &mut kept, &mut added);
+
+    let mut diffs = vec![];
+    diff_node(
+        id.to_owned(),
+        original,
+        true,
+        &copies,
+        &kept,
+        &reasons,
+        &mut diffs,
+    );
+
+    for node in added {
+        if !is_whitespace(&node) {
+            diffs.push(NodeDiff {
+                id: None,
+                tag: dom::tag_name(&node).map(str::to_owned),
+                text: text(&node),
+                change: NodeChange::Added,
+                locator: None,
+            });
+        }
+    }
+
+    diffs
+}
+
+fn diff_node(
+    id: String,
+    original: &Handle,
+    is_parent_kept: bool,
+    copies: &HashMap<*const Node, &Handle>,
+    kept: &HashSet<*const Node>,
+    reasons: &HashMap<*const Node, CleanReason>,
+    diffs: &mut Vec<NodeDiff>,
+) {
+    let Some(copy) = copies.get(&key(original)) else {
+        return;
+    };
+    let is_kept = kept.contains(&key(copy));
+
+    let change = if is_kept {
+        Some(if is_modified(original, copy) {
+            NodeChange::Modified
+        } else {
+            NodeChange::Kept
+        })
+    } else if is_parent_kept {
+        Some(NodeChange::Removed(
+            reasons
+                .get(&key(copy))
+                .copied()
+                .unwrap_or(CleanReason::Postprocess),
+        ))
+    } else {
+        None
+    };
+
+    if let Some(change) = change.filter(|_| !is_whitespace(original)) {
+        diffs.push(NodeDiff {
+            id: Some(id.clone()),
+            tag: dom::tag_name(original).map(str::to_owned),
+            text: text(original),
+            change,
+            locator: None,
+        });
+    }
+
+    for (index, child) in original.children.borrow().iter().enumerate() {
+        diff_node(
+            format!("{id}/{index}"),
+            child,
+            is_kept,
+            copies,
+            kept,
+            reasons,
+            diffs,
+        );
+    }
+}
+
+/// Collect the kept nodes of the cleaned content, and the topmost added nodes.
+fn collect_cleaned(
+    node: &Handle,
+    copied: &HashSet<*const Node>,
+    kept: &mut HashSet<*const Node>,
+    added: &mut Vec<Handle>,
+) {
+    if !copied.contains(&key(node)) {
+        added.push(node.clone());
+        return;
+    }
+
+    kept.insert(key(node));
+    for child in node.children.borrow().iter() {
+        collect_cleaned(child, copied, kept, added);
+    }
+}
+
+fn is_modified(original: &Handle, cleaned: &Handle) -> bool {
+    match (&original.data, &cleaned.data) {
+        (
+            NodeData::Element {
+                name: original_name,
+                attrs: original_attrs,
+                ..
+            },
+            NodeData::Element { name, attrs, .. },
+        ) => original_name != name || *original_attrs.borrow() != *attrs.borrow(),
+        (
+            NodeData::Text {
+                contents: original_contents,
+            },
+            NodeData::Text { contents },
+        ) => *original_contents.borrow() != *contents.borrow(),
+        _ => false,
+    }
+}
+
+fn is_whitespace(node: &Handle) -> bool {
+    match node.data {
+        NodeData::Text { ref contents } => contents.borrow().trim().is_empty(),
+        _ => false,
+    }
+}
+
+fn text(node: &Handle) -> String {
+    match node.data {
+        NodeData::Comment { ref contents } => contents.to_string(),
+        _ => dom::text_content(node).trim().to_owned(),
+    }
+}
+
+fn key(node: &Handle) -> *const Node {
+    &**node
+}
@@ -0,0 +1,202 @@
+//! Wrap the extracted content into a standalone reader-mode HTML document.
+
+use crate::{extractor::Readable, html::escape};
+
+/// A minimal stylesheet for the reader-mode document.
+pub const READER_STYLESHEET: &str = "\
+body { margin: 0 auto; max-width: 42em; padding: 1em; font-family: Georgia, serif; font-size: 1.125em; line-height: 1.6; color: #222; background: #fff; }
+header { margin-bottom: 2em; }
+h1 { line-height: 1.2; }
+.byline, time { color: #666; font-size: 0.9em; }
+img, video, figure { max-width: 100%; height: auto; }
+pre { overflow-x: auto; }
+blockquote { margin-left: 0; padding-left: 1em; border-left: 3px solid #ccc; }";
+
+/// A color theme of the reader-mode document, which overrides the colors of
+/// the stylesheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum ReaderTheme {
+    /// Dark text on a white background.
+    Light,
+    /// Light text on a dark background.
+    Dark,
+    /// Brown text on a beige background.
+    Sepia,
+}
+
+impl ReaderTheme {
+    /// The CSS of the theme.
+    pub fn css(self) -> &'static str {
+        match self {
+            Self::Light => {
+                "body { color: #222; background: #fff; }
+.byline, time { color: #666; }
+                 a { color: #0b57d0; }"
+            }
+            Self::Dark => {
+                "body { color: #ddd; background: #1c1c1e; }
+.byline, time { color: #999; }
+                 a { color: #8ab4f8; }
+blockquote { border-left-color: #555; }"
+            }
+            Self::Sepia => {
+                "body { color: #5b4636; background: #f4ecd8; }
+.byline, time { color: #8b735b; }
+                 a { color: #8b4513; }
+blockquote { border-left-color: #c8b79e; }"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DocumentOptions<'a> {
+    /// The `lang` attribute of the `html` element, e.g. `en`.
+    pub lang: Option<&'a str>,
+    /// The `dir` attribute of the `html` element, i.e. `ltr`, `rtl`, or `auto`.
+    pub dir: Option<&'a str>,
+    /// The author shown below the title. Defaults to [`Readable::byline`].
+    pub byline: Option<&'a str>,
+    /// The publication date shown below the title, preferably in ISO 8601 format.
+    pub published: Option<&'a str>,
+    /// The stylesheet embedded in the `head`, e.g. [`READER_STYLESHEET`].
+    pub stylesheet: Option<&'a str>,
+    /// The color theme, which is embedded after the stylesheet.
+    pub theme: Option<ReaderTheme>,
+    /// Scale the font size, e.g. `1.25` for 125%.
+    pub font_scale: Option<f32>,
+    /// Custom CSS embedded after the stylesheet and the theme, which
+    /// overrides both.
+    pub custom_css: Option<&'a str>,
+}
+
+impl Readable {
+    /// Wrap the content into a complete HTML document, which can be saved to
+    /// disk or served as a reader view.
+    pub fn to_document(&self, opts: &DocumentOptions) -> String {
+        let title = escape(&self.title);
+        let mut html = String::with_capacity(self.content.len() + 1024);
+
+        html.push_str("<!DOCTYPE html>\n<html");
+        if let Some(lang) = opts.lang {
+            html.push_str(&format!(r#" lang="{}""#, escape(lang)));
+        }
+        if let Some(dir) = opts.dir {
+            html.push_str(&format!(r#" dir="{}""#, escape(dir)));
+        }
+        html.push_str(">\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+        html.push_str(&format!("<title>{title}</title>\n"));
+        let styles = [
+            opts.stylesheet.map(str::to_owned),
+            opts.theme.map(|theme| theme.css().to_owned()),
+            opts.font_scale
+                .map(|scale| format!("html {{ font-size: {}%; }}", scale * 100.0)),
+            opts.custom_css.map(str::to_owned),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        if !styles.is_empty() {
+            // Prevent the CSS from closing the `style` element.
+            let styles = styles.join("\n").replace("</", "<\\/");
+            html.push_str(&format!("<style>\n{styles}\n</style>\n"));
+        }
+        html.push_str("</head>\n<body>\n<article>\n<header>\n");
+        html.push_str(&format!("<h1>{title}</h1>\n"));
+        if let Some(byline) = opts.byline.or(self.byline.as_deref()) {
+            html.push_str(&format!("<p class=\"byline\">{}</p>\n", escape(byline)));
+        }
+        if let Some(published) = opts.published {
+            let published = escape(published);
+            html.push_str(&format!(
+                "<time datetime=\"{published}\">{published}</time>\n"
+            ));
+        }
+        html.push_str("</header>\n");
+        html.push_str(&self.content);
+        html.push_str("\n</article>\n</body>\n</html>\n");
+
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_document() {
+        let readable = Readable {
+            title: "Cats & Dogs".to_owned(),
+            content: "<p>Text</p>".to_owned(),
+            text: "Text".to_owned(),
+            ..Default::default()
+        };
+
+        let document = readable.to_document(&DocumentOptions {
+            lang: Some("en"),
+            dir: Some("ltr"),
+            byline: Some("Jane Doe"),
+            published: Some("2024-12-01"),
+            stylesheet: Some(READER_STYLESHEET),
+            ..Default::default()
+        });
+
+        assert!(document.starts_with("<!DOCTYPE html>\n<html lang=\"en\" dir=\"ltr\">\n"));
+        assert!(document.contains("<meta charset=\"utf-8\">"));
+        assert!(document.contains("<title>Cats &amp; Dogs</title>"));
+        assert!(document.contains("<h1>Cats &amp; Dogs</h1>"));
+        assert!(document.contains("<p class=\"byline\">Jane Doe</p>"));
+        assert!(document.contains("<time datetime=\"2024-12-01\">2024-12-01</time>"));
+        assert!(document.contains(READER_STYLESHEET));
+        assert!(document.contains("</header>\n<p>Text</p>\n</article>"));
+    }
+
+    #[test]
+    fn test_to_document_theme() {
+        let readable = Readable {
+            title: "Title".to_owned(),
+            content: "<p>Text</p>".to_owned(),
+            ..Default::default()
+        };
+
+        let document = readable.to_document(&DocumentOptions {
+            stylesheet: Some(READER_STYLESHEET),
+            theme: Some(ReaderTheme::Dark),
+            font_scale: Some(1.25),
+            custom_css: Some("p { margin: 0; } </style><script>"),
+            ..Default::default()
+        });
+
+        let style =
+            &document[document.find("<style>").unwrap()..document.find("</style>").unwrap()];
+        assert!(style.contains(READER_STYLESHEET));
+        assert!(style.contains(ReaderTheme::Dark.css()));
+        assert!(style.contains("html { font-size: 125%; }"));
+        assert!(style.contains(r"p { margin: 0; } <\/style><script>"));
+        assert!(style.find(READER_STYLESHEET) < style.find(ReaderTheme::Dark.css()));
+    }
+
+    #[test]
+    fn test_to_document_default() {
+        let readable = Readable {
+            title: "Title".to_owned(),
+            content: "<p>Text</p>".to_owned(),
+            text: "Text".to_owned(),
+            ..Default::default()
+        };
+
+        let document = readable.to_document(&DocumentOptions::default());
+
+        assert!(document.starts_with("<!DOCTYPE html>\n<html>\n"));
+        assert!(!document.contains("<style>"));
+        assert!(!document.contains("byline"));
+        assert!(!document.contains("<time"));
+    }
+}
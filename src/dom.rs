@@ -38,6 +38,11 @@
 //! [tree structure]: https://en.wikipedia.org/wiki/Tree_(data_structure)
 //! [dom wiki]: https://en.wikipedia.org/wiki/Document_Object_Model
 
+mod arena;
+mod query;
+
+use crate::error::ParseDiagnostic;
+pub use arena::{ArenaDom, ArenaNode, ArenaNodeData, NodeId};
 use html5ever::{
     interface::{
         tree_builder,
@@ -49,6 +54,11 @@ use html5ever::{
     },
     Attribute, ExpandedName, QualName,
 };
+pub use query::{
+    descendants, find_by_attr, find_by_class, find_by_tag, get_attr, has_class, is_tag,
+    matches_any_tag, matches_tag, parent, remove_attr, same_node, set_attr, tag_name, text_content,
+    Descendants,
+};
 use std::{
     borrow::Cow,
     cell::{Cell, RefCell},
@@ -131,7 +141,7 @@ impl Drop for Node {
         let mut nodes = mem::take(&mut *self.children.borrow_mut());
         while let Some(node) = nodes.pop() {
             let children = mem::take(&mut *node.children.borrow_mut());
-            nodes.extend(children.into_iter());
+            nodes.extend(children);
             if let NodeData::Element {
                 ref template_contents,
                 ..
@@ -212,10 +222,13 @@ pub struct RcDom {
     pub document: Handle,
 
     /// Errors that occurred during parsing.
-    pub errors: RefCell<Vec<Cow<'static, str>>>,
+    pub errors: RefCell<Vec<ParseDiagnostic>>,
 
     /// The document's quirks mode.
     pub quirks_mode: Cell<QuirksMode>,
+
+    /// The line of the input which is currently parsed.
+    current_line: Cell<Option<u64>>,
 }
 
 impl TreeSink for RcDom {
@@ -229,7 +242,13 @@ impl TreeSink for RcDom {
     type ElemName<'a> = ExpandedName<'a>;
 
     fn parse_error(&self, msg: Cow<'static, str>) {
-        self.errors.borrow_mut().push(msg);
+        self.errors
+            .borrow_mut()
+            .push(ParseDiagnostic::new(msg, self.current_line.get()));
+    }
+
+    fn set_current_line(&self, line_number: u64) {
+        self.current_line.set(Some(line_number));
     }
 
     fn get_document(&self) -> Handle {
@@ -434,6 +453,7 @@ impl Default for RcDom {
             document: Node::new(NodeData::Document),
             errors: vec![].into(),
             quirks_mode: tree_builder::NoQuirks.into(),
+            current_line: Cell::new(None),
         }
     }
 }
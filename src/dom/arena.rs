@@ -0,0 +1,358 @@
+//! An owned copy of a DOM subtree.
+//!
+//! Parsing, scoring, and cleaning run on [`RcDom`](super::RcDom). An
+//! [`ArenaDom`] is a copy of a subtree of it, e.g. of the extracted content,
+//! whose nodes are stored in a single `Vec` and refer to each other by
+//! [`NodeId`]. The copy owns all of its data, and can be sent to other
+//! threads.
+
+use super::{append, Handle, Node, NodeData};
+use html5ever::{Attribute, QualName};
+use std::cell::RefCell;
+
+/// Reference to a node in an [`ArenaDom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// The position of the node in the arena.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// The different kinds of nodes in an [`ArenaDom`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArenaNodeData {
+    /// The `Document` itself - the root node of a HTML document.
+    Document,
+    /// A `DOCTYPE` with name, public id, and system id.
+    Doctype {
+        name: String,
+        public_id: String,
+        system_id: String,
+    },
+    /// A text node.
+    Text { contents: String },
+    /// A comment.
+    Comment { contents: String },
+    /// An element with attributes.
+    Element {
+        name: QualName,
+        attrs: Vec<(QualName, String)>,
+        /// For HTML \<template\> elements, the template contents.
+        template_contents: Option<NodeId>,
+        /// Whether the node is a HTML integration point.
+        mathml_annotation_xml_integration_point: bool,
+    },
+    /// A Processing instruction.
+    ProcessingInstruction { target: String, contents: String },
+}
+
+impl From<&NodeData> for ArenaNodeData {
+    /// Convert the node data without its children and template contents.
+    fn from(data: &NodeData) -> Self {
+        match data {
+            NodeData::Document => ArenaNodeData::Document,
+            NodeData::Doctype {
+                name,
+                public_id,
+                system_id,
+            } => ArenaNodeData::Doctype {
+                name: name.to_string(),
+                public_id: public_id.to_string(),
+                system_id: system_id.to_string(),
+            },
+            NodeData::Text { contents } => ArenaNodeData::Text {
+                contents: contents.borrow().to_string(),
+            },
+            NodeData::Comment { contents } => ArenaNodeData::Comment {
+                contents: contents.to_string(),
+            },
+            NodeData::Element {
+                name,
+                attrs,
+                mathml_annotation_xml_integration_point,
+                ..
+            } => ArenaNodeData::Element {
+                name: name.clone(),
+                attrs: attrs
+                    .borrow()
+                    .iter()
+                    .map(|attr| (attr.name.clone(), attr.value.to_string()))
+                    .collect(),
+                template_contents: None,
+                mathml_annotation_xml_integration_point: *mathml_annotation_xml_integration_point,
+            },
+            NodeData::ProcessingInstruction { target, contents } => {
+                ArenaNodeData::ProcessingInstruction {
+                    target: target.to_string(),
+                    contents: contents.to_string(),
+                }
+            }
+        }
+    }
+}
+
+impl From<&ArenaNodeData> for NodeData {
+    /// Convert the node data without its children and template contents.
+    fn from(data: &ArenaNodeData) -> Self {
+        match data {
+            ArenaNodeData::Document => NodeData::Document,
+            ArenaNodeData::Doctype {
+                name,
+                public_id,
+                system_id,
+            } => NodeData::Doctype {
+                name: name.as_str().into(),
+                public_id: public_id.as_str().into(),
+                system_id: system_id.as_str().into(),
+            },
+            ArenaNodeData::Text { contents } => NodeData::Text {
+                contents: RefCell::new(contents.as_str().into()),
+            },
+            ArenaNodeData::Comment { contents } => NodeData::Comment {
+                contents: contents.as_str().into(),
+            },
+            ArenaNodeData::Element {
+                name,
+                attrs,
+                mathml_annotation_xml_integration_point,
+                ..
+            } => NodeData::Element {
+                name: name.clone(),
+                attrs: RefCell::new(
+                    attrs
+                        .iter()
+                        .map(|(name, value)| Attribute {
+                            name: name.clone(),
+                            value: value.as_str().into(),
+                        })
+                        .collect(),
+                ),
+                template_contents: RefCell::new(None),
+                mathml_annotation_xml_integration_point: *mathml_annotation_xml_integration_point,
+            },
+            ArenaNodeData::ProcessingInstruction { target, contents } => {
+                NodeData::ProcessingInstruction {
+                    target: target.as_str().into(),
+                    contents: contents.as_str().into(),
+                }
+            }
+        }
+    }
+}
+
+/// A node in an [`ArenaDom`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaNode {
+    /// Parent node.
+    pub parent: Option<NodeId>,
+    /// Child nodes of this node.
+    pub children: Vec<NodeId>,
+    /// Represents this node's data.
+    pub data: ArenaNodeData,
+}
+
+impl ArenaNode {
+    fn new(data: ArenaNodeData) -> Self {
+        Self {
+            parent: None,
+            children: vec![],
+            data,
+        }
+    }
+}
+
+/// An owned, arena-backed copy of an [`RcDom`](super::RcDom) subtree, see
+/// [`ArenaDom::from_handle`].
+#[derive(Debug, Clone)]
+pub struct ArenaDom {
+    nodes: Vec<ArenaNode>,
+}
+
+impl Default for ArenaDom {
+    fn default() -> Self {
+        Self {
+            nodes: vec![ArenaNode::new(ArenaNodeData::Document)],
+        }
+    }
+}
+
+impl ArenaDom {
+    /// The `Document` node.
+    pub fn document(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    /// Get a node by its id.
+    pub fn get(&self, id: NodeId) -> &ArenaNode {
+        &self.nodes[id.0]
+    }
+
+    /// The number of nodes in the arena, including detached nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Check if the arena contains only the `Document` node.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() <= 1
+    }
+
+    /// Iterate over all descendants of a node in document order.
+    pub fn descendants(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut stack: Vec<NodeId> = self.get(id).children.iter().rev().copied().collect();
+
+        std::iter::from_fn(move || {
+            let id = stack.pop()?;
+            stack.extend(self.get(id).children.iter().rev());
+            Some(id)
+        })
+    }
+
+    /// Get the lowercase tag name of an element.
+    pub fn tag_name(&self, id: NodeId) -> Option<&str> {
+        match self.get(id).data {
+            ArenaNodeData::Element { ref name, .. } => Some(name.local.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Get the value of an attribute.
+    pub fn attr(&self, id: NodeId, name: &str) -> Option<&str> {
+        match self.get(id).data {
+            ArenaNodeData::Element { ref attrs, .. } => attrs
+                .iter()
+                .find(|(attr, _)| attr.local.as_ref() == name)
+                .map(|(_, value)| value.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Collect the text of all descendant text nodes without any formatting.
+    pub fn text_content(&self, id: NodeId) -> String {
+        std::iter::once(id)
+            .chain(self.descendants(id))
+            .filter_map(|id| match self.get(id).data {
+                ArenaNodeData::Text { ref contents } => Some(contents.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Copy the subtree of an [`RcDom`](super::RcDom) node into a new arena.
+    ///
+    /// The copied node is appended to the `Document` node of the arena, unless
+    /// it's a `Document` node itself.
+    pub fn from_handle(handle: &Handle) -> (Self, NodeId) {
+        let mut dom = Self::default();
+        let root = match handle.data {
+            NodeData::Document => dom.document(),
+            _ => {
+                let root = dom.push(ArenaNodeData::from(&handle.data));
+                dom.append(dom.document(), root);
+                root
+            }
+        };
+        let mut stack = vec![(handle.clone(), root)];
+
+        while let Some((handle, id)) = stack.pop() {
+            for child in handle.children.borrow().iter() {
+                let child_id = dom.push(ArenaNodeData::from(&child.data));
+                dom.append(id, child_id);
+                stack.push((child.clone(), child_id));
+            }
+        }
+
+        (dom, root)
+    }
+
+    /// Copy the subtree of a node into new [`RcDom`](super::RcDom) nodes,
+    /// which are not attached to any document.
+    ///
+    /// The template contents of `template` elements are not copied.
+    pub fn to_handle(&self, id: NodeId) -> Handle {
+        let root = Node::new(NodeData::from(&self.get(id).data));
+        let mut stack = vec![(id, root.clone())];
+
+        while let Some((id, handle)) = stack.pop() {
+            for &child_id in self.get(id).children.iter() {
+                let child = Node::new(NodeData::from(&self.get(child_id).data));
+                append(&handle, child.clone());
+                stack.push((child_id, child));
+            }
+        }
+
+        root
+    }
+
+    pub(crate) fn push(&mut self, data: ArenaNodeData) -> NodeId {
+        self.nodes.push(ArenaNode::new(data));
+        NodeId(self.nodes.len() - 1)
+    }
+
+    pub(crate) fn append(&mut self, parent: NodeId, child: NodeId) {
+        self.nodes[child.0].parent = Some(parent);
+        self.nodes[parent.0].children.push(child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::{find_by_tag, is_tag, parent, RcDom, SerializableHandle};
+    use html5ever::{parse_document, serialize, tendril::TendrilSink};
+
+    #[test]
+    fn test_from_handle() {
+        let html = r#"<!DOCTYPE html>
+            <html>
+                <head><title>Test Title</title></head>
+                <body><p id="first">First <b>bold</p></body>
+            </html>"#;
+        let dom = parse_document(RcDom::default(), Default::default()).one(html);
+        let (arena, document) = ArenaDom::from_handle(&dom.document);
+
+        let paragraphs = arena
+            .descendants(document)
+            .filter(|&id| arena.tag_name(id) == Some("p"))
+            .collect::<Vec<_>>();
+        assert_eq!(paragraphs.len(), 1);
+        assert_eq!(arena.attr(paragraphs[0], "id"), Some("first"));
+        assert_eq!(arena.text_content(paragraphs[0]), "First bold");
+
+        let (arena, body) = ArenaDom::from_handle(&find_by_tag(&dom.document, "body")[0]);
+        assert_eq!(arena.tag_name(body), Some("body"));
+        assert_eq!(arena.get(body).parent, Some(arena.document()));
+    }
+
+    #[test]
+    fn test_to_handle() {
+        let dom = parse_document(RcDom::default(), Default::default())
+            .one(r#"<article><!-- comment --><p class="a">First <b>bold</b></p>Text</article>"#);
+        let (arena, id) = ArenaDom::from_handle(&dom.document);
+
+        let handle = arena.to_handle(id);
+
+        let serialize_handle = |handle: &Handle| {
+            let mut bytes = vec![];
+            serialize(
+                &mut bytes,
+                &SerializableHandle::from(handle.clone()),
+                Default::default(),
+            )
+            .unwrap();
+            String::from_utf8(bytes).unwrap()
+        };
+        assert_eq!(serialize_handle(&handle), serialize_handle(&dom.document));
+        let bold = find_by_tag(&handle, "b").remove(0);
+        assert!(parent(&bold).is_some_and(|parent| is_tag(&parent, "p")));
+    }
+
+    #[test]
+    fn test_send() {
+        fn assert_send<T: Send + Sync>() {}
+        assert_send::<ArenaDom>();
+    }
+}
@@ -0,0 +1,224 @@
+//! Helpers to query and modify the DOM.
+//!
+//! These helpers can be used to post-process the content node returned by
+//! [`extract_with_node`](crate::extract_with_node) or
+//! [`extract_content`](crate::extract_content).
+
+use super::{Handle, NodeData};
+use html5ever::{namespace_url, ns, Attribute, LocalName, QualName};
+use std::rc::Rc;
+use tendril::StrTendril;
+
+/// Iterator over the descendants of a node in document order.
+///
+/// The node itself is not included.
+pub struct Descendants {
+    stack: Vec<Handle>,
+}
+
+impl Iterator for Descendants {
+    type Item = Handle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let handle = self.stack.pop()?;
+        self.stack
+            .extend(handle.children.borrow().iter().rev().cloned());
+        Some(handle)
+    }
+}
+
+/// Iterate over all descendants of a node in document order.
+pub fn descendants(handle: &Handle) -> Descendants {
+    Descendants {
+        stack: handle.children.borrow().iter().rev().cloned().collect(),
+    }
+}
+
+/// Get the lowercase tag name of an element.
+pub fn tag_name(handle: &Handle) -> Option<&str> {
+    match handle.data {
+        NodeData::Element { ref name, .. } => Some(name.local.as_ref()),
+        _ => None,
+    }
+}
+
+/// Check if the qualified name of an element matches the given tag name.
+///
+/// The comparison is ASCII case-insensitive and doesn't allocate.
+pub fn matches_tag(name: &QualName, tag_name: &str) -> bool {
+    let local_name: &str = &name.local;
+    local_name.eq_ignore_ascii_case(tag_name)
+}
+
+/// Check if the qualified name of an element matches any of the given tag names.
+pub fn matches_any_tag(name: &QualName, tag_names: &[&str]) -> bool {
+    tag_names.iter().any(|tag_name| matches_tag(name, tag_name))
+}
+
+/// Check if the node is an element with the given tag name.
+pub fn is_tag(handle: &Handle, tag_name: &str) -> bool {
+    match handle.data {
+        NodeData::Element { ref name, .. } => matches_tag(name, tag_name),
+        _ => false,
+    }
+}
+
+/// Find all descendant elements with the given tag name.
+pub fn find_by_tag(handle: &Handle, tag_name: &str) -> Vec<Handle> {
+    descendants(handle)
+        .filter(|node| is_tag(node, tag_name))
+        .collect()
+}
+
+/// Find all descendant elements with the given attribute.
+///
+/// If `value` is given, only elements with this exact attribute value are
+/// returned.
+pub fn find_by_attr(handle: &Handle, name: &str, value: Option<&str>) -> Vec<Handle> {
+    descendants(handle)
+        .filter(|node| match get_attr(node, name) {
+            Some(attr) => value.is_none_or(|value| attr == value),
+            None => false,
+        })
+        .collect()
+}
+
+/// Find all descendant elements with the given class.
+pub fn find_by_class(handle: &Handle, class: &str) -> Vec<Handle> {
+    descendants(handle)
+        .filter(|node| has_class(node, class))
+        .collect()
+}
+
+/// Check if the element has the given class.
+pub fn has_class(handle: &Handle, class: &str) -> bool {
+    get_attr(handle, "class")
+        .is_some_and(|classes| classes.split_ascii_whitespace().any(|name| name == class))
+}
+
+/// Get the value of an attribute.
+pub fn get_attr(handle: &Handle, name: &str) -> Option<String> {
+    match handle.data {
+        NodeData::Element { ref attrs, .. } => attrs
+            .borrow()
+            .iter()
+            .find(|attr| attr.name.local.as_ref() == name)
+            .map(|attr| attr.value.to_string()),
+        _ => None,
+    }
+}
+
+/// Set the value of an attribute, adding the attribute if it doesn't exist.
+pub fn set_attr(handle: &Handle, name: &str, value: &str) {
+    if let NodeData::Element { ref attrs, .. } = handle.data {
+        let mut attrs = attrs.borrow_mut();
+
+        match attrs
+            .iter_mut()
+            .find(|attr| attr.name.local.as_ref() == name)
+        {
+            Some(attr) => attr.value = StrTendril::from_slice(value),
+            None => attrs.push(Attribute {
+                name: QualName::new(None, ns!(), LocalName::from(name)),
+                value: StrTendril::from_slice(value),
+            }),
+        }
+    }
+}
+
+/// Remove an attribute, and return its value.
+pub fn remove_attr(handle: &Handle, name: &str) -> Option<String> {
+    match handle.data {
+        NodeData::Element { ref attrs, .. } => {
+            let mut attrs = attrs.borrow_mut();
+            let index = attrs
+                .iter()
+                .position(|attr| attr.name.local.as_ref() == name)?;
+            Some(attrs.remove(index).value.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Collect the text of all descendant text nodes without any formatting.
+pub fn text_content(handle: &Handle) -> String {
+    let mut text = String::new();
+
+    if let NodeData::Text { ref contents } = handle.data {
+        text.push_str(&contents.borrow());
+    }
+
+    for node in descendants(handle) {
+        if let NodeData::Text { ref contents } = node.data {
+            text.push_str(&contents.borrow());
+        }
+    }
+
+    text
+}
+
+/// Get the parent of a node.
+pub fn parent(handle: &Handle) -> Option<Handle> {
+    let weak = handle.parent.take()?;
+    let parent = weak.upgrade();
+    handle.parent.set(Some(weak));
+    parent
+}
+
+/// Check if two handles refer to the same node.
+pub fn same_node(a: &Handle, b: &Handle) -> bool {
+    Rc::ptr_eq(a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    fn parse(html: &str) -> Handle {
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap();
+        dom.document.clone()
+    }
+
+    #[test]
+    fn test_query() {
+        let document = parse(
+            r#"<html><body>
+                <div id="main" class="content post">
+                    <p>First <a href="/one">one</a></p>
+                    <p class="post">Second</p>
+                </div>
+            </body></html>"#,
+        );
+
+        let paragraphs = find_by_tag(&document, "p");
+        assert_eq!(paragraphs.len(), 2);
+        assert_eq!(text_content(&paragraphs[0]), "First one");
+
+        let posts = find_by_class(&document, "post");
+        assert_eq!(posts.len(), 2);
+        assert_eq!(get_attr(&posts[0], "id").as_deref(), Some("main"));
+        assert!(same_node(&parent(&posts[1]).unwrap(), &posts[0]));
+
+        assert_eq!(find_by_attr(&document, "href", None).len(), 1);
+        assert_eq!(find_by_attr(&document, "href", Some("/two")).len(), 0);
+    }
+
+    #[test]
+    fn test_attributes() {
+        let document = parse(r#"<p id="first">Text</p>"#);
+        let p = &find_by_tag(&document, "p")[0];
+
+        set_attr(p, "id", "second");
+        set_attr(p, "class", "text");
+        assert_eq!(get_attr(p, "id").as_deref(), Some("second"));
+        assert!(has_class(p, "text"));
+
+        assert_eq!(remove_attr(p, "id").as_deref(), Some("second"));
+        assert_eq!(get_attr(p, "id"), None);
+    }
+}
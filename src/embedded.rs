@@ -0,0 +1,147 @@
+//! Recover the article body which is embedded in the script payloads of
+//! frameworks, e.g. `__NEXT_DATA__` of Next.js, `window.__APOLLO_STATE__`, or
+//! the `articleBody` of JSON-LD.
+
+use crate::{
+    dom::{self, Handle},
+    html,
+};
+use serde_json::Value;
+
+/// The global variables of the initial state of frameworks, which are
+/// assigned a JSON object in inline scripts.
+const STATE_VARIABLES: [&str; 4] = [
+    "__APOLLO_STATE__",
+    "__INITIAL_STATE__",
+    "__PRELOADED_STATE__",
+    "__NUXT__",
+];
+/// The keys of article bodies, compared in lowercase without `_` and `-`.
+const BODY_KEYS: [&str; 5] = [
+    "articlebody",
+    "articlehtml",
+    "bodyhtml",
+    "bodytext",
+    "contenthtml",
+];
+/// The minimal number of characters of an embedded article body.
+const MIN_BODY_LENGTH: usize = 200;
+
+/// Find the longest article body in the JSON-LD scripts, the data of Next.js,
+/// and the initial state of frameworks, and convert it to HTML.
+///
+/// Must be called before preprocessing removes the scripts.
+pub(crate) fn article_html(document: &Handle) -> Option<String> {
+    let mut body: Option<String> = None;
+
+    for script in dom::find_by_tag(document, "script") {
+        let Some(payload) = script_payload(&script) else {
+            continue;
+        };
+        find_body(&payload, &mut body);
+    }
+
+    body.filter(|body| body.trim().chars().count() >= MIN_BODY_LENGTH)
+        .map(|body| to_html(&body))
+}
+
+/// Parse the JSON payload of a script, if any.
+fn script_payload(script: &Handle) -> Option<Value> {
+    let text = dom::text_content(script);
+    let is_json = dom::get_attr(script, "type")
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("application/ld+json"))
+        || dom::get_attr(script, "id").as_deref() == Some("__NEXT_DATA__");
+
+    if is_json {
+        return serde_json::from_str(&text).ok();
+    }
+
+    // E.g. `window.__APOLLO_STATE__ = {...};`, followed by other statements.
+    let start = STATE_VARIABLES
+        .iter()
+        .find_map(|variable| text.find(variable).map(|index| index + variable.len()))?;
+    let json = text[start..].trim_start().strip_prefix('=')?;
+
+    serde_json::Deserializer::from_str(json)
+        .into_iter::<Value>()
+        .next()?
+        .ok()
+}
+
+/// Find the longest string of an article body key in a JSON value.
+fn find_body(value: &Value, body: &mut Option<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    Value::String(text) if is_body_key(key) => {
+                        if body.as_ref().is_none_or(|body| text.len() > body.len()) {
+                            *body = Some(text.clone());
+                        }
+                    }
+                    _ => find_body(value, body),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| find_body(value, body)),
+        _ => (),
+    }
+}
+
+fn is_body_key(key: &str) -> bool {
+    let key = key.replace(['_', '-'], "").to_lowercase();
+    BODY_KEYS.contains(&key.as_str())
+}
+
+/// Use an HTML body as is, or convert the lines of a text body to paragraphs.
+fn to_html(body: &str) -> String {
+    let is_html = body
+        .find('<')
+        .is_some_and(|index| body[index + 1..].starts_with(|c: char| c.is_ascii_alphabetic()));
+
+    if is_html {
+        return body.to_owned();
+    }
+
+    body.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| format!("<p>{}</p>", html::escape(line)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    fn article_html_of(html: &str) -> Option<String> {
+        let dom = parse_document(RcDom::default(), Default::default()).one(html);
+        article_html(&dom.document)
+    }
+
+    #[test]
+    fn test_article_html() {
+        let body = "A paragraph of the article & more. ".repeat(10);
+
+        let html = format!(
+            r#"<script id="__NEXT_DATA__" type="application/json">
+            {{"props": {{"pageProps": {{"story": {{"article_body": "{body}\n\nLast"}}}}}}}}
+            </script>"#
+        );
+        assert_eq!(
+            article_html_of(&html),
+            Some(format!("<p>{}</p><p>Last</p>", html::escape(body.trim())))
+        );
+
+        let html = format!(
+            r#"<script>window.__APOLLO_STATE__ = {{"Article:1": {{"bodyHtml": "<p>{body}</p>"}}}};
+            window.other = 1;</script>"#
+        );
+        assert_eq!(article_html_of(&html), Some(format!("<p>{body}</p>")));
+
+        let html = r#"<script type="application/ld+json">{"articleBody": "Too short"}</script>"#;
+        assert_eq!(article_html_of(html), None);
+    }
+}
@@ -0,0 +1,329 @@
+//! Export the extracted content as a single-chapter EPUB 3.
+
+use crate::{
+    error::ReadabilityError,
+    extractor::Readable,
+    xhtml::{escape_xml, to_xhtml},
+};
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+    io::{Seek, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+/// Download the image with the given `src`.
+pub type FetchImage<'a> = &'a dyn Fn(&str) -> Option<EpubImage>;
+
+/// An image downloaded by [`EpubOptions::fetch_image`].
+#[derive(Debug, Clone)]
+pub struct EpubImage {
+    pub data: Vec<u8>,
+    /// The media type of the image, e.g. `image/png`.
+    pub media_type: String,
+}
+
+#[derive(Clone, Default)]
+pub struct EpubOptions<'a> {
+    /// The unique identifier of the publication. Defaults to a hash of the content.
+    pub identifier: Option<&'a str>,
+    /// The language of the publication. Defaults to `en`.
+    pub lang: Option<&'a str>,
+    /// The author of the article.
+    pub author: Option<&'a str>,
+    /// The publication date of the article.
+    pub published: Option<&'a str>,
+    /// The modification date in the format `CCYY-MM-DDThh:mm:ssZ`. Defaults to
+    /// the current time.
+    pub modified: Option<&'a str>,
+    /// Download the image with the given `src` to embed it into the EPUB.
+    ///
+    /// Images which are not downloaded are referenced by their original url.
+    pub fetch_image: Option<FetchImage<'a>>,
+}
+
+impl fmt::Debug for EpubOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EpubOptions")
+            .field("identifier", &self.identifier)
+            .field("lang", &self.lang)
+            .field("author", &self.author)
+            .field("published", &self.published)
+            .field("modified", &self.modified)
+            .field("fetch_image", &self.fetch_image.is_some())
+            .finish()
+    }
+}
+
+struct ImageItem {
+    path: String,
+    media_type: String,
+}
+
+impl Readable {
+    /// Write the content as a single-chapter EPUB.
+    pub fn to_epub<W>(&self, writer: W, opts: &EpubOptions) -> Result<W, ReadabilityError>
+    where
+        W: Write + Seek,
+    {
+        let mut zip = ZipWriter::new(writer);
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        // The mimetype must be the first file and must not be compressed.
+        zip.start_file("mimetype", stored)?;
+        zip.write_all(b"application/epub+zip")?;
+
+        zip.start_file("META-INF/container.xml", deflated)?;
+        zip.write_all(CONTAINER.as_bytes())?;
+
+        let mut images = vec![];
+        let body = to_xhtml(
+            &self.content,
+            Some(&mut |src| {
+                let image = opts.fetch_image.and_then(|fetch_image| fetch_image(src))?;
+                let path = format!(
+                    "images/image-{}.{}",
+                    images.len() + 1,
+                    extension(&image.media_type)
+                );
+                images.push((path.clone(), image));
+                Some(path)
+            }),
+        );
+
+        let lang = escape_xml(opts.lang.unwrap_or("en"));
+        let title = escape_xml(&self.title);
+
+        zip.start_file("OEBPS/chapter.xhtml", deflated)?;
+        write!(
+            zip,
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" lang="{lang}" xml:lang="{lang}">
+<head>
+<meta charset="utf-8"/>
+<title>{title}</title>
+</head>
+<body>
+<article>
+<h1>{title}</h1>
+{body}
+</article>
+</body>
+</html>
+"#
+        )?;
+
+        zip.start_file("OEBPS/nav.xhtml", deflated)?;
+        write!(
+            zip,
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" lang="{lang}" xml:lang="{lang}">
+<head>
+<meta charset="utf-8"/>
+<title>{title}</title>
+</head>
+<body>
+<nav epub:type="toc">
+<ol>
+<li><a href="chapter.xhtml">{title}</a></li>
+</ol>
+</nav>
+</body>
+</html>
+"#
+        )?;
+
+        let mut items = vec![];
+        for (path, image) in images {
+            zip.start_file(format!("OEBPS/{path}"), deflated)?;
+            zip.write_all(&image.data)?;
+            items.push(ImageItem {
+                path,
+                media_type: image.media_type,
+            });
+        }
+
+        zip.start_file("OEBPS/content.opf", deflated)?;
+        zip.write_all(self.package(opts, &lang, &title, &items).as_bytes())?;
+
+        Ok(zip.finish()?)
+    }
+
+    fn package(&self, opts: &EpubOptions, lang: &str, title: &str, images: &[ImageItem]) -> String {
+        let identifier = match opts.identifier {
+            Some(identifier) => escape_xml(identifier),
+            None => {
+                let mut hasher = DefaultHasher::new();
+                self.title.hash(&mut hasher);
+                self.content.hash(&mut hasher);
+                format!("urn:readability:{:016x}", hasher.finish())
+            }
+        };
+        let modified = match opts.modified {
+            Some(modified) => escape_xml(modified),
+            None => now(),
+        };
+
+        let mut metadata = format!(
+            r#"<dc:identifier id="id">{identifier}</dc:identifier>
+<dc:title>{title}</dc:title>
+<dc:language>{lang}</dc:language>
+<meta property="dcterms:modified">{modified}</meta>
+"#
+        );
+        if let Some(author) = opts.author {
+            metadata.push_str(&format!(
+                "<dc:creator>{}</dc:creator>\n",
+                escape_xml(author)
+            ));
+        }
+        if let Some(published) = opts.published {
+            metadata.push_str(&format!("<dc:date>{}</dc:date>\n", escape_xml(published)));
+        }
+
+        let mut manifest = String::from(
+            r#"<item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+<item id="chapter" href="chapter.xhtml" media-type="application/xhtml+xml"/>
+"#,
+        );
+        for (index, image) in images.iter().enumerate() {
+            manifest.push_str(&format!(
+                "<item id=\"image-{}\" href=\"{}\" media-type=\"{}\"/>\n",
+                index + 1,
+                image.path,
+                escape_xml(&image.media_type)
+            ));
+        }
+
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="id">
+<metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+{metadata}</metadata>
+<manifest>
+{manifest}</manifest>
+<spine>
+<itemref idref="chapter"/>
+</spine>
+</package>
+"#
+        )
+    }
+}
+
+fn extension(media_type: &str) -> &str {
+    match media_type {
+        "image/jpeg" => "jpg",
+        "image/svg+xml" => "svg",
+        _ => media_type
+            .strip_prefix("image/")
+            .filter(|extension| extension.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or("bin"),
+    }
+}
+
+/// Format the current time as `CCYY-MM-DDThh:mm:ssZ`.
+fn now() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let (days, secs) = (secs / 86400, secs % 86400);
+
+    // Convert days since the epoch to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+    use zip::ZipArchive;
+
+    fn read_file<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> String {
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn test_to_epub() {
+        let readable = Readable {
+            title: "Title".to_owned(),
+            content: r#"<div><p>Text</p><img src="https://example.com/a.png"></div>"#.to_owned(),
+            text: "Text".to_owned(),
+            ..Default::default()
+        };
+        let fetch_image = |src: &str| {
+            assert_eq!(src, "https://example.com/a.png");
+            Some(EpubImage {
+                data: vec![1, 2, 3],
+                media_type: "image/png".to_owned(),
+            })
+        };
+
+        let epub = readable
+            .to_epub(
+                Cursor::new(vec![]),
+                &EpubOptions {
+                    author: Some("Jane Doe"),
+                    modified: Some("2024-12-01T00:00:00Z"),
+                    fetch_image: Some(&fetch_image),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let mut archive = ZipArchive::new(epub).unwrap();
+        let mimetype = archive.by_index(0).unwrap();
+        assert_eq!(mimetype.name(), "mimetype");
+        assert_eq!(mimetype.compression(), CompressionMethod::Stored);
+        drop(mimetype);
+
+        assert_eq!(read_file(&mut archive, "mimetype"), "application/epub+zip");
+        assert!(read_file(&mut archive, "OEBPS/chapter.xhtml")
+            .contains(r#"<div><p>Text</p><img src="images/image-1.png"/></div>"#));
+
+        let package = read_file(&mut archive, "OEBPS/content.opf");
+        assert!(package.contains("<dc:creator>Jane Doe</dc:creator>"));
+        assert!(package
+            .contains(r#"<item id="image-1" href="images/image-1.png" media-type="image/png"/>"#));
+        assert_eq!(
+            archive.by_name("OEBPS/images/image-1.png").unwrap().size(),
+            3
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::{borrow::Cow, io};
+use std::{borrow::Cow, fmt, io};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -6,11 +6,169 @@ pub enum ReadabilityError {
     #[error("Can't parse url: {0:?}")]
     ParseUrl(#[from] url::ParseError),
     #[error("Can't parse HTML: {0:?}")]
-    ParseHtml(Vec<Cow<'static, str>>),
+    ParseHtml(Vec<ParseDiagnostic>),
     #[error("Can't read/write HTML: {0:?}")]
     ReadWriteHtml(#[from] io::Error),
+    #[cfg(feature = "epub")]
+    #[error("Can't write EPUB: {0}")]
+    WriteEpub(#[from] zip::result::ZipError),
+    #[cfg(feature = "config")]
+    #[error("Can't parse config: {0}")]
+    ParseConfig(String),
+    #[error("Can't compile regex: {0}")]
+    CompileRegex(#[from] regex::Error),
+    #[error("Can't parse MHTML: {0}")]
+    ParseMhtml(String),
     #[error("Can't fetch url")]
     FetchUrl,
+    #[error("Empty input")]
+    EmptyInput,
+    #[error("Input is not HTML")]
+    NotHtml,
+    #[error("No content found")]
+    NoContent,
+    #[error("Content too short: {actual} of {required} characters")]
+    ContentTooShort { actual: usize, required: usize },
+    #[error("Page requires JavaScript")]
+    RequiresJavaScript,
     #[error("Unexpected error")]
     Unexpected,
 }
+
+/// The severity of a recoverable parse error reported by html5ever.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Cosmetic errors which don't affect the structure of the DOM, e.g. invalid
+    /// character references or duplicate attributes.
+    #[default]
+    Minor,
+    /// Errors which required the parser to restructure the DOM, e.g. unclosed
+    /// or misnested tags.
+    Major,
+}
+
+/// A recoverable parse error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseDiagnostic {
+    /// The error message reported by html5ever.
+    pub message: Cow<'static, str>,
+    /// The line in the input where the error occurred, if available.
+    pub line: Option<u64>,
+    /// The severity, which is classified from the message.
+    pub severity: Severity,
+}
+
+impl ParseDiagnostic {
+    pub fn new(message: Cow<'static, str>, line: Option<u64>) -> Self {
+        let severity = classify(&message);
+
+        Self {
+            message,
+            line,
+            severity,
+        }
+    }
+}
+
+impl fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{} (line {line})", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+const MINOR_ERRORS: [&str; 11] = [
+    "character reference",
+    "Bad character",
+    "Duplicate attribute",
+    "Attributes on an end tag",
+    "Self-closing end tag",
+    "Unacknowledged self-closing tag",
+    "Non-space table text",
+    "Unexpected characters",
+    "DOCTYPE",
+    "Numeric character reference",
+    "EOF after '#'",
+];
+
+/// The tokenizer states in which a bad character, which is reported as "Saw
+/// <character> in state <state>" if exact errors are enabled, doesn't affect
+/// the structure of the DOM, e.g. in attributes or the doctype.
+///
+/// In other states, e.g. `TagOpen` or `MarkupDeclarationOpen`, the markup is
+/// read as text or as a bogus comment instead.
+const MINOR_STATES: [&str; 22] = [
+    "Data",
+    "Plaintext",
+    "TagName",
+    "RawData",
+    "BeforeAttributeName",
+    "AttributeName",
+    "AfterAttributeName",
+    "BeforeAttributeValue",
+    "AttributeValue",
+    "AfterAttributeValueQuoted",
+    "SelfClosingStartTag",
+    "Doctype",
+    "BeforeDoctypeName",
+    "DoctypeName",
+    "AfterDoctypeName",
+    "AfterDoctypeKeyword",
+    "BeforeDoctypeIdentifier",
+    "DoctypeIdentifierDoubleQuoted",
+    "DoctypeIdentifierSingleQuoted",
+    "AfterDoctypeIdentifier",
+    "BetweenDoctypePublicAndSystemIdentifiers",
+    "BogusDoctype",
+];
+
+fn classify(message: &str) -> Severity {
+    // Unexpected EOF is reported as "Saw EOF" if exact errors are enabled.
+    if message.contains("EOF in state") {
+        return Severity::Major;
+    }
+
+    if let Some((_, state)) = message
+        .strip_prefix("Saw ")
+        .and_then(|message| message.rsplit_once(" in state "))
+    {
+        // Strip the kind of states like `AttributeValue(DoubleQuoted)`.
+        let state = state.split('(').next().unwrap_or(state);
+
+        return if MINOR_STATES.contains(&state) {
+            Severity::Minor
+        } else {
+            Severity::Major
+        };
+    }
+
+    if MINOR_ERRORS.iter().any(|minor| message.contains(minor)) {
+        Severity::Minor
+    } else {
+        Severity::Major
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("Bad character"), Severity::Minor);
+        assert_eq!(
+            classify("Saw b in state AfterAttributeValueQuoted"),
+            Severity::Minor
+        );
+        assert_eq!(
+            classify("Saw \" in state AttributeValue(Unquoted)"),
+            Severity::Minor
+        );
+        // `</ p>` is read as a bogus comment.
+        assert_eq!(classify("Saw   in state EndTagOpen"), Severity::Major);
+        assert_eq!(classify("Saw EOF in state Comment"), Severity::Major);
+        assert_eq!(classify("Unexpected token"), Severity::Major);
+    }
+}
@@ -1,41 +1,340 @@
+#[cfg(feature = "json")]
+use crate::embedded;
 use crate::{
-    dom::{Handle, NodeData, RcDom, SerializableHandle},
-    error::ReadabilityError,
-    html,
-    scorer::{self, Scorer, ScorerOptions, TopCandidate},
+    app_shell,
+    author::{self, AuthorBio},
+    charset, compression,
+    diff::CleanReason,
+    dom::{self, ArenaDom, Handle, Node, NodeData, NodeId, RcDom},
+    error::{ParseDiagnostic, ReadabilityError, Severity},
+    footnotes, html,
+    images::{collect_images, ImageInfo},
+    links::{collect_links, LinkInfo},
+    locator::Locators,
+    metadata::{self, Metadata},
+    paywall::{self, TruncationReason},
+    render::{render_content_with, ContentNode, SerializeOptions},
+    scorer::{self, CandidateStats, Scorer, ScorerOptions, TopCandidate},
+    text::{normalize_text, render_text, NormalizeOptions, TextOptions},
+    toc::{table_of_contents, TocEntry, TocOptions},
     utils::{debug_candidate, debug_candidates},
 };
-use html5ever::{parse_document, serialize, tendril::stream::TendrilSink, ParseOpts};
+use html5ever::{
+    parse_document,
+    tendril::stream::TendrilSink,
+    tokenizer::TokenizerOpts,
+    tree_builder::{NodeOrText, TreeBuilderOpts, TreeSink},
+    ParseOpts,
+};
 use log::{debug, trace};
 use scorer::Candidate;
-use std::{cell::Cell, collections::BTreeMap, default::Default, io::Read, path::Path};
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, HashSet},
+    default::Default,
+    io::Read,
+    path::Path,
+    rc::Rc,
+};
 use url::Url;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Readable {
     pub title: String,
     pub content: String,
     pub text: String,
+    /// The author of the article, which is removed from the content.
+    pub byline: Option<String>,
+    /// The language declared by the `lang` attribute of the `html` element.
+    pub lang: Option<String>,
+    /// The ISO 639-3 code of the language detected from the text, which can be
+    /// compared to [`Readable::lang`] if the declared language is missing or wrong.
+    ///
+    /// Requires the `lang-detect` feature, and is `None` if the detection is
+    /// not reliable.
+    pub detected_language: Option<String>,
+    /// The url of the AMP version of the article from `<link rel="amphtml">`.
+    pub amp_url: Option<String>,
+    /// The canonical url of the article from `<link rel="canonical">`, e.g. the
+    /// url of the original article if the document is an AMP version.
+    pub canonical_url: Option<String>,
+    /// The headings of the content, if [`ExtractOptions::toc_options`] is set.
+    pub toc: Vec<TocEntry>,
+    /// The images of the content.
+    pub images: Vec<ImageInfo>,
+    /// The outbound links of the content.
+    pub links: Vec<LinkInfo>,
+    /// Whether the article is paywalled or truncated, i.e. the content is
+    /// probably a teaser of the full article.
+    pub truncated: bool,
+    /// Why the article is considered truncated.
+    pub truncation_reason: Option<TruncationReason>,
+    /// The confidence in the extracted content between `0.0` and `1.0`, which
+    /// can be used to decide whether to trust the content or fall back to the
+    /// whole page.
+    ///
+    /// The confidence is derived from the score of the top candidate, the
+    /// length and the link density of the content, and is `0.0` if no
+    /// candidate was found.
+    pub confidence: f32,
+    /// The statistics of the candidate detection, e.g. whether the scoring was
+    /// cut short by [`ScorerOptions::max_candidates`].
+    pub candidate_stats: CandidateStats,
+    /// The CSS selector of the content in the original document, i.e. a chain
+    /// of `nth-child` selectors like `html > body:nth-child(2) >
+    /// article:nth-child(1)`, e.g. to highlight the content in a browser with
+    /// `document.querySelector`.
+    ///
+    /// If the content was created during preprocessing, e.g. a paragraph
+    /// converted from a `div`, the selector locates its closest ancestor. The
+    /// selector is empty if the content is not part of the document, e.g. an
+    /// [`ExtractOptions::embedded_article`], or if [`OutputSet::selectors`] is
+    /// not set.
+    pub content_selector: String,
+    /// The text of the author bio, if [`ExtractOptions::author_bio`] is set to
+    /// [`AuthorBio::Preserve`] or [`AuthorBio::Extract`].
+    pub author_bio: Option<String>,
+    /// The metadata of the document, which is not part of the content.
+    pub metadata: Metadata,
+    /// The content node, which is rendered on demand by [`Readable::to_html`],
+    /// [`Readable::to_text`], and [`Readable::to_markdown`], if
+    /// [`OutputSet::content_node`] is set.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub content_node: Option<ContentNode>,
 }
 
 #[derive(Debug)]
 pub struct Content {
     pub node: Handle,
     pub title: String,
+    pub byline: Option<String>,
+    /// See [`Readable::confidence`].
+    pub confidence: f32,
+    /// See [`Readable::candidate_stats`].
+    pub candidate_stats: CandidateStats,
+    /// See [`Readable::content_selector`].
+    pub selector: String,
+}
+
+/// The extracted content with an owned DOM, which can be sent to other threads.
+#[derive(Debug, Clone)]
+pub struct OwnedContent {
+    pub dom: ArenaDom,
+    pub node: NodeId,
+    pub title: String,
+    pub byline: Option<String>,
+}
+
+impl From<Content> for OwnedContent {
+    fn from(content: Content) -> Self {
+        let (dom, node) = ArenaDom::from_handle(&content.node);
+
+        Self {
+            dom,
+            node,
+            title: content.title,
+            byline: content.byline,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct ExtractOptions<'a> {
     pub parse_options: ParseOptions,
     pub scorer_options: ScorerOptions<'a>,
+    /// How [`Readable::content`] is serialized.
+    pub serialize_options: SerializeOptions,
+    /// What to return as content if no candidate was found.
+    pub fallback_policy: FallbackPolicy,
+    /// Return [`ReadabilityError::ContentTooShort`] if the text of the content
+    /// has fewer characters, ignoring leading and trailing whitespace.
+    pub min_content_length: usize,
+    /// Return [`ReadabilityError::RequiresJavaScript`] if the document is an
+    /// app shell whose content is rendered by JavaScript, e.g. a `body` with
+    /// an empty `<div id="root">` and scripts, so the url can be fetched
+    /// again with a headless browser.
+    pub detect_app_shell: bool,
+    /// Use the article body which is embedded in script payloads, e.g. the
+    /// `articleBody` of JSON-LD, `__NEXT_DATA__`, or
+    /// `window.__APOLLO_STATE__`, if the text of the extracted content is
+    /// shorter than half of the text of the embedded article.
+    ///
+    /// Requires the `json` feature, and is ignored otherwise.
+    pub embedded_article: bool,
+    /// Render [`Readable::text`] with [`render_text`] instead of
+    /// [`extract_text`], which preserves the document structure.
+    pub text_options: Option<TextOptions>,
+    /// Normalize whitespace and Unicode in [`Readable::text`].
+    pub normalize_options: Option<NormalizeOptions>,
+    /// Build [`Readable::toc`] from the headings of the content.
+    pub toc_options: Option<TocOptions>,
+    /// Preserve or extract the author bio instead of leaving it to the scorer.
+    pub author_bio: AuthorBio,
+    /// Skip the serialization of [`Readable::content`] or the extraction of
+    /// [`Readable::text`] if they are not needed.
+    pub outputs: OutputSet,
 }
 
-#[derive(Debug, Clone, Default)]
+/// What to return as content if no candidate was found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum FallbackPolicy {
+    /// Clean the `body` element, or the whole document if there is none.
+    #[default]
+    Body,
+    /// Return [`ReadabilityError::NoContent`].
+    Error,
+    /// Return empty content.
+    Empty,
+}
+
+/// The outputs of the extraction, which are left empty if not requested.
+///
+/// Skipped outputs can still be rendered with [`Readable::to_html`] and
+/// [`Readable::to_text`] if the content node is retained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputSet {
+    /// Serialize the content as HTML into [`Readable::content`].
+    pub html: bool,
+    /// Extract the text of the content into [`Readable::text`].
+    ///
+    /// The text is also used to detect truncation markers and the language,
+    /// which are skipped otherwise.
+    pub text: bool,
+    /// Retain a copy of the content in [`Readable::content_node`], which
+    /// costs a copy of the content subtree.
+    pub content_node: bool,
+    /// Locate the content in the original document by a CSS selector in
+    /// [`Readable::content_selector`], which costs a map of all elements.
+    pub selectors: bool,
+}
+
+impl OutputSet {
+    /// Both HTML and text.
+    pub const ALL: Self = Self {
+        html: true,
+        text: true,
+        content_node: false,
+        selectors: false,
+    };
+    /// Only the HTML.
+    pub const HTML: Self = Self {
+        html: true,
+        text: false,
+        content_node: false,
+        selectors: false,
+    };
+    /// Only the text.
+    pub const TEXT: Self = Self {
+        html: false,
+        text: true,
+        content_node: false,
+        selectors: false,
+    };
+}
+
+impl Default for OutputSet {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct ParseOptions {
+    /// Return [`ReadabilityError::ParseHtml`] if the HTML contains parse errors.
     pub strict: bool,
+    /// Report all parse errors described in the spec, at some performance
+    /// penalty, and use exact error messages.
+    pub exact_errors: bool,
+    /// Parse the document as if scripting were enabled, i.e. the content of
+    /// `noscript` elements is treated as raw text.
+    pub scripting_enabled: bool,
+    /// Parse the document as the `srcdoc` of an `iframe`.
+    pub iframe_srcdoc: bool,
+    /// Drop the `DOCTYPE` from the DOM.
+    pub drop_doctype: bool,
+    /// Only parse errors of at least this severity are considered in strict mode.
+    pub min_severity: Severity,
+    /// Parse errors containing one of these messages are ignored in strict mode.
+    pub allowed_errors: Vec<String>,
+    /// The maximal number of parse errors which are tolerated in strict mode.
+    pub max_errors: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            exact_errors: false,
+            scripting_enabled: true,
+            iframe_srcdoc: false,
+            drop_doctype: false,
+            min_severity: Severity::default(),
+            allowed_errors: vec![],
+            max_errors: 0,
+        }
+    }
+}
+
+impl From<&ParseOptions> for ParseOpts {
+    fn from(options: &ParseOptions) -> Self {
+        ParseOpts {
+            tokenizer: TokenizerOpts {
+                exact_errors: options.exact_errors,
+                ..Default::default()
+            },
+            tree_builder: TreeBuilderOpts {
+                exact_errors: options.exact_errors,
+                scripting_enabled: options.scripting_enabled,
+                iframe_srcdoc: options.iframe_srcdoc,
+                drop_doctype: options.drop_doctype,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Filter the parse errors which are considered in strict mode.
+    pub fn filter_errors(&self, errors: Vec<ParseDiagnostic>) -> Vec<ParseDiagnostic> {
+        errors
+            .into_iter()
+            .filter(|error| error.severity >= self.min_severity)
+            .filter(|error| {
+                !self
+                    .allowed_errors
+                    .iter()
+                    .any(|allowed| error.message.contains(allowed.as_str()))
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<(&str, &Url)> for Readable {
+    type Error = ReadabilityError;
+
+    fn try_from((html, url): (&str, &Url)) -> Result<Self, Self::Error> {
+        extract_from_str(html, url, ExtractOptions::default())
+    }
+}
+
+impl TryFrom<(&[u8], &Url)> for Readable {
+    type Error = ReadabilityError;
+
+    fn try_from((bytes, url): (&[u8], &Url)) -> Result<Self, Self::Error> {
+        extract_from_bytes(bytes, url, ExtractOptions::default())
+    }
 }
 
 /// Extract content from an HTML reader.
+///
+/// The input is expected to be UTF-8 encoded. Use [`extract_from_bytes`] if
+/// the encoding is unknown.
 pub fn extract<R>(
     input: &mut R,
     url: &Url,
@@ -44,86 +343,730 @@ pub fn extract<R>(
 where
     R: Read,
 {
-    let mut dom = parse_document(RcDom::default(), ParseOpts::default())
-        .from_utf8()
-        .read_from(input)?;
+    let mut bytes = vec![];
+    input.read_to_end(&mut bytes)?;
+    let bytes = compression::decompress(&bytes)?;
+
+    extract_from_utf8(&bytes, url, opts).map(|(readable, _)| readable)
+}
 
-    if opts.parse_options.strict && !dom.errors.borrow().is_empty() {
-        return Err(ReadabilityError::ParseHtml(dom.errors.into_inner()));
+/// Extract content from an HTML reader, and return the content node along with
+/// the [`Readable`].
+///
+/// The content node can be used to post-process the DOM without parsing the
+/// serialized content again.
+pub fn extract_with_node<R>(
+    input: &mut R,
+    url: &Url,
+    opts: ExtractOptions,
+) -> Result<(Readable, Handle), ReadabilityError>
+where
+    R: Read,
+{
+    let mut bytes = vec![];
+    input.read_to_end(&mut bytes)?;
+    let bytes = compression::decompress(&bytes)?;
+
+    extract_from_utf8(&bytes, url, opts)
+}
+
+/// Extract content from an HTML reader, and return an owned copy of the content
+/// DOM along with the [`Readable`].
+///
+/// In contrast to [`extract_with_node`], the result can be sent to other threads.
+pub fn extract_owned<R>(
+    input: &mut R,
+    url: &Url,
+    opts: ExtractOptions,
+) -> Result<(Readable, OwnedContent), ReadabilityError>
+where
+    R: Read,
+{
+    let (readable, node) = extract_with_node(input, url, opts)?;
+    let content = Content {
+        node,
+        title: readable.title.clone(),
+        byline: readable.byline.clone(),
+        confidence: readable.confidence,
+        candidate_stats: readable.candidate_stats,
+        selector: readable.content_selector.clone(),
+    };
+
+    Ok((readable, content.into()))
+}
+
+/// Extract content from an HTML string.
+pub fn extract_from_str(
+    html: &str,
+    url: &Url,
+    opts: ExtractOptions,
+) -> Result<Readable, ReadabilityError> {
+    extract_from_utf8(html.as_bytes(), url, opts).map(|(readable, _)| readable)
+}
+
+/// Extract content from HTML bytes.
+///
+/// The character encoding is detected from the byte order mark or the `meta`
+/// charset declaration, and the input is decoded to UTF-8 before parsing.
+///
+/// Gzip or zlib compressed input is decompressed with the `gzip` feature, and
+/// zstd compressed input with the `zstd` feature.
+pub fn extract_from_bytes(
+    bytes: &[u8],
+    url: &Url,
+    opts: ExtractOptions,
+) -> Result<Readable, ReadabilityError> {
+    let bytes = compression::decompress(bytes)?;
+    let html = charset::decode(&bytes);
+
+    extract_from_utf8(html.as_bytes(), url, opts).map(|(readable, _)| readable)
+}
+
+/// A reusable extractor, which owns the options and the prepared scorer.
+///
+/// The options are set up once, e.g. with custom [`CandidatePatterns`] or
+/// loaded from a config, and the candidate patterns are prepared once instead
+/// of for every document. The extractor can be shared between threads, e.g.
+/// as part of the application state of a web service.
+///
+/// ```
+/// use readability::{ExtractOptions, Extractor, Preset};
+/// use url::Url;
+///
+/// let extractor = Extractor::new(ExtractOptions::preset(Preset::News)).unwrap();
+/// let url = Url::parse("https://example.com").unwrap();
+/// let html = "<p>This is a test paragraph with more than 25 characters.</p>";
+///
+/// let readable = extractor.extract_from_str(html, &url).unwrap();
+/// assert_eq!(
+///     readable.text,
+///     "This is a test paragraph with more than 25 characters."
+/// );
+/// ```
+///
+/// [`CandidatePatterns`]: crate::CandidatePatterns
+#[derive(Debug, Clone, Default)]
+pub struct Extractor<'a> {
+    options: ExtractOptions<'a>,
+    scorer: Scorer<'a>,
+}
+
+impl<'a> TryFrom<ExtractOptions<'a>> for Extractor<'a> {
+    type Error = ReadabilityError;
+
+    fn try_from(options: ExtractOptions<'a>) -> Result<Self, Self::Error> {
+        Self::new(options)
     }
+}
 
-    let content = extract_content(&mut dom, url, opts);
+impl<'a> Extractor<'a> {
+    /// Create an extractor, which prepares the scorer once, see
+    /// [`Scorer::new`].
+    pub fn new(options: ExtractOptions<'a>) -> Result<Self, ReadabilityError> {
+        let scorer = Scorer::new(options.scorer_options.clone())?;
 
-    let mut bytes = vec![];
+        Ok(Self { options, scorer })
+    }
+
+    pub fn options(&self) -> &ExtractOptions<'a> {
+        &self.options
+    }
+
+    /// Extract content from an HTML reader, see [`extract`].
+    pub fn extract<R>(&self, input: &mut R, url: &Url) -> Result<Readable, ReadabilityError>
+    where
+        R: Read,
+    {
+        let mut bytes = vec![];
+        input.read_to_end(&mut bytes)?;
+        let bytes = compression::decompress(&bytes)?;
+
+        extract_with_scorer(&bytes, url, &self.options, &self.scorer).map(|(readable, _)| readable)
+    }
+
+    /// Extract content from an HTML string, see [`extract_from_str`].
+    pub fn extract_from_str(&self, html: &str, url: &Url) -> Result<Readable, ReadabilityError> {
+        extract_with_scorer(html.as_bytes(), url, &self.options, &self.scorer)
+            .map(|(readable, _)| readable)
+    }
+
+    /// Extract content from HTML bytes, see [`extract_from_bytes`].
+    pub fn extract_from_bytes(
+        &self,
+        bytes: &[u8],
+        url: &Url,
+    ) -> Result<Readable, ReadabilityError> {
+        let bytes = compression::decompress(bytes)?;
+        let html = charset::decode(&bytes);
+
+        extract_with_scorer(html.as_bytes(), url, &self.options, &self.scorer)
+            .map(|(readable, _)| readable)
+    }
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(url = %url))
+)]
+fn extract_from_utf8(
+    bytes: &[u8],
+    url: &Url,
+    opts: ExtractOptions,
+) -> Result<(Readable, Handle), ReadabilityError> {
+    let scorer = Scorer::new(opts.scorer_options.clone())?;
+
+    extract_with_scorer(bytes, url, &opts, &scorer)
+}
+
+/// Extract content from UTF-8 encoded HTML with a prepared scorer, which is
+/// reused by an [`Extractor`].
+fn extract_with_scorer(
+    bytes: &[u8],
+    url: &Url,
+    opts: &ExtractOptions,
+    scorer: &Scorer,
+) -> Result<(Readable, Handle), ReadabilityError> {
+    let mut dom = parse_html(bytes, &opts.parse_options)?;
+
+    if opts.detect_app_shell && app_shell::is_app_shell(&dom.document) {
+        return Err(ReadabilityError::RequiresJavaScript);
+    }
 
-    serialize(
-        &mut bytes,
-        &SerializableHandle::from(content.node.clone()),
-        Default::default(),
-    )?;
+    let document = DocumentInfo::new(&dom.document, url);
+    let locators = Locators::collect_if(opts.outputs.selectors, &dom.document);
+    let author_bio_node = match opts.author_bio {
+        AuthorBio::Remove => None,
+        AuthorBio::Preserve | AuthorBio::Extract => author::find_author_bio(&dom.document),
+    };
+    let author_bio = author_bio_node.as_ref().map(author::text);
+    if let Some(ref node) = author_bio_node {
+        dom.remove_from_parent(node);
+    }
+    #[cfg(feature = "json")]
+    let embedded_article = opts
+        .embedded_article
+        .then(|| embedded::article_html(&dom.document))
+        .flatten();
+    #[cfg(not(feature = "json"))]
+    let embedded_article: Option<String> = None;
+    let content = extract_located_content(&mut dom, url, opts, scorer, locators);
+    let (mut dom, content) = match embedded_article {
+        Some(html) => recover_embedded_article(dom, content, &html, url, opts, scorer)?,
+        None => (dom, content?),
+    };
+
+    if let Some(node) = author_bio_node.filter(|_| opts.author_bio == AuthorBio::Preserve) {
+        let clean_options = &opts.scorer_options.clean_options;
+        footnotes::clean_note(&mut dom, &node, url, clean_options, &HashSet::new());
+        dom.append(&content.node, NodeOrText::AppendNode(node));
+    }
+
+    let (mut readable, node) = readable_from_content(&mut dom, content, document, url, opts)?;
+    readable.author_bio = author_bio;
+
+    Ok((readable, node))
+}
+
+/// The properties of the document which are not part of the content, and
+/// must be collected before preprocessing.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DocumentInfo {
+    lang: Option<String>,
+    amp_url: Option<String>,
+    canonical_url: Option<String>,
+    truncation_reason: Option<TruncationReason>,
+    metadata: Metadata,
+}
+
+impl DocumentInfo {
+    pub(crate) fn new(document: &Handle, url: &Url) -> Self {
+        Self {
+            lang: document_lang(document),
+            amp_url: document_link(document, "amphtml", url),
+            canonical_url: document_link(document, "canonical", url),
+            truncation_reason: paywall::document_truncation(document),
+            metadata: metadata::document_metadata(document, url),
+        }
+    }
+}
 
-    let mut text: String = String::new();
+/// Render the cleaned content to HTML and text, and collect its headings,
+/// images, and links according to the extract options.
+///
+/// The author bio is left empty.
+pub(crate) fn readable_from_content(
+    dom: &mut RcDom,
+    content: Content,
+    document: DocumentInfo,
+    url: &Url,
+    opts: &ExtractOptions,
+) -> Result<(Readable, Handle), ReadabilityError> {
+    let outputs = opts.outputs;
+    let toc = opts
+        .toc_options
+        .as_ref()
+        .map(|toc_options| table_of_contents(&content.node, toc_options))
+        .unwrap_or_default();
+    let images = collect_images(&content.node, url);
+    let links = collect_links(&content.node, url);
+
+    let rendered = {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("serialize").entered();
+
+        // Extract the text along with the HTML, unless it's rendered separately.
+        render_content_with(
+            &content.node,
+            outputs.html,
+            outputs.text && opts.text_options.is_none(),
+            !opts.scorer_options.legacy_line_breaks,
+            &opts.serialize_options,
+        )?
+    };
+
+    let text = match opts.text_options {
+        Some(ref text_options) if outputs.text => render_text(&content.node, text_options),
+        _ => rendered.text.unwrap_or_default(),
+    };
+    let text = match opts.normalize_options {
+        Some(ref normalize_options) if outputs.text => normalize_text(&text, normalize_options),
+        _ => text,
+    };
 
-    extract_text(content.node.clone(), &mut text, true);
+    if opts.min_content_length > 0 {
+        let content_length = if outputs.text {
+            text.trim().chars().count()
+        } else {
+            dom::text_content(&content.node).trim().chars().count()
+        };
+
+        if content_length < opts.min_content_length {
+            return Err(ReadabilityError::ContentTooShort {
+                actual: content_length,
+                required: opts.min_content_length,
+            });
+        }
+    }
 
-    let content_string = String::from_utf8(bytes).unwrap_or_default();
+    let content_string = rendered.html.unwrap_or_default();
+    let truncation_reason = document
+        .truncation_reason
+        .or_else(|| paywall::text_truncation(&text));
 
     debug!("Extracted title: {}", content.title);
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        title = %content.title,
+        content_len = content_string.len(),
+        text_len = text.len(),
+        "extracted content"
+    );
     trace!("Extracted text: {text}");
     trace!("Extracted content: {content_string}");
 
-    Ok(Readable {
-        title: content.title,
-        content: content_string,
-        text,
-    })
+    #[cfg(feature = "lang-detect")]
+    let detected_language = detect_language(&text);
+    #[cfg(not(feature = "lang-detect"))]
+    let detected_language = None;
+
+    // Detach the content node, otherwise its children are dropped along with the DOM.
+    dom.remove_from_parent(&content.node);
+    let content_node = outputs.content_node.then(|| {
+        let (dom, node) = ArenaDom::from_handle(&content.node);
+        ContentNode { dom, node }
+    });
+
+    Ok((
+        Readable {
+            title: content.title,
+            content: content_string,
+            text,
+            byline: content.byline,
+            lang: document.lang,
+            detected_language,
+            amp_url: document.amp_url,
+            canonical_url: document.canonical_url,
+            toc,
+            images,
+            links,
+            truncated: truncation_reason.is_some(),
+            truncation_reason,
+            confidence: content.confidence,
+            candidate_stats: content.candidate_stats,
+            content_selector: content.selector,
+            author_bio: None,
+            metadata: document.metadata,
+            content_node,
+        },
+        content.node,
+    ))
+}
+
+/// Extract the embedded article instead if no content was found, or if the
+/// content has less than half of its text, see
+/// [`ExtractOptions::embedded_article`].
+fn recover_embedded_article(
+    dom: RcDom,
+    content: Result<Content, ReadabilityError>,
+    article_html: &str,
+    url: &Url,
+    opts: &ExtractOptions,
+    scorer: &Scorer,
+) -> Result<(RcDom, Content), ReadabilityError> {
+    let mut embedded_dom = parse_document(RcDom::default(), Default::default()).one(format!(
+        "<html><body><article>{article_html}</article></body></html>"
+    ));
+    let locators = Locators::collect_if(opts.outputs.selectors, &embedded_dom.document);
+    let Ok(embedded_content) =
+        extract_located_content(&mut embedded_dom, url, opts, scorer, locators)
+    else {
+        return Ok((dom, content?));
+    };
+    let text_len = |content: &Content| dom::text_content(&content.node).trim().chars().count();
+
+    match content {
+        Ok(content) if text_len(&content) * 2 >= text_len(&embedded_content) => Ok((dom, content)),
+        Ok(content) => Ok((
+            embedded_dom,
+            Content {
+                title: content.title,
+                byline: content.byline.or(embedded_content.byline),
+                selector: String::new(),
+                ..embedded_content
+            },
+        )),
+        Err(_) => Ok((
+            embedded_dom,
+            Content {
+                selector: String::new(),
+                ..embedded_content
+            },
+        )),
+    }
+}
+
+/// Parse UTF-8 encoded HTML, and check the parse errors in strict mode.
+///
+/// The input is expected to be decompressed by the caller already, see
+/// [`compression::decompress`].
+pub(crate) fn parse_html(
+    bytes: &[u8],
+    parse_options: &ParseOptions,
+) -> Result<RcDom, ReadabilityError> {
+    check_html(bytes)?;
+
+    let dom = parse_document(RcDom::default(), ParseOpts::from(parse_options))
+        .from_utf8()
+        .read_from(&mut &bytes[..])?;
+
+    if parse_options.strict {
+        let errors = parse_options.filter_errors(dom.errors.take());
+
+        if errors.len() > parse_options.max_errors {
+            return Err(ReadabilityError::ParseHtml(errors));
+        }
+    }
+
+    Ok(dom)
 }
 
 /// Extract content `Node` from DOM.
-pub fn extract_content(dom: &mut RcDom, url: &Url, opts: ExtractOptions) -> Content {
+///
+/// If no candidate was found, the content depends on
+/// [`ExtractOptions::fallback_policy`].
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(url = %url))
+)]
+pub fn extract_content(
+    dom: &mut RcDom,
+    url: &Url,
+    opts: ExtractOptions,
+) -> Result<Content, ReadabilityError> {
+    let scorer = Scorer::new(opts.scorer_options.clone())?;
+    let locators = Locators::collect_if(opts.outputs.selectors, &dom.document);
+    extract_located_content(dom, url, &opts, &scorer, locators)
+}
+
+/// Extract the content, and locate it by the positions of the elements in
+/// the original document, which are collected before the document is
+/// modified, e.g. by removing the author bio.
+fn extract_located_content(
+    dom: &mut RcDom,
+    url: &Url,
+    opts: &ExtractOptions,
+    scorer: &Scorer,
+    locators: Locators,
+) -> Result<Content, ReadabilityError> {
+    let scoring = score_document(dom, scorer, locators);
+
+    let (id, node, score) = match scoring.top_candidate {
+        Some((ref id, ref node, score)) => (id.clone(), node.clone(), Some(score)),
+        None => match fallback_content(&scoring, &dom.document, opts.fallback_policy)? {
+            Some((id, node)) => (id, node, None),
+            None => {
+                return Ok(Content {
+                    node: Node::new(NodeData::Document),
+                    title: scoring.title,
+                    byline: scoring.byline,
+                    confidence: 0.0,
+                    candidate_stats: scoring.stats,
+                    selector: String::new(),
+                })
+            }
+        },
+    };
+    let selector = scoring.locators.closest_selector(&node).unwrap_or_default();
+    let confidence = clean_content(
+        dom,
+        scorer,
+        &id,
+        &node,
+        url,
+        &scoring.candidates,
+        score,
+        &mut vec![],
+    );
+
+    Ok(Content {
+        node,
+        title: scoring.title,
+        byline: scoring.byline,
+        confidence,
+        candidate_stats: scoring.stats,
+        selector,
+    })
+}
+
+/// The preprocessed and scored document, see [`score_document`].
+pub(crate) struct Scoring {
+    pub title: String,
+    pub byline: Option<String>,
+    pub candidates: BTreeMap<String, Candidate>,
+    /// The id, node, and score of the top candidate, if any.
+    pub top_candidate: Option<(String, Handle, f32)>,
+    /// The id and node of the `body` element, if any.
+    pub body: Option<(String, Handle)>,
+    pub stats: CandidateStats,
+    /// The positions of the elements before preprocessing.
+    pub locators: Locators,
+}
+
+/// Get the id and node of the content if no candidate was found according to
+/// the fallback policy, or `None` for empty content.
+pub(crate) fn fallback_content(
+    scoring: &Scoring,
+    document: &Handle,
+    fallback_policy: FallbackPolicy,
+) -> Result<Option<(String, Handle)>, ReadabilityError> {
+    match fallback_policy {
+        FallbackPolicy::Body => Ok(Some(
+            scoring
+                .body
+                .clone()
+                .unwrap_or_else(|| ("/".to_owned(), document.clone())),
+        )),
+        FallbackPolicy::Error => Err(ReadabilityError::NoContent),
+        FallbackPolicy::Empty => Ok(None),
+    }
+}
+
+/// Preprocess the document, and score the candidates.
+pub(crate) fn score_document(dom: &mut RcDom, scorer: &Scorer, locators: Locators) -> Scoring {
     let mut title = String::new();
     let mut candidates = BTreeMap::new();
     let mut nodes = BTreeMap::new();
     let handle = dom.document.clone();
-    let scorer = Scorer::new(opts.scorer_options);
 
-    scorer.preprocess(dom, handle.clone(), &mut title);
-    scorer.find_candidates(Path::new("/"), handle.clone(), &mut candidates, &mut nodes);
+    {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("preprocess").entered();
+
+        scorer.preprocess(dom, &handle, &mut title);
+    }
+
+    let byline = scorer.find_byline(dom, &handle);
+    debug!("Found byline: {byline:?}");
+
+    let stats = {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("find_candidates").entered();
+
+        scorer.find_candidates(Path::new("/"), &handle, &mut candidates, &mut nodes)
+    };
 
     debug!("Found candidates: {}", candidates.values().len());
+    if stats.candidate_limit_reached || stats.terminated_early {
+        debug!("Scoring was cut short: {stats:?}");
+    }
     trace!("Found candidates: {:?}", debug_candidates(&candidates));
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        candidates = candidates.len(),
+        nodes = nodes.len(),
+        "found candidates"
+    );
 
-    let top_candidate = scorer.find_top_candidate(&candidates).unwrap_or_else(|| {
-        TopCandidate::new(
-            "/",
-            Candidate {
-                node: handle.clone(),
-                score: Cell::new(0.0),
-            },
-        )
+    let content_hint = scorer.find_content_hint(&handle).and_then(|hint| {
+        nodes
+            .iter()
+            .find(|(_, node)| Rc::ptr_eq(node, &hint))
+            .map(|(id, node)| {
+                let score = candidates
+                    .get(id)
+                    .map_or(0.0, |candidate| candidate.score.get());
+                debug!("Found content hint: {id}");
+                TopCandidate::new(
+                    id,
+                    Candidate {
+                        node: node.clone(),
+                        score: Cell::new(score),
+                    },
+                )
+            })
     });
+    let top_candidate = content_hint
+        .or_else(|| scorer.find_top_candidate(&candidates))
+        .map(|top_candidate| {
+            debug!(
+                "Found top candidate: {:?}",
+                debug_candidate(top_candidate.candidate())
+            );
+            #[cfg(feature = "tracing")]
+            if let Some(tag) = debug_candidate(top_candidate.candidate()) {
+                tracing::debug!(
+                    id = top_candidate.id(),
+                    tag = %tag.name,
+                    attribute_id = tag.attribute_id.as_deref(),
+                    score = tag.score,
+                    "found top candidate"
+                );
+            }
 
-    debug!(
-        "Found top candidate: {:?}",
-        debug_candidate(top_candidate.candidate())
-    );
+            (
+                top_candidate.id().to_owned(),
+                top_candidate.node().clone(),
+                top_candidate.score().get(),
+            )
+        });
 
-    scorer.clean(
-        dom,
-        Path::new(top_candidate.id()),
-        top_candidate.node().clone(),
-        url,
-        &candidates,
-    );
+    let body = nodes
+        .iter()
+        .find(|(_, node)| dom::is_tag(node, "body"))
+        .map(|(id, node)| (id.clone(), node.clone()));
 
-    Content {
-        node: top_candidate.node().clone(),
+    Scoring {
         title,
+        byline,
+        candidates,
+        top_candidate,
+        body,
+        stats,
+        locators,
+    }
+}
+
+/// Clean the content of a candidate, and estimate the confidence in the
+/// content, which is `0.0` without a score.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn clean_content(
+    dom: &mut RcDom,
+    scorer: &Scorer,
+    id: &str,
+    node: &Handle,
+    url: &Url,
+    candidates: &BTreeMap<String, Candidate>,
+    score: Option<f32>,
+    removed: &mut Vec<(Handle, CleanReason)>,
+) -> f32 {
+    {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("clean", id = id).entered();
+
+        scorer.clean_with_reasons(dom, Path::new(id), node, url, candidates, removed);
+        scorer.remove_related_sections(dom, node, url);
+        scorer.append_footnotes(dom, node, url);
+        scorer.postprocess(dom, node);
     }
+
+    let confidence = score.map_or(0.0, |score| scorer.confidence(score, node));
+    debug!("Confidence: {confidence}");
+
+    confidence
+}
+
+/// Get the `lang` attribute of the `html` element.
+fn document_lang(document: &Handle) -> Option<String> {
+    document
+        .children
+        .borrow()
+        .iter()
+        .find(|child| dom::is_tag(child, "html"))
+        .and_then(|html| dom::get_attr(html, "lang"))
+        .map(|lang| lang.trim().to_owned())
+        .filter(|lang| !lang.is_empty())
+}
+
+/// Get the absolute `href` of the first `link` element with the given `rel`.
+fn document_link(document: &Handle, rel: &str, url: &Url) -> Option<String> {
+    dom::find_by_tag(document, "link")
+        .iter()
+        .filter(|link| {
+            dom::get_attr(link, "rel").is_some_and(|value| {
+                value
+                    .split_ascii_whitespace()
+                    .any(|value| value.eq_ignore_ascii_case(rel))
+            })
+        })
+        .filter_map(|link| dom::get_attr(link, "href"))
+        .find_map(|href| url.join(href.trim()).ok())
+        .map(String::from)
+}
+
+/// Detect the language of the extracted text.
+#[cfg(feature = "lang-detect")]
+fn detect_language(text: &str) -> Option<String> {
+    whatlang::detect(text)
+        .filter(|info| info.is_reliable())
+        .map(|info| info.lang().code().to_owned())
+}
+
+/// Check if the input is empty, or looks like binary data or plain text
+/// rather than markup.
+pub(crate) fn check_html(bytes: &[u8]) -> Result<(), ReadabilityError> {
+    if bytes.iter().all(|byte| byte.is_ascii_whitespace()) {
+        return Err(ReadabilityError::EmptyInput);
+    }
+
+    if !is_html(bytes) {
+        return Err(ReadabilityError::NotHtml);
+    }
+
+    Ok(())
+}
+
+/// The number of bytes at the start of the input which are checked for binary
+/// data, like the resource header in the MIME sniffing standard.
+const BINARY_SNIFF_LENGTH: usize = 1445;
+
+/// Check if the input looks like markup rather than binary data or plain text.
+///
+/// Like in MIME sniffing, only the first [`BINARY_SNIFF_LENGTH`] bytes are
+/// checked for NUL bytes, so that a stray NUL byte in a page is accepted.
+fn is_html(bytes: &[u8]) -> bool {
+    let header = &bytes[..bytes.len().min(BINARY_SNIFF_LENGTH)];
+
+    !header.contains(&0) && bytes.contains(&b'<')
 }
 
 /// Convert HTML to formatted text, including linebreaks and whitespaces.
-pub fn extract_text(handle: Handle, text: &mut String, deep: bool) {
+pub fn extract_text(handle: &Handle, text: &mut String, deep: bool) {
+    extract_text_with(handle, text, deep, true)
+}
+
+/// Like [`extract_text`], but `br` elements only start a new line if
+/// `line_breaks` is `true`, see [`ScorerOptions::legacy_line_breaks`].
+pub fn extract_text_with(handle: &Handle, text: &mut String, deep: bool, line_breaks: bool) {
     let mut last_tag_name = None;
 
     for child in handle.children.borrow().iter() {
@@ -131,18 +1074,16 @@ pub fn extract_text(handle: Handle, text: &mut String, deep: bool) {
             NodeData::Text { ref contents } => {
                 text.push_str(contents.borrow().as_ref());
             }
-            NodeData::Element { .. } => {
-                if deep {
-                    if let Some(tag_name) = last_tag_name {
-                        if &tag_name == "p" {
-                            text.push('\n');
-                        }
-                    }
+            NodeData::Element { .. } if deep => {
+                if last_tag_name == Some("p")
+                    || (line_breaks && html::get_tag_name(child) == Some("br"))
+                {
+                    text.push('\n');
+                }
 
-                    extract_text(child.clone(), text, deep);
+                extract_text_with(child, text, deep, line_breaks);
 
-                    last_tag_name = html::get_tag_name(child.clone());
-                }
+                last_tag_name = html::get_tag_name(child);
             }
             _ => (),
         }
@@ -0,0 +1,72 @@
+//! Describe nodes by features, e.g. to train a content classifier, and score
+//! candidates with an external classifier instead of the heuristics.
+
+use std::fmt;
+
+/// The features of an element, as seen by the scorer after preprocessing.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NodeFeatures {
+    /// The lowercase tag name.
+    pub tag: String,
+    /// The depth of the element, where the `html` element has depth `1`.
+    pub depth: usize,
+    /// The text length, measured with
+    /// [`ScorerOptions::length_metric`](crate::ScorerOptions::length_metric).
+    pub text_length: usize,
+    /// The ratio of the text of links to the text, see
+    /// [`ScorerOptions::hash_link_coefficient`](crate::ScorerOptions::hash_link_coefficient).
+    pub link_density: f32,
+    /// The number of punctuation marks, see
+    /// [`ScorerOptions::punctuations`](crate::ScorerOptions::punctuations).
+    pub punctuation_count: usize,
+    /// The weight of the `id` and `class` of the element.
+    pub class_weight: f32,
+    /// The number of child elements.
+    pub child_count: usize,
+    /// The number of sibling elements, excluding the element itself.
+    pub sibling_count: usize,
+    /// The total text length of the sibling elements.
+    pub sibling_text_length: usize,
+}
+
+/// A classifier which replaces the candidate detection and the content score
+/// of the scorer, see [`ScorerOptions::classifier`].
+///
+/// The score of a node is distributed among its parents like the content
+/// score, so the top candidate is the parent of the most content.
+///
+/// ```
+/// use readability::{NodeFeatures, ScorerOptions};
+///
+/// let classifier = |features: &NodeFeatures| {
+///     (features.tag == "p" && features.link_density < 0.5)
+///         .then(|| features.text_length as f32 / 100.0)
+/// };
+/// let options = ScorerOptions {
+///     classifier: Some(&classifier),
+///     ..Default::default()
+/// };
+/// ```
+///
+/// [`ScorerOptions::classifier`]: crate::ScorerOptions::classifier
+pub trait NodeClassifier: Send + Sync {
+    /// Return the content score of a node, or `None` if it is not a
+    /// candidate.
+    fn score(&self, features: &NodeFeatures) -> Option<f32>;
+}
+
+impl<F> NodeClassifier for F
+where
+    F: Fn(&NodeFeatures) -> Option<f32> + Send + Sync,
+{
+    fn score(&self, features: &NodeFeatures) -> Option<f32> {
+        self(features)
+    }
+}
+
+impl fmt::Debug for dyn NodeClassifier + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NodeClassifier")
+    }
+}
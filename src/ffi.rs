@@ -0,0 +1,186 @@
+//! C bindings.
+//!
+//! Build the shared library with
+//! `cargo rustc --release --features ffi --crate-type cdylib`. The header
+//! `include/readability.h` is generated with
+//! `cbindgen --config cbindgen.toml --output include/readability.h`, and CI
+//! checks that it is up to date.
+
+use crate::{
+    bindings::{BindingOptions, BindingReadable},
+    extractor, ReadabilityError,
+};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    ptr, slice,
+};
+use url::Url;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+}
+
+fn to_c_string(string: String) -> *mut c_char {
+    match CString::new(string) {
+        Ok(string) => string.into_raw(),
+        Err(err) => {
+            set_last_error(err.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+unsafe fn to_str<'a>(string: *const c_char, name: &str) -> Result<&'a str, String> {
+    if string.is_null() {
+        return Err(format!("`{name}` is null"));
+    }
+
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|err| format!("`{name}` is not valid UTF-8: {err}"))
+}
+
+unsafe fn extract(
+    html: *const u8,
+    len: usize,
+    url: *const c_char,
+    options_json: *const c_char,
+) -> Result<String, String> {
+    if html.is_null() {
+        return Err("`html` is null".to_owned());
+    }
+
+    let html = slice::from_raw_parts(html, len);
+    let url = Url::parse(to_str(url, "url")?).map_err(|err| err.to_string())?;
+    let options: BindingOptions = if options_json.is_null() {
+        BindingOptions::default()
+    } else {
+        serde_json::from_str(to_str(options_json, "options_json")?)
+            .map_err(|err| err.to_string())?
+    };
+
+    let readable = extractor::extract_from_bytes(html, &url, options.to_extract_options())
+        .map_err(|err: ReadabilityError| err.to_string())?;
+
+    serde_json::to_string(&BindingReadable::from(&readable)).map_err(|err| err.to_string())
+}
+
+/// Extract the readable content from an HTML document.
+///
+/// Returns a JSON object with exactly the fields `title`, `content`, `text`,
+/// `byline`, `lang`, and `detected_language`, which must be freed with
+/// `readability_string_free`.
+/// Returns null on error, and the error message can be retrieved with
+/// `readability_last_error`.
+///
+/// # Safety
+///
+/// `html` must point to `len` readable bytes. `url` must be a null-terminated
+/// string. `options_json` must be null or a null-terminated JSON object with
+/// the optional fields `strict`, `errorOnNoContent`, `minCandidateLength`, and
+/// `maxCandidateParents`.
+#[no_mangle]
+pub unsafe extern "C" fn readability_extract(
+    html: *const u8,
+    len: usize,
+    url: *const c_char,
+    options_json: *const c_char,
+) -> *mut c_char {
+    match extract(html, len, url, options_json) {
+        Ok(json) => to_c_string(json),
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Return the message of the last error on the current thread, or null if no
+/// error occurred.
+///
+/// The returned string must be freed with `readability_string_free`.
+#[no_mangle]
+pub extern "C" fn readability_last_error() -> *mut c_char {
+    LAST_ERROR
+        .with(|error| error.borrow_mut().take())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Free a string returned by this library.
+///
+/// # Safety
+///
+/// `string` must be null or a string returned by this library, which wasn't
+/// freed before.
+#[no_mangle]
+pub unsafe extern "C" fn readability_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readability_extract() {
+        let html = "<!DOCTYPE html><html><head><title>Test Title</title></head><body><p>This is a test paragraph with more than 25 characters.</p></body></html>";
+        let url = CString::new("https://example.com").unwrap();
+        let options = CString::new(r#"{"strict": true}"#).unwrap();
+
+        unsafe {
+            let json =
+                readability_extract(html.as_ptr(), html.len(), url.as_ptr(), options.as_ptr());
+            assert!(!json.is_null());
+
+            let readable: serde_json::Value =
+                serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+            assert_eq!(readable["title"], "Test Title");
+            assert_eq!(
+                readable["text"],
+                "This is a test paragraph with more than 25 characters."
+            );
+            assert_eq!(
+                readable.as_object().unwrap().keys().collect::<Vec<_>>(),
+                [
+                    "byline",
+                    "content",
+                    "detected_language",
+                    "lang",
+                    "text",
+                    "title"
+                ]
+            );
+
+            readability_string_free(json);
+        }
+    }
+
+    #[test]
+    fn test_readability_extract_error() {
+        let html = "<p>Test</p>";
+        let url = CString::new("not a url").unwrap();
+
+        unsafe {
+            let json = readability_extract(html.as_ptr(), html.len(), url.as_ptr(), ptr::null());
+            assert!(json.is_null());
+
+            let error = readability_last_error();
+            assert!(!error.is_null());
+            assert_eq!(
+                CStr::from_ptr(error).to_str().unwrap(),
+                "relative URL without a base"
+            );
+            readability_string_free(error);
+
+            assert!(readability_last_error().is_null());
+        }
+    }
+}
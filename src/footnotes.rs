@@ -0,0 +1,228 @@
+//! Pull footnotes which live outside of the content into a footnotes section.
+
+use crate::{
+    dom::{self, Handle, NodeData, RcDom},
+    html,
+    scorer::{fix_anchor_path, fix_img_path},
+    CleanOptions,
+};
+use html5ever::{
+    local_name, namespace_url, ns,
+    tree_builder::{ElementFlags, NodeOrText, TreeSink},
+    QualName,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashSet;
+use tendril::StrTendril;
+use url::Url;
+
+const FOOTNOTE_REFS: &str = r"(?i)fn|foot|note|ref|cite";
+lazy_static! {
+    static ref FOOTNOTE_REF: Regex = Regex::new(FOOTNOTE_REFS).unwrap();
+}
+
+/// Find the footnotes referenced from the content, and append them in a
+/// footnotes section.
+///
+/// A footnote reference is a fragment link inside a `sup` element, with
+/// `role="doc-noteref"`, or with a target like `#fn1` or `#note-1`, whose
+/// target is not part of the content. The ids of references and notes are
+/// kept, so links in both directions keep working.
+///
+/// Returns the number of footnotes.
+pub(crate) fn append_footnotes(
+    dom: &mut RcDom,
+    content: &Handle,
+    url: &Url,
+    options: &CleanOptions,
+) -> usize {
+    let content_ids = dom::descendants(content)
+        .filter_map(|node| dom::get_attr(&node, "id"))
+        .collect::<HashSet<_>>();
+    let mut target_ids = vec![];
+
+    for link in dom::find_by_tag(content, "a") {
+        let Some(id) = dom::get_attr(&link, "href")
+            .and_then(|href| href.strip_prefix('#').map(|id| id.to_owned()))
+        else {
+            continue;
+        };
+
+        if id.is_empty() || content_ids.contains(&id) || target_ids.contains(&id) {
+            continue;
+        }
+
+        if is_footnote_ref(&link, &id) {
+            target_ids.push(id);
+        }
+    }
+
+    let notes = target_ids
+        .iter()
+        .filter_map(|id| {
+            dom::find_by_attr(&dom.document, "id", Some(id))
+                .into_iter()
+                .next()
+        })
+        .filter(|note| !contains(note, content))
+        .collect::<Vec<_>>();
+
+    if notes.is_empty() {
+        return 0;
+    }
+
+    let section = create_element(dom, local_name!("section"));
+    let heading = create_element(dom, local_name!("h2"));
+    let list = create_element(dom, local_name!("ol"));
+    dom.append(
+        &heading,
+        NodeOrText::AppendText(StrTendril::from("Footnotes")),
+    );
+    dom.append(&section, NodeOrText::AppendNode(heading));
+    dom.append(&section, NodeOrText::AppendNode(list.clone()));
+
+    let note_ids = target_ids.into_iter().collect::<HashSet<_>>();
+    let mut list_items = 0;
+
+    for note in notes.iter() {
+        dom.remove_from_parent(note);
+        clean_note(dom, note, url, options, &note_ids);
+
+        if dom::is_tag(note, "li") {
+            dom.append(&list, NodeOrText::AppendNode(note.clone()));
+            list_items += 1;
+        } else {
+            dom.append(&section, NodeOrText::AppendNode(note.clone()));
+        }
+    }
+
+    if list_items == 0 {
+        dom.remove_from_parent(&list);
+    }
+
+    dom.append(content, NodeOrText::AppendNode(section));
+
+    notes.len()
+}
+
+fn is_footnote_ref(link: &Handle, id: &str) -> bool {
+    let in_sup = dom::parent(link).is_some_and(|parent| dom::is_tag(&parent, "sup"));
+    let is_noteref = dom::get_attr(link, "role").is_some_and(|role| role.contains("doc-noteref"));
+
+    in_sup || is_noteref || FOOTNOTE_REF.is_match(id)
+}
+
+/// Check if the node is the content or one of its ancestors.
+fn contains(node: &Handle, content: &Handle) -> bool {
+    let mut current = Some(content.clone());
+
+    while let Some(handle) = current {
+        if dom::same_node(&handle, node) {
+            return true;
+        }
+        current = dom::parent(&handle);
+    }
+
+    false
+}
+
+fn create_element(dom: &mut RcDom, name: html5ever::LocalName) -> Handle {
+    dom.create_element(
+        QualName::new(None, ns!(html), name),
+        vec![],
+        ElementFlags::default(),
+    )
+}
+
+/// Clean a footnote, or another block which is appended to the content, without
+/// the heuristics of [`Scorer::clean`](crate::Scorer::clean), which would
+/// remove most of the short notes.
+pub(crate) fn clean_note(
+    dom: &mut RcDom,
+    handle: &Handle,
+    url: &Url,
+    options: &CleanOptions,
+    note_ids: &HashSet<String>,
+) {
+    if let NodeData::Element {
+        ref name,
+        ref attrs,
+        ..
+    } = handle.data
+    {
+        match name.local {
+            local_name!("img") => {
+                fix_img_path(handle, url, options);
+            }
+            local_name!("a") => {
+                fix_anchor_path(handle, url, options);
+            }
+            _ => (),
+        }
+
+        let mut attrs = attrs.borrow_mut();
+        let is_note = html::attr("id", &attrs).is_some_and(|id| note_ids.contains(&id));
+        options.clean_attrs(&mut attrs, is_note);
+    }
+
+    let children = handle.children.borrow().clone();
+
+    for child in children.iter() {
+        match child.data {
+            NodeData::Comment { .. } => dom.remove_from_parent(child),
+            NodeData::Element { ref name, .. }
+                if matches!(
+                    name.local,
+                    local_name!("script") | local_name!("style") | local_name!("noscript")
+                ) =>
+            {
+                dom.remove_from_parent(child)
+            }
+            _ => clean_note(dom, child, url, options, note_ids),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use html5ever::{parse_document, serialize, tendril::TendrilSink};
+
+    #[test]
+    fn test_append_footnotes() {
+        let mut dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(
+                &mut r##"<body>
+                <article><p>Text<sup id="ref-1"><a href="#fn-1">1</a></sup> and
+                    <a href="#top">top</a>.</p></article>
+                <ol class="notes"><li id="fn-1" class="note">Note <a href="#ref-1">↩</a></li></ol>
+                </body>"##
+                    .as_bytes(),
+            )
+            .unwrap();
+        let url = Url::parse("https://example.com").unwrap();
+        let article = dom::find_by_tag(&dom.document, "article")[0].clone();
+
+        let count = append_footnotes(&mut dom, &article, &url, &CleanOptions::default());
+        assert_eq!(count, 1);
+
+        let mut bytes = vec![];
+        serialize(
+            &mut bytes,
+            &dom::SerializableHandle::from(article),
+            Default::default(),
+        )
+        .unwrap();
+        let content = String::from_utf8(bytes).unwrap();
+
+        assert!(content.ends_with(
+            r##"<section><h2>Footnotes</h2><ol><li id="fn-1">Note <a href="#ref-1">↩</a></li></ol></section>"##
+        ));
+        assert!(dom::find_by_class(&dom.document, "notes")[0]
+            .children
+            .borrow()
+            .is_empty());
+    }
+}
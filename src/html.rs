@@ -1,5 +1,5 @@
-use crate::dom::{Handle, Node, NodeData};
-use html5ever::Attribute;
+use crate::dom::{matches_any_tag, matches_tag, Handle, Node, NodeData};
+use html5ever::{local_name, Attribute};
 use std::{rc::Rc, str::FromStr};
 use tendril::StrTendril;
 
@@ -12,7 +12,7 @@ pub fn attr(attr_name: &str, attrs: &[Attribute]) -> Option<String> {
     None
 }
 
-pub fn set_attr(attr_name: &str, value: &str, handle: Handle) {
+pub fn set_attr(attr_name: &str, value: &str, handle: &Handle) {
     if let NodeData::Element {
         name: _, ref attrs, ..
     } = handle.data
@@ -41,14 +41,14 @@ pub fn clean_attr(attr_name: &str, attrs: &mut Vec<Attribute>) {
     }
 }
 
-pub fn get_tag_name(handle: Handle) -> Option<String> {
+pub fn get_tag_name(handle: &Handle) -> Option<&str> {
     match handle.data {
-        NodeData::Element { ref name, .. } => Some(name.local.as_ref().to_lowercase().to_string()),
+        NodeData::Element { ref name, .. } => Some(name.local.as_ref()),
         _ => None,
     }
 }
 
-pub fn get_attr(name: &str, handle: Handle) -> Option<String> {
+pub fn get_attr(name: &str, handle: &Handle) -> Option<String> {
     match handle.data {
         NodeData::Element {
             name: _, ref attrs, ..
@@ -57,58 +57,55 @@ pub fn get_attr(name: &str, handle: Handle) -> Option<String> {
     }
 }
 
-pub fn is_empty(handle: Handle) -> bool {
+pub fn is_empty(handle: &Handle) -> bool {
     for child in handle.children.borrow().iter() {
-        let c = child.clone();
-        match c.data {
-            NodeData::Text { ref contents } => {
-                if contents.borrow().trim().len() > 0 {
-                    return false;
-                }
+        match child.data {
+            NodeData::Text { ref contents } if !contents.borrow().trim().is_empty() => {
+                return false;
             }
-            NodeData::Element { ref name, .. } => {
-                let tag_name = name.local.as_ref();
-                match tag_name.to_lowercase().as_ref() {
-                    "li" | "dt" | "dd" | "p" | "div" => {
-                        if !is_empty(child.clone()) {
-                            return false;
-                        }
+            NodeData::Element { ref name, .. } => match name.local {
+                local_name!("li")
+                | local_name!("dt")
+                | local_name!("dd")
+                | local_name!("p")
+                | local_name!("div") => {
+                    if !is_empty(child) {
+                        return false;
                     }
-                    _ => return false,
                 }
-            }
+                _ => return false,
+            },
             _ => (),
         }
     }
     matches!(
-        get_tag_name(handle.clone()).unwrap_or_default().as_ref(),
+        get_tag_name(handle).unwrap_or_default(),
         "li" | "dt" | "dd" | "p" | "div" | "canvas"
     )
 }
 
 #[allow(dead_code)]
-pub fn has_link(handle: Handle) -> bool {
-    if "a" == &get_tag_name(handle.clone()).unwrap_or_default() {
+pub fn has_link(handle: &Handle) -> bool {
+    if get_tag_name(handle) == Some("a") {
         return true;
     }
     for child in handle.children.borrow().iter() {
-        if has_link(child.clone()) {
+        if has_link(child) {
             return true;
         }
     }
     false
 }
 
-pub fn text_len(handle: Handle) -> usize {
+pub fn text_len(handle: &Handle) -> usize {
     let mut len = 0;
     for child in handle.children.borrow().iter() {
-        let c = child.clone();
-        match c.data {
+        match child.data {
             NodeData::Text { ref contents } => {
                 len += contents.borrow().trim().chars().count();
             }
             NodeData::Element { .. } => {
-                len += text_len(child.clone());
+                len += text_len(child);
             }
             _ => (),
         }
@@ -116,40 +113,49 @@ pub fn text_len(handle: Handle) -> usize {
     len
 }
 
-pub fn find_node(handle: Handle, tag_name: &str, nodes: &mut Vec<Rc<Node>>) {
+/// Like [`text_len`], but measure the trimmed text of each text node with `len`.
+pub fn text_len_with(handle: &Handle, len: &dyn Fn(&str) -> f32) -> f32 {
+    let mut total = 0.0;
     for child in handle.children.borrow().iter() {
-        let c = child.clone();
-        if let NodeData::Element { ref name, .. } = c.data {
-            let t = name.local.as_ref();
-            if t.to_lowercase() == tag_name {
+        match child.data {
+            NodeData::Text { ref contents } => {
+                total += len(contents.borrow().trim());
+            }
+            NodeData::Element { .. } => {
+                total += text_len_with(child, len);
+            }
+            _ => (),
+        }
+    }
+    total
+}
+
+pub fn find_node(handle: &Handle, tag_name: &str, nodes: &mut Vec<Rc<Node>>) {
+    for child in handle.children.borrow().iter() {
+        if let NodeData::Element { ref name, .. } = child.data {
+            if matches_tag(name, tag_name) {
                 nodes.push(child.clone());
             };
-            find_node(child.clone(), tag_name, nodes)
+            find_node(child, tag_name, nodes)
         }
     }
 }
 
-pub fn has_nodes(handle: Handle, tag_names: &[&str]) -> bool {
+pub fn has_nodes(handle: &Handle, tag_names: &[&str]) -> bool {
     for child in handle.children.borrow().iter() {
-        let tag_name: &str = &get_tag_name(child.clone()).unwrap_or_default();
-        if tag_names.iter().any(|&n| n == tag_name) {
-            return true;
-        }
-        if match child.clone().data {
-            NodeData::Element { .. } => has_nodes(child.clone(), tag_names),
-            _ => false,
-        } {
-            return true;
+        if let NodeData::Element { ref name, .. } = child.data {
+            if matches_any_tag(name, tag_names) || has_nodes(child, tag_names) {
+                return true;
+            }
         }
     }
     false
 }
 
-pub fn text_children_count(handle: Handle) -> usize {
+pub fn text_children_count(handle: &Handle) -> usize {
     let mut count = 0;
     for child in handle.children.borrow().iter() {
-        let c = child.clone();
-        if let NodeData::Text { ref contents } = c.data {
+        if let NodeData::Text { ref contents } = child.data {
             let s = contents.borrow();
             if s.trim().len() >= 20 {
                 count += 1
@@ -158,3 +164,21 @@ pub fn text_children_count(handle: Handle) -> usize {
     }
     count
 }
+
+/// Escape text for use in element content or double-quoted attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\u{a0}' => escaped.push_str("&nbsp;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -0,0 +1,117 @@
+//! Collect the images of the content.
+
+use crate::dom::{self, Handle};
+use url::Url;
+
+/// An image of the content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ImageInfo {
+    /// The absolute url of the image.
+    pub url: String,
+    /// The `alt` text of the image.
+    pub alt: Option<String>,
+    /// The `width` attribute in pixels.
+    pub width: Option<u32>,
+    /// The `height` attribute in pixels.
+    pub height: Option<u32>,
+    /// The `figcaption` of the surrounding `figure`.
+    pub caption: Option<String>,
+}
+
+/// Collect the images in document order.
+///
+/// Images without a `src` or with a `src` which can't be resolved against the
+/// url are skipped.
+pub fn collect_images(handle: &Handle, url: &Url) -> Vec<ImageInfo> {
+    dom::find_by_tag(handle, "img")
+        .iter()
+        .filter_map(|img| {
+            let src = dom::get_attr(img, "src")?;
+            let url = url.join(src.trim()).ok()?;
+
+            Some(ImageInfo {
+                url: url.into(),
+                alt: dom::get_attr(img, "alt")
+                    .map(|alt| collapse_whitespace(&alt))
+                    .filter(|alt| !alt.is_empty()),
+                width: dimension(img, "width"),
+                height: dimension(img, "height"),
+                caption: caption(img),
+            })
+        })
+        .collect()
+}
+
+/// Parse a dimension like `640` or `640px`.
+fn dimension(handle: &Handle, name: &str) -> Option<u32> {
+    let value = dom::get_attr(handle, name)?;
+    let value = value.trim();
+    value.strip_suffix("px").unwrap_or(value).parse().ok()
+}
+
+/// Get the caption of the closest `figure` ancestor.
+fn caption(handle: &Handle) -> Option<String> {
+    let mut current = dom::parent(handle);
+
+    while let Some(node) = current {
+        if dom::is_tag(&node, "figure") {
+            return dom::find_by_tag(&node, "figcaption")
+                .first()
+                .map(|figcaption| collapse_whitespace(&dom::text_content(figcaption)))
+                .filter(|caption| !caption.is_empty());
+        }
+        current = dom::parent(&node);
+    }
+
+    None
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    #[test]
+    fn test_collect_images() {
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(
+                &mut r#"<article>
+                <figure><img src="/a.png" alt=" A  cat " width="640px" height="480">
+                    <figcaption>The <em>cat</em></figcaption></figure>
+                <p><img src="//cdn.example.com/b.png" width="100%"><img alt="No src"></p>
+                </article>"#
+                    .as_bytes(),
+            )
+            .unwrap();
+        let url = Url::parse("https://example.com/post/").unwrap();
+
+        let images = collect_images(&dom.document, &url);
+
+        assert_eq!(
+            images,
+            vec![
+                ImageInfo {
+                    url: "https://example.com/a.png".to_owned(),
+                    alt: Some("A cat".to_owned()),
+                    width: Some(640),
+                    height: Some(480),
+                    caption: Some("The cat".to_owned()),
+                },
+                ImageInfo {
+                    url: "https://cdn.example.com/b.png".to_owned(),
+                    alt: None,
+                    width: None,
+                    height: None,
+                    caption: None,
+                },
+            ]
+        );
+    }
+}
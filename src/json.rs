@@ -0,0 +1,106 @@
+//! Serialize the extracted article to JSON, with the field names of the result
+//! of `Readability.parse()` in Readability.js where possible.
+
+use crate::{extractor::Readable, images::ImageInfo, links::LinkInfo};
+use serde::Serialize;
+
+/// The JSON schema of [`Readable::to_json`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonReadable<'a> {
+    title: &'a str,
+    byline: Option<&'a str>,
+    content: String,
+    text_content: &'a str,
+    excerpt: Option<&'a str>,
+    length: usize,
+    site_name: Option<&'a str>,
+    lang: Option<&'a str>,
+    published_time: Option<&'a str>,
+    images: &'a [ImageInfo],
+    links: &'a [LinkInfo],
+}
+
+impl Readable {
+    /// Serialize the article to JSON, which is compatible with the result of
+    /// `Readability.parse()` in Readability.js:
+    ///
+    /// - `title`, the title of the article,
+    /// - `byline`, the author of the article, or `null`,
+    /// - `content`, the HTML of the content,
+    /// - `textContent`, the text of the content,
+    /// - `excerpt`, the description of the article, or the first paragraph of
+    ///   the text,
+    /// - `length`, the number of characters of the text,
+    /// - `siteName`, the name of the website, or `null`,
+    /// - `lang`, the declared language, or `null`,
+    /// - `publishedTime`, the published date as given by the document, or
+    ///   `null`,
+    /// - `images`, the images of the content, see [`ImageInfo`],
+    /// - `links`, the links of the content, see [`LinkInfo`].
+    ///
+    /// Further fields may be added, but existing fields are not renamed or
+    /// removed.
+    pub fn to_json(&self) -> String {
+        let json = JsonReadable {
+            title: &self.title,
+            byline: self.byline.as_deref().or(self.metadata.author.as_deref()),
+            content: self.to_html(),
+            text_content: &self.text,
+            excerpt: self.metadata.excerpt.as_deref().or_else(|| {
+                self.text
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty())
+            }),
+            length: self.text.chars().count(),
+            site_name: self.metadata.site_name.as_deref(),
+            lang: self.lang.as_deref(),
+            published_time: self.metadata.published_time.as_deref(),
+            images: &self.images,
+            links: &self.links,
+        };
+
+        serde_json::to_string(&json).expect("readable can be serialized")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Metadata;
+
+    #[test]
+    fn test_to_json() {
+        let readable = Readable {
+            title: "Title".to_owned(),
+            content: "<p>First paragraph</p><p>Second paragraph</p>".to_owned(),
+            text: "\nFirst paragraph\nSecond paragraph".to_owned(),
+            lang: Some("en".to_owned()),
+            metadata: Metadata {
+                author: Some("Jane Doe".to_owned()),
+                site_name: Some("Example".to_owned()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let json: serde_json::Value = serde_json::from_str(&readable.to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "title": "Title",
+                "byline": "Jane Doe",
+                "content": "<p>First paragraph</p><p>Second paragraph</p>",
+                "textContent": "\nFirst paragraph\nSecond paragraph",
+                "excerpt": "First paragraph",
+                "length": 33,
+                "siteName": "Example",
+                "lang": "en",
+                "publishedTime": null,
+                "images": [],
+                "links": []
+            })
+        );
+    }
+}
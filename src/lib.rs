@@ -1,13 +1,103 @@
-mod dom;
+mod app_shell;
+mod author;
+#[cfg(any(feature = "wasm", feature = "ffi", feature = "python"))]
+mod bindings;
+mod charset;
+mod cleaner;
+mod compression;
+#[cfg(feature = "config")]
+mod config;
+mod corpus;
+mod diff;
+mod document;
+pub mod dom;
+#[cfg(feature = "json")]
+mod embedded;
+#[cfg(feature = "epub")]
+mod epub;
 mod error;
 mod extractor;
+mod features;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod footnotes;
 mod html;
+mod images;
+#[cfg(feature = "json")]
+mod json;
+mod links;
+mod locator;
+mod metadata;
+mod mhtml;
+mod microdata;
+mod microformats;
+mod paywall;
+mod preset;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "json")]
+mod readability_js;
+mod readerable;
+mod render;
+mod scan;
+mod scored;
 mod scorer;
+mod stats;
+mod text;
+mod title;
+mod toc;
+#[cfg(feature = "tuning")]
+mod tuning;
 mod utils;
+#[cfg(feature = "warc")]
+mod warc;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod xhtml;
 
-pub use dom::{RcDom, SerializableHandle};
-pub use error::ReadabilityError;
+pub use author::{AuthorBio, AUTHOR_BIOS};
+pub use cleaner::{
+    CleanDecision, CleanOptions, CleanPredicate, DataUriPolicy, NodeView, UrlKind, UrlRewriter,
+    RELATED_SECTIONS, SHARE_ELEMENTS, SHARE_LINKS, SIGNUP_ELEMENTS, TRACKING_HOSTS,
+    TRACKING_IMAGES, TRACKING_PARAMS,
+};
+#[cfg(feature = "config")]
+pub use config::{ConfigFormat, ExtractConfig};
+pub use corpus::{run_corpus, text_similarity, CorpusReport, PageDiff, PageReport};
+pub use diff::{CleanReason, NodeChange, NodeDiff};
+pub use document::{DocumentOptions, ReaderTheme, READER_STYLESHEET};
+pub use dom::{Handle, Node, NodeData, RcDom, SerializableHandle};
+#[cfg(feature = "epub")]
+pub use epub::{EpubImage, EpubOptions, FetchImage};
+pub use error::{ParseDiagnostic, ReadabilityError, Severity};
 pub use extractor::{
-    extract, extract_content, extract_text, ExtractOptions, ParseOptions, Readable,
+    extract, extract_content, extract_from_bytes, extract_from_str, extract_owned, extract_text,
+    extract_text_with, extract_with_node, Content, ExtractOptions, Extractor, FallbackPolicy,
+    OutputSet, OwnedContent, ParseOptions, Readable,
+};
+pub use features::{NodeClassifier, NodeFeatures};
+pub use images::{collect_images, ImageInfo};
+pub use links::{collect_links, LinkInfo};
+pub use metadata::{extract_metadata, DiscoveryLink, Metadata};
+pub use mhtml::{extract_mhtml, MhtmlResources};
+pub use paywall::{TruncationReason, PAYWALL_ELEMENTS, TRUNCATION_MARKERS};
+pub use preset::Preset;
+#[cfg(feature = "json")]
+pub use readability_js::{run_readability_js_corpus, ReadabilityJsOptions, READABILITY_JS_URL};
+pub use readerable::is_probably_readerable;
+pub use render::{render_content, ContentNode, RenderedContent, SerializeOptions};
+pub use scored::{CandidateView, ScoredDocument};
+pub use scorer::{
+    candidate_regex, is_cjk, CandidatePatterns, CandidateScore, CandidateStats, ClassWeightMode,
+    EarlyTermination, LengthMetric, Scorer, ScorerOptions, BLOCK_CHILD_TAGS, BYLINE_CANDIDATES,
+    LIKELY_CANDIDATES, MIN_CONTENT_HINT_LENGTH, NEGATIVE_CANDIDATES, OVERLAY_ELEMENTS,
+    POSITIVE_CANDIDATES, PUNCTUATIONS_REGEX, TAG_SCORES, UNLIKELY_CANDIDATES,
 };
-pub use scorer::{Scorer, ScorerOptions};
+pub use stats::{text_stats, TextStats};
+pub use text::{normalize_text, render_text, HeadingStyle, NormalizeOptions, TextOptions};
+pub use title::extract_title;
+pub use toc::{table_of_contents, TocEntry, TocOptions};
+#[cfg(feature = "tuning")]
+pub use tuning::{tune_thresholds, TuningGrid, TuningReport};
+#[cfg(feature = "warc")]
+pub use warc::WarcReader;
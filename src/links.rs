@@ -0,0 +1,91 @@
+//! Collect the outbound links of the content.
+
+use crate::dom::{self, Handle};
+use url::Url;
+
+/// A link of the content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LinkInfo {
+    /// The absolute url of the link.
+    pub url: String,
+    /// The anchor text of the link.
+    pub text: String,
+    /// The `rel` attribute of the link, e.g. `nofollow`.
+    pub rel: Option<String>,
+}
+
+/// Collect the links in document order.
+///
+/// Fragment links within the page, e.g. `href="#note-1"`, and links which
+/// can't be resolved against the url, e.g. `javascript:` links, are skipped.
+pub fn collect_links(handle: &Handle, url: &Url) -> Vec<LinkInfo> {
+    dom::find_by_tag(handle, "a")
+        .iter()
+        .filter_map(|link| {
+            let href = dom::get_attr(link, "href")?;
+            let href = href.trim();
+
+            if href.is_empty() || href.starts_with('#') {
+                return None;
+            }
+
+            let url = url.join(href).ok()?;
+
+            if url.scheme() == "javascript" {
+                return None;
+            }
+
+            Some(LinkInfo {
+                url: url.into(),
+                text: dom::text_content(link)
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                rel: dom::get_attr(link, "rel")
+                    .map(|rel| rel.trim().to_owned())
+                    .filter(|rel| !rel.is_empty()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    #[test]
+    fn test_collect_links() {
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(
+                &mut r##"<p>See <a href="/docs" rel="nofollow">the
+                <em>docs</em></a>, <a href="#top">top</a>,
+                <a href="javascript:void(0)">menu</a>, and
+                <a href="https://example.org/">example</a>.</p>"##
+                    .as_bytes(),
+            )
+            .unwrap();
+        let url = Url::parse("https://example.com/post/").unwrap();
+
+        let links = collect_links(&dom.document, &url);
+
+        assert_eq!(
+            links,
+            vec![
+                LinkInfo {
+                    url: "https://example.com/docs".to_owned(),
+                    text: "the docs".to_owned(),
+                    rel: Some("nofollow".to_owned()),
+                },
+                LinkInfo {
+                    url: "https://example.org/".to_owned(),
+                    text: "example".to_owned(),
+                    rel: None,
+                },
+            ]
+        );
+    }
+}
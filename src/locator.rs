@@ -0,0 +1,157 @@
+//! Locate the nodes of a scored document in the original document, e.g. to
+//! highlight the content in a browser.
+
+use crate::dom::{self, Handle, Node, NodeData};
+use std::{collections::HashMap, rc::Weak};
+
+/// The position of an element in the original document.
+struct Location {
+    /// A weak reference to the element, which keeps its address from being
+    /// reused by nodes created during preprocessing, but doesn't keep removed
+    /// elements and their children alive.
+    _node: Weak<Node>,
+    parent: Option<*const Node>,
+    tag: String,
+    /// The position among the elements of the parent, starting at `1`.
+    nth_child: usize,
+}
+
+/// The positions of the elements before preprocessing, which moves, replaces,
+/// and removes nodes.
+#[derive(Default)]
+pub(crate) struct Locators {
+    locations: HashMap<*const Node, Location>,
+}
+
+impl Locators {
+    pub(crate) fn new(document: &Handle) -> Self {
+        let mut locators = Self::default();
+        locators.collect(document, None);
+
+        locators
+    }
+
+    /// Collect the positions if `enabled`, or return empty locators, which
+    /// locate no nodes.
+    pub(crate) fn collect_if(enabled: bool, document: &Handle) -> Self {
+        if enabled {
+            Self::new(document)
+        } else {
+            Self::default()
+        }
+    }
+
+    fn collect(&mut self, handle: &Handle, parent: Option<*const Node>) {
+        let mut nth_child = 0;
+
+        for child in handle.children.borrow().iter() {
+            let NodeData::Element { ref name, .. } = child.data else {
+                continue;
+            };
+            nth_child += 1;
+
+            let key = key(child);
+            self.locations.insert(
+                key,
+                Location {
+                    _node: Handle::downgrade(child),
+                    parent,
+                    tag: name.local.to_string(),
+                    nth_child,
+                },
+            );
+            self.collect(child, Some(key));
+        }
+    }
+
+    /// The CSS selector of an element in the original document, i.e. a chain
+    /// of `nth-child` selectors like `html > body:nth-child(2) >
+    /// div:nth-child(1)`, or `None` if the node isn't an element of the
+    /// original document.
+    pub(crate) fn selector(&self, handle: &Handle) -> Option<String> {
+        let mut location = self.locations.get(&key(handle))?;
+        let mut steps = vec![];
+
+        loop {
+            match location
+                .parent
+                .and_then(|parent| self.locations.get(&parent))
+            {
+                Some(parent) => {
+                    steps.push(format!(
+                        "{}:nth-child({})",
+                        location.tag, location.nth_child
+                    ));
+                    location = parent;
+                }
+                None => {
+                    steps.push(location.tag.clone());
+                    break;
+                }
+            }
+        }
+        steps.reverse();
+
+        Some(steps.join(" > "))
+    }
+
+    /// The selector of the node, or of its closest ancestor in the original
+    /// document if the node was created during preprocessing, e.g. a
+    /// paragraph converted from a `div`.
+    pub(crate) fn closest_selector(&self, handle: &Handle) -> Option<String> {
+        let mut node = Some(handle.clone());
+
+        while let Some(handle) = node {
+            if let Some(selector) = self.selector(&handle) {
+                return Some(selector);
+            }
+            node = dom::parent(&handle);
+        }
+
+        None
+    }
+}
+
+fn key(handle: &Handle) -> *const Node {
+    Handle::as_ptr(handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    #[test]
+    fn test_selector() {
+        let dom = parse_document(RcDom::default(), Default::default())
+            .one("<p>First</p>text<div><p>Second</p><span>Third</span></div>");
+        let locators = Locators::new(&dom.document);
+
+        let spans = dom::find_by_tag(&dom.document, "span");
+        assert_eq!(
+            locators.selector(&spans[0]).as_deref(),
+            Some("html > body:nth-child(2) > div:nth-child(2) > span:nth-child(2)")
+        );
+
+        // The selector is the position before preprocessing, e.g. before a
+        // sibling is removed.
+        let paragraphs = dom::find_by_tag(&dom.document, "p");
+        let body = dom::find_by_tag(&dom.document, "body");
+        body[0]
+            .children
+            .borrow_mut()
+            .retain(|child| !dom::same_node(child, &paragraphs[0]));
+        assert_eq!(
+            locators.selector(&paragraphs[1]).as_deref(),
+            Some("html > body:nth-child(2) > div:nth-child(2) > p:nth-child(1)")
+        );
+
+        let text = body[0].children.borrow()[0].clone();
+        assert_eq!(locators.selector(&text), None);
+        assert_eq!(
+            locators.closest_selector(&text).as_deref(),
+            Some("html > body:nth-child(2)")
+        );
+    }
+}
@@ -0,0 +1,493 @@
+//! Collect the metadata of the document, which is not part of the content.
+
+use crate::{
+    charset, compression,
+    dom::{self, Handle},
+    error::ReadabilityError,
+    extractor::check_html,
+    microdata, microformats,
+    scan::scan,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+lazy_static! {
+    static ref COMMENT_COUNT_META: Regex =
+        Regex::new(r"(?i)^((og|article):)?comments?[_-]?count$").unwrap();
+    static ref COMMENT_COUNT_LINK: Regex = Regex::new(
+        r"(?i)^(\d[\d,.]*)\s+(comments?|replies|responses)$|^(comments?|replies)\s*\((\d[\d,.]*)\)$"
+    )
+    .unwrap();
+    static ref COMMENT_COUNT_JSON: Regex = Regex::new(r#""commentCount"\s*:\s*"?(\d+)"?"#).unwrap();
+    static ref DISCUSSION_URL_JSON: Regex =
+        Regex::new(r#""discussionUrl"\s*:\s*"([^"]+)""#).unwrap();
+}
+
+/// The metadata of the document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Metadata {
+    /// The number of comments, even if the comments are removed from the
+    /// content.
+    ///
+    /// The count is taken from schema.org `commentCount` in microdata or
+    /// JSON-LD, `comment_count` meta tags, or links like "123 comments".
+    pub comment_count: Option<u64>,
+    /// The absolute url of the comments, from schema.org `discussionUrl` or
+    /// the link of the comment count.
+    pub discussion_url: Option<String>,
+    /// The title of the article from the `h-entry` or `hentry` microformats,
+    /// or the schema.org `headline` microdata.
+    pub title: Option<String>,
+    /// The author of the article from the `h-entry` or `hentry` microformats,
+    /// or the schema.org `author` microdata.
+    pub author: Option<String>,
+    /// The published date of the article as given by the document, e.g.
+    /// `2024-05-01T10:00:00Z`, from the `h-entry` or `hentry` microformats,
+    /// or the schema.org `datePublished` microdata.
+    pub published_time: Option<String>,
+    /// The summary of the article from the `description`, `og:description`,
+    /// or `twitter:description` meta tags.
+    pub excerpt: Option<String>,
+    /// The name of the website from the `og:site_name` or `application-name`
+    /// meta tags.
+    pub site_name: Option<String>,
+    /// The RSS, Atom, and JSON feeds of the document, from
+    /// `<link rel="alternate">` elements.
+    pub feeds: Vec<DiscoveryLink>,
+    /// The oEmbed endpoints of the document, from `<link rel="alternate">`
+    /// elements with the type `application/json+oembed` or `text/xml+oembed`.
+    pub oembed: Vec<DiscoveryLink>,
+    /// The absolute url of the largest icon, from `<link rel="icon">` or
+    /// `<link rel="apple-touch-icon">` elements.
+    pub icon: Option<String>,
+}
+
+/// A `<link rel="alternate">` element, which links to another representation
+/// of the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DiscoveryLink {
+    /// The absolute url of the link.
+    pub url: String,
+    /// The lowercase media type of the link, e.g. `application/rss+xml`.
+    pub media_type: String,
+    /// The title of the link, if any.
+    pub title: Option<String>,
+}
+
+/// The media types of feeds.
+const FEED_TYPES: &[&str] = &[
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+];
+/// The media types of oEmbed endpoints.
+const OEMBED_TYPES: &[&str] = &["application/json+oembed", "text/xml+oembed"];
+/// The size of Apple touch icons without `sizes`, which are 180x180 pixels
+/// by convention.
+const APPLE_TOUCH_ICON_SIZE: u32 = 180;
+
+/// Extract the metadata from HTML bytes without extracting the content.
+///
+/// The input is only tokenized, and just the `head` and the JSON-LD scripts of
+/// the `body` are kept, so the metadata of microformats, microdata, and links
+/// to comments in the `body` is missing. This is much cheaper than a full
+/// extraction, e.g. to generate link previews.
+///
+/// The input is decoded and decompressed like by
+/// [`extract_from_bytes`](crate::extract_from_bytes).
+pub fn extract_metadata(bytes: &[u8], url: &Url) -> Result<Metadata, ReadabilityError> {
+    let bytes = compression::decompress(bytes)?;
+    check_html(&bytes)?;
+    let html = charset::decode(&bytes);
+
+    // Only the `head` and the JSON-LD scripts are kept, without building the
+    // DOM of the `body`.
+    let dom = scan(&html, |_| false).to_dom();
+
+    Ok(document_metadata(&dom.document, url))
+}
+
+/// Collect the metadata, which must be called before preprocessing removes
+/// scripts, links, and unlikely candidates.
+pub(crate) fn document_metadata(document: &Handle, url: &Url) -> Metadata {
+    let (comment_count, discussion_url) = comments(document, url);
+    let entry = microformats::entry(document).unwrap_or_default();
+    let article = microdata::article(document).unwrap_or_default();
+    let (feeds, oembed) = discovery_links(document, url)
+        .into_iter()
+        .filter(|link| {
+            FEED_TYPES.contains(&link.media_type.as_str())
+                || OEMBED_TYPES.contains(&link.media_type.as_str())
+        })
+        .partition(|link| FEED_TYPES.contains(&link.media_type.as_str()));
+
+    Metadata {
+        comment_count,
+        discussion_url,
+        title: entry.title.or(article.headline),
+        author: entry.author.or(article.author),
+        published_time: entry
+            .published
+            .or(article.published)
+            .or_else(|| meta_content(document, &["article:published_time"])),
+        excerpt: meta_content(
+            document,
+            &["description", "og:description", "twitter:description"],
+        ),
+        site_name: meta_content(document, &["og:site_name", "application-name"]),
+        feeds,
+        oembed,
+        icon: icon(document, url),
+    }
+}
+
+/// Find the url of the largest icon, where scalable icons with the size `any`
+/// are preferred, and icons without `sizes` are considered the smallest.
+fn icon(document: &Handle, url: &Url) -> Option<String> {
+    let mut icon: Option<(u32, Url)> = None;
+
+    for link in dom::find_by_tag(document, "link") {
+        let Some(rel) = dom::get_attr(&link, "rel").map(|rel| rel.to_ascii_lowercase()) else {
+            continue;
+        };
+        let is_touch_icon = rel
+            .split_ascii_whitespace()
+            .any(|rel| rel.starts_with("apple-touch-icon"));
+        if !is_touch_icon && !rel.split_ascii_whitespace().any(|rel| rel == "icon") {
+            continue;
+        }
+        let Some(href) = dom::get_attr(&link, "href").and_then(|href| url.join(href.trim()).ok())
+        else {
+            continue;
+        };
+
+        let default_size = if is_touch_icon {
+            APPLE_TOUCH_ICON_SIZE
+        } else {
+            0
+        };
+        let size = dom::get_attr(&link, "sizes")
+            .and_then(|sizes| icon_size(&sizes))
+            .unwrap_or(default_size);
+
+        if icon.as_ref().is_none_or(|(max_size, _)| size > *max_size) {
+            icon = Some((size, href));
+        }
+    }
+
+    icon.map(|(_, href)| href.into())
+}
+
+/// Parse the largest size of `sizes` like `16x16 32x32` or `any`.
+fn icon_size(sizes: &str) -> Option<u32> {
+    sizes
+        .split_ascii_whitespace()
+        .filter_map(|size| {
+            if size.eq_ignore_ascii_case("any") {
+                return Some(u32::MAX);
+            }
+            let (width, height) = size.split_once(['x', 'X'])?;
+            Some(width.parse::<u32>().ok()?.max(height.parse().ok()?))
+        })
+        .max()
+}
+
+/// Collect the `<link rel="alternate">` elements with a media type, without
+/// duplicate urls.
+fn discovery_links(document: &Handle, url: &Url) -> Vec<DiscoveryLink> {
+    let mut links: Vec<DiscoveryLink> = vec![];
+
+    for link in dom::find_by_tag(document, "link") {
+        let is_alternate = dom::get_attr(&link, "rel").is_some_and(|rel| {
+            rel.split_ascii_whitespace()
+                .any(|rel| rel.eq_ignore_ascii_case("alternate"))
+        });
+        if !is_alternate {
+            continue;
+        }
+        let (Some(media_type), Some(href)) =
+            (dom::get_attr(&link, "type"), dom::get_attr(&link, "href"))
+        else {
+            continue;
+        };
+        let Ok(href) = url.join(href.trim()) else {
+            continue;
+        };
+        if links.iter().any(|link| link.url == href.as_str()) {
+            continue;
+        }
+
+        links.push(DiscoveryLink {
+            url: href.into(),
+            media_type: media_type.trim().to_ascii_lowercase(),
+            title: dom::get_attr(&link, "title")
+                .map(|title| title.trim().to_owned())
+                .filter(|title| !title.is_empty()),
+        });
+    }
+
+    links
+}
+
+/// Find the comment count and the discussion url.
+fn comments(document: &Handle, url: &Url) -> (Option<u64>, Option<String>) {
+    let json_ld = json_ld(document);
+    let mut comment_count = itemprop(document, "commentCount")
+        .and_then(|count| parse_count(&count))
+        .or_else(|| {
+            dom::find_by_tag(document, "meta").iter().find_map(|meta| {
+                ["name", "property"]
+                    .iter()
+                    .filter_map(|name| dom::get_attr(meta, name))
+                    .any(|name| COMMENT_COUNT_META.is_match(name.trim()))
+                    .then(|| dom::get_attr(meta, "content"))
+                    .flatten()
+                    .and_then(|count| parse_count(&count))
+            })
+        })
+        .or_else(|| {
+            json_ld.iter().find_map(|json| {
+                COMMENT_COUNT_JSON
+                    .captures(json)
+                    .and_then(|captures| parse_count(&captures[1]))
+            })
+        });
+    let mut discussion_url = itemprop(document, "discussionUrl")
+        .or_else(|| {
+            json_ld.iter().find_map(|json| {
+                DISCUSSION_URL_JSON
+                    .captures(json)
+                    .map(|captures| captures[1].to_owned())
+            })
+        })
+        .and_then(|href| url.join(href.trim()).ok())
+        .map(String::from);
+
+    if comment_count.is_none() || discussion_url.is_none() {
+        for link in dom::find_by_tag(document, "a") {
+            let text = dom::text_content(&link)
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let Some(captures) = COMMENT_COUNT_LINK.captures(&text) else {
+                continue;
+            };
+            let Some(count) = captures
+                .get(1)
+                .or_else(|| captures.get(4))
+                .and_then(|count| parse_count(count.as_str()))
+            else {
+                continue;
+            };
+
+            comment_count.get_or_insert(count);
+            if discussion_url.is_none() {
+                discussion_url = dom::get_attr(&link, "href")
+                    .and_then(|href| url.join(href.trim()).ok())
+                    .map(String::from);
+            }
+            break;
+        }
+    }
+
+    (comment_count, discussion_url)
+}
+
+/// Get the value of the first element with the given `itemprop`, i.e. its
+/// `content`, `href`, or text.
+fn itemprop(document: &Handle, name: &str) -> Option<String> {
+    dom::find_by_attr(document, "itemprop", Some(name))
+        .first()
+        .map(|node| {
+            dom::get_attr(node, "content")
+                .or_else(|| dom::get_attr(node, "href"))
+                .unwrap_or_else(|| dom::text_content(node))
+        })
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
+
+/// Get the content of the first meta tag with one of the given names or
+/// properties, in the order of the names.
+fn meta_content(document: &Handle, names: &[&str]) -> Option<String> {
+    let metas = dom::find_by_tag(document, "meta");
+
+    names.iter().find_map(|name| {
+        metas.iter().find_map(|meta| {
+            ["name", "property"]
+                .iter()
+                .filter_map(|attr| dom::get_attr(meta, attr))
+                .any(|value| value.trim().eq_ignore_ascii_case(name))
+                .then(|| dom::get_attr(meta, "content"))
+                .flatten()
+                .map(|content| content.trim().to_owned())
+                .filter(|content| !content.is_empty())
+        })
+    })
+}
+
+/// Get the content of the JSON-LD scripts.
+fn json_ld(document: &Handle) -> Vec<String> {
+    dom::find_by_tag(document, "script")
+        .iter()
+        .filter(|script| {
+            dom::get_attr(script, "type")
+                .is_some_and(|value| value.trim().eq_ignore_ascii_case("application/ld+json"))
+        })
+        .map(dom::text_content)
+        .collect()
+}
+
+/// Parse a count like `1,234`.
+fn parse_count(count: &str) -> Option<u64> {
+    count.trim().replace([',', '.'], "").parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    fn parse(html: &str) -> RcDom {
+        parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_extract_metadata() {
+        let url = Url::parse("https://example.com/post/").unwrap();
+        let html = format!(
+            r#"<html><head><meta property="og:site_name" content="Example">
+            <meta name="description" content="The summary"></head>
+            <body><p>{}</p>
+            <div class="h-entry"><h1 class="p-name">Entry</h1></div>
+            <script type="application/ld+json">{{"commentCount": 12}}</script></body></html>"#,
+            "Text ".repeat(10_000)
+        );
+
+        let metadata = extract_metadata(html.as_bytes(), &url).unwrap();
+        assert_eq!(metadata.site_name.as_deref(), Some("Example"));
+        assert_eq!(metadata.excerpt.as_deref(), Some("The summary"));
+        assert_eq!(metadata.comment_count, Some(12));
+        // The microformats of the `body` are skipped.
+        assert_eq!(metadata.title, None);
+    }
+
+    #[test]
+    fn test_comments() {
+        let url = Url::parse("https://example.com/post/").unwrap();
+
+        let dom = parse(r##"<p>Text</p><a href="#comments">1,234 comments</a>"##);
+        assert_eq!(
+            document_metadata(&dom.document, &url),
+            Metadata {
+                comment_count: Some(1234),
+                discussion_url: Some("https://example.com/post/#comments".to_owned()),
+                ..Default::default()
+            }
+        );
+
+        let dom = parse(
+            r#"<script type="application/ld+json">
+            {"@type": "Article", "commentCount": 12, "discussionUrl": "/post/comments"}
+            </script><a href="/forum">Comments (3)</a>"#,
+        );
+        let metadata = document_metadata(&dom.document, &url);
+        assert_eq!(metadata.comment_count, Some(12));
+        assert_eq!(
+            metadata.discussion_url.as_deref(),
+            Some("https://example.com/post/comments")
+        );
+
+        let dom = parse(r#"<meta itemprop="commentCount" content="7"><a href="/">Comments</a>"#);
+        let metadata = document_metadata(&dom.document, &url);
+        assert_eq!(metadata.comment_count, Some(7));
+        assert_eq!(metadata.discussion_url, None);
+    }
+
+    #[test]
+    fn test_discovery_links() {
+        let url = Url::parse("https://example.com/post/").unwrap();
+        let dom = parse(
+            r#"<head>
+            <link rel="alternate" type="application/rss+xml" title="Feed" href="/feed.xml">
+            <link rel="alternate" type="application/atom+xml" href="/feed.xml">
+            <link rel="alternate" type="Application/JSON+oEmbed"
+                href="/oembed?url=https%3A%2F%2Fexample.com%2Fpost%2F">
+            <link rel="alternate" hreflang="de" href="/de/post/">
+            <link rel="stylesheet" type="text/css" href="/style.css">
+            </head>"#,
+        );
+
+        let metadata = document_metadata(&dom.document, &url);
+
+        assert_eq!(
+            metadata.feeds,
+            vec![DiscoveryLink {
+                url: "https://example.com/feed.xml".to_owned(),
+                media_type: "application/rss+xml".to_owned(),
+                title: Some("Feed".to_owned()),
+            }]
+        );
+        assert_eq!(metadata.oembed.len(), 1);
+        assert_eq!(metadata.oembed[0].media_type, "application/json+oembed");
+    }
+
+    #[test]
+    fn test_icon() {
+        let url = Url::parse("https://example.com/post/").unwrap();
+
+        let dom = parse(
+            r#"<head>
+            <link rel="shortcut icon" href="/favicon.ico">
+            <link rel="icon" sizes="16x16 32x32" href="/favicon.png">
+            <link rel="apple-touch-icon" href="touch-icon.png">
+            <link rel="icon" sizes="96x96" href="/icon-96.png">
+            </head>"#,
+        );
+        assert_eq!(
+            document_metadata(&dom.document, &url).icon.as_deref(),
+            Some("https://example.com/post/touch-icon.png")
+        );
+
+        let dom = parse(
+            r#"<head><link rel="icon" href="/favicon.ico">
+            <link rel="icon" sizes="any" type="image/svg+xml" href="/icon.svg"></head>"#,
+        );
+        assert_eq!(
+            document_metadata(&dom.document, &url).icon.as_deref(),
+            Some("https://example.com/icon.svg")
+        );
+
+        let dom = parse(r#"<head><link rel="mask-icon" href="/mask.svg"></head>"#);
+        assert_eq!(document_metadata(&dom.document, &url).icon, None);
+    }
+
+    #[test]
+    fn test_meta_content() {
+        let url = Url::parse("https://example.com").unwrap();
+        let dom = parse(
+            r#"<head>
+            <meta property="og:description" content="Open Graph description">
+            <meta name="description" content=" Description ">
+            <meta property="og:site_name" content="">
+            <meta name="application-name" content="Example">
+            <meta property="article:published_time" content="2024-05-01T10:00:00Z">
+            </head>"#,
+        );
+        let metadata = document_metadata(&dom.document, &url);
+
+        assert_eq!(metadata.excerpt.as_deref(), Some("Description"));
+        assert_eq!(metadata.site_name.as_deref(), Some("Example"));
+        assert_eq!(
+            metadata.published_time.as_deref(),
+            Some("2024-05-01T10:00:00Z")
+        );
+    }
+}
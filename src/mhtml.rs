@@ -0,0 +1,416 @@
+//! Extract the content of MHTML archives, i.e. web pages which were saved as a
+//! `multipart/related` MIME message along with their resources.
+
+use crate::{
+    charset,
+    error::ReadabilityError,
+    extractor::{extract_from_str, ExtractOptions, Readable},
+};
+use encoding_rs::Encoding;
+use std::borrow::Cow;
+use url::Url;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// How to resolve the urls of the resources in an MHTML archive, e.g. images
+/// referenced by `cid:` urls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MhtmlResources {
+    /// Replace `cid:` urls by the original url of the resource, or by a
+    /// `data:` url if the original url is unknown.
+    #[default]
+    Original,
+    /// Replace `cid:` urls and the original urls of the resources by `data:`
+    /// urls, so the content doesn't depend on the original resources.
+    Inline,
+}
+
+/// A part of a MIME message with decoded body.
+struct Part {
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Part {
+    fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, name)
+    }
+
+    fn content_type(&self) -> String {
+        self.header("content-type")
+            .and_then(|content_type| content_type.split(';').next())
+            .map(|media_type| media_type.trim().to_ascii_lowercase())
+            .unwrap_or_else(|| "text/plain".to_owned())
+    }
+
+    /// The `Content-ID` without angle brackets.
+    fn content_id(&self) -> Option<&str> {
+        self.header("content-id")
+            .map(|id| id.trim().trim_start_matches('<').trim_end_matches('>'))
+    }
+
+    fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.content_type(), base64(&self.body))
+    }
+}
+
+/// Extract content from an MHTML archive.
+///
+/// The root HTML part is extracted with its `Content-Location` as base url,
+/// and the urls of the other parts are resolved as configured.
+pub fn extract_mhtml(
+    bytes: &[u8],
+    opts: ExtractOptions,
+    resources: MhtmlResources,
+) -> Result<Readable, ReadabilityError> {
+    let (headers, body) = split_message(bytes);
+    let headers = parse_headers(headers);
+    let content_type = header(&headers, "content-type")
+        .filter(|content_type| {
+            content_type
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("multipart/")
+        })
+        .ok_or_else(|| ReadabilityError::ParseMhtml("not a multipart message".to_owned()))?;
+    let boundary = param(content_type, "boundary")
+        .ok_or_else(|| ReadabilityError::ParseMhtml("missing boundary".to_owned()))?;
+    let start = param(content_type, "start").map(|start| {
+        start
+            .trim_start_matches('<')
+            .trim_end_matches('>')
+            .to_owned()
+    });
+
+    let parts = split_parts(body, &boundary)
+        .into_iter()
+        .map(|part| {
+            let (headers, body) = split_message(part);
+            let headers = parse_headers(headers);
+            let body = match header(&headers, "content-transfer-encoding")
+                .map(|encoding| encoding.trim().to_ascii_lowercase())
+                .as_deref()
+            {
+                Some("base64") => decode_base64(body),
+                Some("quoted-printable") => decode_quoted_printable(body),
+                _ => body.to_vec(),
+            };
+
+            Part { headers, body }
+        })
+        .collect::<Vec<_>>();
+
+    let root_index = start
+        .and_then(|start| {
+            parts
+                .iter()
+                .position(|part| part.content_id() == Some(start.as_str()))
+        })
+        .or_else(|| {
+            parts
+                .iter()
+                .position(|part| part.content_type() == "text/html")
+        })
+        .ok_or_else(|| ReadabilityError::ParseMhtml("missing HTML part".to_owned()))?;
+    let root = &parts[root_index];
+
+    let location = root
+        .header("content-location")
+        .or_else(|| header(&headers, "snapshot-content-location"))
+        .ok_or_else(|| ReadabilityError::ParseMhtml("missing Content-Location".to_owned()))?;
+    let url = Url::parse(location.trim())?;
+
+    let encoding = root
+        .header("content-type")
+        .and_then(|content_type| param(content_type, "charset"))
+        .and_then(|label| Encoding::for_label(label.as_bytes()));
+    let mut html = match encoding {
+        Some(encoding) => encoding.decode(&root.body).0,
+        None => charset::decode(&root.body),
+    }
+    .into_owned();
+
+    for (index, part) in parts.iter().enumerate() {
+        if index == root_index {
+            continue;
+        }
+
+        let location = part.header("content-location").map(str::trim);
+        let target = match (resources, location) {
+            (MhtmlResources::Original, Some(location)) => Cow::Borrowed(location),
+            _ => Cow::Owned(part.data_url()),
+        };
+
+        if let Some(id) = part.content_id() {
+            html = replace_url(&html, &format!("cid:{id}"), &target);
+        }
+        if let (MhtmlResources::Inline, Some(location)) = (resources, location) {
+            html = replace_url(&html, location, &target);
+        }
+    }
+
+    extract_from_str(&html, &url, opts)
+}
+
+/// Split a message into its headers and body at the first blank line.
+fn split_message(bytes: &[u8]) -> (&[u8], &[u8]) {
+    let crlf = find(bytes, b"\r\n\r\n").map(|index| (index, 4));
+    let lf = find(bytes, b"\n\n").map(|index| (index, 2));
+    let separator = match (crlf, lf) {
+        (Some(crlf), Some(lf)) => Some(if crlf.0 < lf.0 { crlf } else { lf }),
+        (crlf, lf) => crlf.or(lf),
+    };
+
+    match separator {
+        Some((index, len)) => (&bytes[..index], &bytes[index + len..]),
+        None => (bytes, &[]),
+    }
+}
+
+/// Parse the headers of a message, unfolding continuation lines.
+fn parse_headers(bytes: &[u8]) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = vec![];
+
+    for line in String::from_utf8_lossy(bytes).lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_owned()));
+        }
+    }
+
+    headers
+}
+
+fn header<'h>(headers: &'h [(String, String)], name: &str) -> Option<&'h str> {
+    headers
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Get a parameter of a header value, e.g. the `boundary` of a content type.
+fn param(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().trim_matches('"').to_owned())
+    })
+}
+
+/// Split the body of a multipart message into its parts.
+fn split_parts<'b>(body: &'b [u8], boundary: &str) -> Vec<&'b [u8]> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = vec![];
+    let mut part_start = None;
+    let mut position = 0;
+
+    while position < body.len() {
+        let line_end = find(&body[position..], b"\n").map_or(body.len(), |end| position + end + 1);
+        let line = trim_line_end(&body[position..line_end]);
+
+        if let Some(rest) = line.strip_prefix(delimiter.as_bytes()) {
+            if let Some(start) = part_start {
+                // The line break before the delimiter belongs to the delimiter.
+                parts.push(trim_line_end(&body[start..position]));
+            }
+            if rest.starts_with(b"--") {
+                break;
+            }
+            part_start = Some(line_end);
+        }
+
+        position = line_end;
+    }
+
+    parts
+}
+
+fn trim_line_end(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Replace a url in attribute values and CSS `url()` values.
+fn replace_url(html: &str, from: &str, to: &str) -> String {
+    let mut html = html.to_owned();
+    let escaped = from.replace('&', "&amp;");
+
+    for from in [from, escaped.as_str()] {
+        for (open, close) in [("\"", "\""), ("'", "'"), ("(", ")")] {
+            html = html.replace(
+                &format!("{open}{from}{close}"),
+                &format!("{open}{to}{close}"),
+            );
+        }
+    }
+
+    html
+}
+
+fn decode_quoted_printable(bytes: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'=' if bytes[index + 1..].starts_with(b"\r\n") => index += 3,
+            b'=' if bytes[index + 1..].starts_with(b"\n") => index += 2,
+            b'=' => {
+                let byte = bytes
+                    .get(index + 1..index + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+                match byte {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        index += 3;
+                    }
+                    None => {
+                        decoded.push(b'=');
+                        index += 1;
+                    }
+                }
+            }
+            byte => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    decoded
+}
+
+fn decode_base64(bytes: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(bytes.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for byte in bytes {
+        let Some(value) = BASE64_ALPHABET.iter().position(|c| c == byte) else {
+            // Skip line breaks and padding.
+            continue;
+        };
+
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+
+    decoded
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let buffer = chunk.iter().enumerate().fold(0u32, |buffer, (i, byte)| {
+            buffer | (*byte as u32) << (16 - 8 * i)
+        });
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[(buffer >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+fn find(bytes: &[u8], pattern: &[u8]) -> Option<usize> {
+    bytes
+        .windows(pattern.len())
+        .position(|window| window == pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MHTML: &str = concat!(
+        "From: <Saved by Blink>\r\n",
+        "Snapshot-Content-Location: https://example.com/article\r\n",
+        "Subject: Article\r\n",
+        "MIME-Version: 1.0\r\n",
+        "Content-Type: multipart/related;\r\n",
+        "\ttype=\"text/html\";\r\n",
+        "\tboundary=\"----MultipartBoundary--abc----\"\r\n",
+        "\r\n",
+        "------MultipartBoundary--abc----\r\n",
+        "Content-Type: text/html\r\n",
+        "Content-ID: <frame-1@mhtml.blink>\r\n",
+        "Content-Transfer-Encoding: quoted-printable\r\n",
+        "Content-Location: https://example.com/article\r\n",
+        "\r\n",
+        "<html><head><title>Article</title></head><body><article>\r\n",
+        "<p>This is a paragraph of the article, with a comma, and more commas, and=\r\n",
+        " more text.</p><img src=3D\"cid:image-1@mhtml.blink\"><img src=3D\"/b.png\">\r\n",
+        "</article></body></html>\r\n",
+        "------MultipartBoundary--abc----\r\n",
+        "Content-Type: image/png\r\n",
+        "Content-ID: <image-1@mhtml.blink>\r\n",
+        "Content-Transfer-Encoding: base64\r\n",
+        "Content-Location: https://example.com/a.png\r\n",
+        "\r\n",
+        "iVBORw0=\r\n",
+        "------MultipartBoundary--abc----\r\n",
+        "Content-Type: image/png\r\n",
+        "Content-Transfer-Encoding: base64\r\n",
+        "Content-Location: https://example.com/b.png\r\n",
+        "\r\n",
+        "iVBO\r\n",
+        "------MultipartBoundary--abc------\r\n",
+    );
+
+    #[test]
+    fn test_extract_mhtml() {
+        let readable = extract_mhtml(
+            MHTML.as_bytes(),
+            Default::default(),
+            MhtmlResources::Original,
+        )
+        .unwrap();
+
+        assert_eq!(readable.title, "Article");
+        assert!(readable.text.contains("and more text."));
+        assert!(readable.content.contains(
+            r#"<img src="https://example.com/a.png"><img src="https://example.com/b.png">"#
+        ));
+
+        let readable =
+            extract_mhtml(MHTML.as_bytes(), Default::default(), MhtmlResources::Inline).unwrap();
+
+        assert!(readable.content.contains(
+            r#"<img src="data:image/png;base64,iVBORw0="><img src="https://example.com/b.png">"#
+        ));
+
+        let result = extract_mhtml(
+            b"<html></html>",
+            Default::default(),
+            MhtmlResources::Original,
+        );
+        assert!(matches!(result, Err(ReadabilityError::ParseMhtml(_))));
+    }
+
+    #[test]
+    fn test_base64() {
+        for bytes in [&b""[..], b"a", b"ab", b"abc", b"\x89PNG\r\n"] {
+            assert_eq!(decode_base64(base64(bytes).as_bytes()), bytes);
+        }
+        assert_eq!(base64(b"ab"), "YWI=");
+    }
+}
@@ -0,0 +1,180 @@
+//! Parse schema.org microdata (`itemscope` and `itemprop`) of articles.
+
+use crate::dom::{self, Handle};
+
+/// The schema.org types of articles, e.g. `https://schema.org/NewsArticle`.
+const ARTICLE_TYPES: &[&str] = &[
+    "Article",
+    "AdvertiserContentArticle",
+    "AnalysisNewsArticle",
+    "BackgroundNewsArticle",
+    "BlogPosting",
+    "LiveBlogPosting",
+    "NewsArticle",
+    "OpinionNewsArticle",
+    "Report",
+    "ReportageNewsArticle",
+    "ReviewNewsArticle",
+    "SatiricalArticle",
+    "ScholarlyArticle",
+    "SocialMediaPosting",
+    "TechArticle",
+];
+
+/// The properties of an article item.
+#[derive(Debug, Default)]
+pub(crate) struct Article {
+    /// The `headline` or `name`.
+    pub headline: Option<String>,
+    /// The `author`, i.e. the `name` of a nested person or organization.
+    pub author: Option<String>,
+    /// The `datePublished`.
+    pub published: Option<String>,
+}
+
+/// Find the articles of the document, i.e. the outermost items with an
+/// article type.
+fn find_articles(handle: &Handle) -> Vec<Handle> {
+    let mut articles = vec![];
+
+    for child in handle.children.borrow().iter() {
+        if is_article(child) {
+            articles.push(child.clone());
+        } else {
+            articles.extend(find_articles(child));
+        }
+    }
+
+    articles
+}
+
+fn is_article(handle: &Handle) -> bool {
+    dom::get_attr(handle, "itemscope").is_some()
+        && dom::get_attr(handle, "itemtype").is_some_and(|itemtype| {
+            itemtype.split_ascii_whitespace().any(|itemtype| {
+                itemtype
+                    .trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .is_some_and(|name| ARTICLE_TYPES.contains(&name))
+            })
+        })
+}
+
+/// Parse the first article of the document.
+pub(crate) fn article(document: &Handle) -> Option<Article> {
+    let article = find_articles(document).into_iter().next()?;
+
+    Some(Article {
+        headline: find_property(&article, "headline")
+            .or_else(|| find_property(&article, "name"))
+            .and_then(|node| value(&node)),
+        author: find_property(&article, "author").and_then(|node| author(&node)),
+        published: find_property(&article, "datePublished").and_then(|node| value(&node)),
+    })
+}
+
+/// Find the `articleBody` of the article, if the document contains a single
+/// article, e.g. not a list of teasers.
+pub(crate) fn article_body(document: &Handle) -> Option<Handle> {
+    let articles = find_articles(document);
+
+    match articles.as_slice() {
+        [article] => find_property(article, "articleBody"),
+        _ => None,
+    }
+}
+
+/// Find the first property of the item, which is not a property of a nested
+/// item like a comment or the author.
+fn find_property(item: &Handle, name: &str) -> Option<Handle> {
+    for child in item.children.borrow().iter() {
+        if has_itemprop(child, name) {
+            return Some(child.clone());
+        }
+        if dom::get_attr(child, "itemscope").is_some() {
+            continue;
+        }
+        if let Some(property) = find_property(child, name) {
+            return Some(property);
+        }
+    }
+
+    None
+}
+
+fn has_itemprop(handle: &Handle, name: &str) -> bool {
+    dom::get_attr(handle, "itemprop")
+        .is_some_and(|itemprop| itemprop.split_ascii_whitespace().any(|prop| prop == name))
+}
+
+/// Get the value of a property, i.e. its `content`, `datetime`, or text.
+fn value(handle: &Handle) -> Option<String> {
+    let value = dom::get_attr(handle, "content")
+        .or_else(|| dom::get_attr(handle, "datetime"))
+        .unwrap_or_else(|| dom::text_content(handle))
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    (!value.is_empty()).then_some(value)
+}
+
+/// Get the name of the author, i.e. the `name` of a nested item.
+fn author(handle: &Handle) -> Option<String> {
+    if dom::get_attr(handle, "itemscope").is_some() {
+        find_property(handle, "name").and_then(|node| value(&node))
+    } else {
+        value(handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    fn parse(html: &str) -> RcDom {
+        parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_article() {
+        let dom = parse(
+            r#"<article itemscope itemtype="https://schema.org/NewsArticle">
+                <h1 itemprop="headline">The  title</h1>
+                <span itemprop="author" itemscope itemtype="https://schema.org/Person">
+                    <span itemprop="name">Jane Doe</span></span>
+                <time itemprop="datePublished" datetime="2024-05-01">May 1</time>
+                <div itemprop="articleBody"><p>The content.</p></div>
+                <div itemprop="comment" itemscope itemtype="https://schema.org/Comment">
+                    <p itemprop="name">A comment</p></div>
+            </article>"#,
+        );
+
+        let article = super::article(&dom.document).unwrap();
+
+        assert_eq!(article.headline.as_deref(), Some("The title"));
+        assert_eq!(article.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(article.published.as_deref(), Some("2024-05-01"));
+        assert!(article_body(&dom.document).is_some_and(|body| dom::is_tag(&body, "div")));
+
+        let dom = parse(
+            r#"<div itemscope itemtype="http://schema.org/BlogPosting">
+                <meta itemprop="headline" content="First"><p itemprop="articleBody">First</p></div>
+            <div itemscope itemtype="http://schema.org/BlogPosting">
+                <p itemprop="articleBody">Second</p></div>
+            <div itemscope itemtype="http://schema.org/Product"><p itemprop="name">Product</p></div>"#,
+        );
+
+        let article = super::article(&dom.document).unwrap();
+
+        assert_eq!(article.headline.as_deref(), Some("First"));
+        assert_eq!(article.author, None);
+        assert!(article_body(&dom.document).is_none());
+    }
+}
@@ -0,0 +1,154 @@
+//! Parse classic microformats (`hentry`) and microformats2 (`h-entry`).
+
+use crate::dom::{self, Handle};
+
+/// The properties of an `h-entry` or `hentry`.
+#[derive(Debug, Default)]
+pub(crate) struct Entry {
+    /// The `p-name` or `entry-title`.
+    pub title: Option<String>,
+    /// The `p-author` or `author`, i.e. the name of its `h-card` or `vcard`.
+    pub author: Option<String>,
+    /// The `dt-published` or `published` date.
+    pub published: Option<String>,
+}
+
+/// Find the entries of the document, i.e. the outermost elements with the
+/// class `h-entry` or `hentry`.
+fn find_entries(handle: &Handle) -> Vec<Handle> {
+    let mut entries = vec![];
+
+    for child in handle.children.borrow().iter() {
+        if has_any_class(child, &["h-entry", "hentry"]) {
+            entries.push(child.clone());
+        } else {
+            entries.extend(find_entries(child));
+        }
+    }
+
+    entries
+}
+
+/// Parse the first entry of the document.
+pub(crate) fn entry(document: &Handle) -> Option<Entry> {
+    let entry = find_entries(document).into_iter().next()?;
+
+    Some(Entry {
+        title: find_property(&entry, &["p-name", "entry-title"]).and_then(|node| text(&node)),
+        author: find_property(&entry, &["p-author", "author"]).and_then(|node| author(&node)),
+        published: find_property(&entry, &["dt-published", "published"])
+            .and_then(|node| published(&node)),
+    })
+}
+
+/// Find the content of the entry, if the document contains a single entry,
+/// e.g. not the index page of a blog.
+pub(crate) fn entry_content(document: &Handle) -> Option<Handle> {
+    let entries = find_entries(document);
+
+    match entries.as_slice() {
+        [entry] => find_property(entry, &["e-content", "entry-content"]),
+        _ => None,
+    }
+}
+
+/// Find the first property of the entry, which is not part of a nested entry
+/// like a comment, or a nested card like the author.
+fn find_property(entry: &Handle, classes: &[&str]) -> Option<Handle> {
+    for child in entry.children.borrow().iter() {
+        if has_any_class(child, classes) {
+            return Some(child.clone());
+        }
+        if has_any_class(child, &["h-entry", "hentry", "h-cite", "h-card", "vcard"]) {
+            continue;
+        }
+        if let Some(property) = find_property(child, classes) {
+            return Some(property);
+        }
+    }
+
+    None
+}
+
+fn has_any_class(handle: &Handle, classes: &[&str]) -> bool {
+    classes.iter().any(|class| dom::has_class(handle, class))
+}
+
+fn text(handle: &Handle) -> Option<String> {
+    let text = dom::text_content(handle)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    (!text.is_empty()).then_some(text)
+}
+
+/// Get the name of the author, i.e. the name of a nested `h-card` or `vcard`.
+fn author(handle: &Handle) -> Option<String> {
+    find_property(handle, &["p-name", "fn"])
+        .or_else(|| Some(handle.clone()))
+        .and_then(|node| text(&node))
+}
+
+/// Get the published date from the `datetime` of a `time` element, a value
+/// title, or the text.
+fn published(handle: &Handle) -> Option<String> {
+    dom::get_attr(handle, "datetime")
+        .or_else(|| {
+            dom::find_by_class(handle, "value-title")
+                .first()
+                .and_then(|node| dom::get_attr(node, "title"))
+        })
+        .or_else(|| dom::get_attr(handle, "title").filter(|_| dom::is_tag(handle, "abbr")))
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+        .or_else(|| text(handle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    fn parse(html: &str) -> RcDom {
+        parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_entry() {
+        let dom = parse(
+            r#"<article class="h-entry">
+                <h1 class="p-name">The  title</h1>
+                <p>By <a class="p-author h-card" href="/"><span class="p-name">Jane Doe</span></a>
+                on <time class="dt-published" datetime="2024-05-01T10:00:00Z">May 1</time></p>
+                <div class="e-content"><p>The content.</p></div>
+                <div class="h-entry"><p class="p-name">A comment</p></div>
+            </article>"#,
+        );
+
+        let entry = super::entry(&dom.document).unwrap();
+
+        assert_eq!(entry.title.as_deref(), Some("The title"));
+        assert_eq!(entry.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(entry.published.as_deref(), Some("2024-05-01T10:00:00Z"));
+        assert!(entry_content(&dom.document)
+            .is_some_and(|content| dom::has_class(&content, "e-content")));
+
+        let dom = parse(
+            r#"<div class="hentry"><h2 class="entry-title">First</h2>
+                <span class="published"><span class="value-title" title="2024-01-01"></span>
+                January 1</span><div class="entry-content">First post</div></div>
+            <div class="hentry"><div class="entry-content">Second post</div></div>"#,
+        );
+
+        let entry = super::entry(&dom.document).unwrap();
+
+        assert_eq!(entry.title.as_deref(), Some("First"));
+        assert_eq!(entry.published.as_deref(), Some("2024-01-01"));
+        assert!(entry_content(&dom.document).is_none());
+    }
+}
@@ -0,0 +1,141 @@
+//! Detect paywalled or truncated articles.
+
+use crate::dom::{self, Handle};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// The pattern for the `class` or `id` of paywall containers.
+pub const PAYWALL_ELEMENTS: &str = concat!(
+    r"(?i)(\b|_|-)(paywall|regwall|meter(ed)?-?(content|paywall)",
+    r"|subscriber-only|subscribers-only|premium-(content|article)|piano-(offer|paywall)",
+    r"|tp-modal|article-locked|locked-content)(\b|_|-)"
+);
+/// The pattern for calls to action at the end of truncated content.
+pub const TRUNCATION_MARKERS: &str = concat!(
+    r"(?i)(subscribe|sign up|sign in|log in|register)(\s+now)?\s+(to|for)\s+",
+    r"(continue|keep|read|unlock|full access|unlimited access)",
+    r"|(continue|keep) reading (with|for)\b",
+    r"|this (article|content|story) is (only )?(for|reserved for|exclusive to) (subscribers|members)",
+    r"|already (a subscriber|have an account)"
+);
+
+/// The number of characters at the end of the text which are searched for
+/// truncation markers.
+const TRUNCATION_MARKER_WINDOW: usize = 300;
+
+lazy_static! {
+    static ref PAYWALL_ELEMENT: Regex = Regex::new(PAYWALL_ELEMENTS).unwrap();
+    static ref TRUNCATION_MARKER: Regex = Regex::new(TRUNCATION_MARKERS).unwrap();
+    static ref NOT_ACCESSIBLE_FOR_FREE: Regex =
+        Regex::new(r#"(?i)"isAccessibleForFree"\s*:\s*"?false"?"#).unwrap();
+}
+
+/// The reason why an article is considered truncated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TruncationReason {
+    /// The structured data declares `isAccessibleForFree` as `false`.
+    NotAccessibleForFree,
+    /// The document contains a paywall container, see [`PAYWALL_ELEMENTS`].
+    PaywallElement,
+    /// The text ends with a call to subscribe, see [`TRUNCATION_MARKERS`].
+    TruncationMarker,
+}
+
+/// Detect paywall markers in the document, which must be called before
+/// preprocessing removes scripts and unlikely candidates.
+pub(crate) fn document_truncation(document: &Handle) -> Option<TruncationReason> {
+    if is_not_accessible_for_free(document) {
+        return Some(TruncationReason::NotAccessibleForFree);
+    }
+
+    dom::descendants(document)
+        .any(|node| {
+            ["class", "id"].iter().any(|name| {
+                dom::get_attr(&node, name).is_some_and(|value| PAYWALL_ELEMENT.is_match(&value))
+            })
+        })
+        .then_some(TruncationReason::PaywallElement)
+}
+
+/// Detect a call to subscribe at the end of the extracted text.
+pub(crate) fn text_truncation(text: &str) -> Option<TruncationReason> {
+    let text = text.trim_end();
+    let start = text
+        .char_indices()
+        .rev()
+        .nth(TRUNCATION_MARKER_WINDOW - 1)
+        .map_or(0, |(index, _)| index);
+
+    TRUNCATION_MARKER
+        .is_match(&text[start..])
+        .then_some(TruncationReason::TruncationMarker)
+}
+
+/// Check the JSON-LD scripts and microdata for `isAccessibleForFree: false`.
+fn is_not_accessible_for_free(document: &Handle) -> bool {
+    let json_ld = dom::find_by_tag(document, "script").iter().any(|script| {
+        dom::get_attr(script, "type")
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("application/ld+json"))
+            && NOT_ACCESSIBLE_FOR_FREE.is_match(&dom::text_content(script))
+    });
+
+    json_ld
+        || dom::find_by_attr(document, "itemprop", Some("isAccessibleForFree"))
+            .iter()
+            .any(|node| {
+                dom::get_attr(node, "content")
+                    .is_some_and(|value| value.trim().eq_ignore_ascii_case("false"))
+            })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    fn parse(html: &str) -> RcDom {
+        parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_document_truncation() {
+        let dom = parse(
+            r#"<script type="application/ld+json">
+            {"@type": "NewsArticle", "isAccessibleForFree": "False"}</script><p>Text</p>"#,
+        );
+        assert_eq!(
+            document_truncation(&dom.document),
+            Some(TruncationReason::NotAccessibleForFree)
+        );
+
+        let dom =
+            parse(r#"<article><p>Text</p><div class="article-paywall">Offer</div></article>"#);
+        assert_eq!(
+            document_truncation(&dom.document),
+            Some(TruncationReason::PaywallElement)
+        );
+
+        let dom = parse(r#"<article class="wallpaper"><p>Text</p></article>"#);
+        assert_eq!(document_truncation(&dom.document), None);
+    }
+
+    #[test]
+    fn test_text_truncation() {
+        assert_eq!(
+            text_truncation("The first paragraph.\nSubscribe now to continue reading.\n"),
+            Some(TruncationReason::TruncationMarker)
+        );
+        assert_eq!(
+            text_truncation(&format!(
+                "Subscribe to continue reading. {}",
+                "Text. ".repeat(100)
+            )),
+            None
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Named option presets for different kinds of pages.
+
+use crate::{
+    cleaner::CleanOptions,
+    extractor::ExtractOptions,
+    scorer::{CandidatePatterns, ScorerOptions},
+    toc::TocOptions,
+};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref NEWS: CandidatePatterns = CandidatePatterns::default()
+        .with_extra_unlikely("newsletter|paywall|recirc")
+        .and_then(|patterns| patterns.with_extra_positive("article-body|story-body"))
+        .unwrap();
+    static ref BLOG: CandidatePatterns = CandidatePatterns::default();
+    static ref FORUM: CandidatePatterns = CandidatePatterns::default()
+        .without_unlikely(&["comment"])
+        .and_then(|patterns| patterns.without_negative(&["comment", "com-"]))
+        .and_then(|patterns| patterns.without_positive(&["text"]))
+        .unwrap();
+    static ref DOCS: CandidatePatterns = CandidatePatterns::default()
+        .with_extra_positive("docs|documentation|markdown-body|rst-content")
+        .and_then(|patterns| patterns.without_negative(&["footnote"]))
+        .unwrap();
+}
+
+/// A preset of options for a kind of page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Preset {
+    /// News articles, which strips tracking parameters and removes related
+    /// articles and newsletter forms.
+    News,
+    /// Blog posts, which uses the default options.
+    Blog,
+    /// Forum threads and comment pages, where comments are content.
+    Forum,
+    /// Documentation pages, which keeps the `id` of elements for deep links and
+    /// builds a table of contents.
+    Docs,
+}
+
+impl ExtractOptions<'static> {
+    /// Create the options of a preset.
+    ///
+    /// Single fields can be overridden with the struct update syntax:
+    ///
+    /// ```
+    /// use readability::{ExtractOptions, FallbackPolicy, Preset};
+    ///
+    /// let options = ExtractOptions {
+    ///     fallback_policy: FallbackPolicy::Error,
+    ///     ..ExtractOptions::preset(Preset::Forum)
+    /// };
+    /// ```
+    pub fn preset(preset: Preset) -> Self {
+        match preset {
+            Preset::News => Self {
+                scorer_options: ScorerOptions {
+                    clean_options: CleanOptions {
+                        strip_tracking_params: true,
+                        ..Default::default()
+                    },
+                    ..ScorerOptions::from(&*NEWS)
+                },
+                ..Default::default()
+            },
+            Preset::Blog => Self {
+                scorer_options: ScorerOptions::from(&*BLOG),
+                ..Default::default()
+            },
+            Preset::Forum => Self {
+                scorer_options: ScorerOptions {
+                    min_candidate_length: 10,
+                    clean_options: CleanOptions {
+                        remove_related_sections: false,
+                        ..Default::default()
+                    },
+                    ..ScorerOptions::from(&*FORUM)
+                },
+                ..Default::default()
+            },
+            Preset::Docs => Self {
+                scorer_options: ScorerOptions {
+                    hash_link_coefficient: 0.0,
+                    clean_options: CleanOptions {
+                        remove_related_sections: false,
+                        preserved_attributes: &["id"],
+                        ..Default::default()
+                    },
+                    ..ScorerOptions::from(&*DOCS)
+                },
+                toc_options: Some(TocOptions::default()),
+                ..Default::default()
+            },
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Python bindings via `pyo3`.
+//!
+//! Build the wheel with `maturin build --release`, see `pyproject.toml`, which
+//! builds the library as `cdylib`.
+
+use crate::{
+    bindings::{BindingOptions, BindingReadable},
+    extractor,
+};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+use url::Url;
+
+/// Extract the readable content from an HTML string.
+///
+/// Returns a dict with exactly the keys `title`, `content`, `text`, `byline`,
+/// `lang`, and `detected_language`. Raises `ValueError` if the url is invalid or the
+/// extraction fails.
+#[pyfunction]
+#[pyo3(signature = (
+    html,
+    url,
+    *,
+    strict = false,
+    error_on_no_content = false,
+    min_candidate_length = None,
+    max_candidate_parents = None,
+))]
+fn extract<'py>(
+    py: Python<'py>,
+    html: &str,
+    url: &str,
+    strict: bool,
+    error_on_no_content: bool,
+    min_candidate_length: Option<usize>,
+    max_candidate_parents: Option<usize>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let url = Url::parse(url).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    let options = BindingOptions {
+        strict,
+        error_on_no_content,
+        min_candidate_length,
+        max_candidate_parents,
+    };
+
+    let readable = py
+        .allow_threads(|| extractor::extract_from_str(html, &url, options.to_extract_options()))
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+    let readable = BindingReadable::from(&readable);
+    let dict = PyDict::new(py);
+    dict.set_item("title", readable.title)?;
+    dict.set_item("content", readable.content)?;
+    dict.set_item("text", readable.text)?;
+    dict.set_item("byline", readable.byline)?;
+    dict.set_item("lang", readable.lang)?;
+    dict.set_item("detected_language", readable.detected_language)?;
+
+    Ok(dict)
+}
+
+#[pymodule]
+#[pyo3(name = "readability_rs")]
+fn readability_rs(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(extract, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! Run the test fixtures of Readability.js, and map its options, to measure the
+//! parity with the reference implementation.
+
+use crate::{
+    cleaner::CleanOptions,
+    corpus::{read_optional, CorpusReport, PageReport},
+    dom::RcDom,
+    error::ReadabilityError,
+    extractor::{extract_from_bytes, extract_text, ExtractOptions},
+    scorer::{CandidateScore, ScorerOptions},
+};
+use html5ever::{parse_document, tendril::TendrilSink};
+use std::{fs, path::Path};
+use url::Url;
+
+/// The url of the test pages of Readability.js.
+pub const READABILITY_JS_URL: &str = "http://fakehost/test/page.html";
+
+/// The options of Readability.js, as passed to the `Readability` constructor.
+///
+/// Options without an equivalent, e.g. `charThreshold` or `nbTopCandidates`,
+/// are accepted but ignored, see [`ReadabilityJsOptions::to_extract_options`].
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReadabilityJsOptions {
+    /// Keep the `class` attributes of the content.
+    pub keep_classes: bool,
+    /// The classes which are kept if `keepClasses` is not set. Since single
+    /// classes can't be kept, the `class` attributes are kept if this is not
+    /// empty.
+    pub classes_to_preserve: Vec<String>,
+    /// The minimum number of characters of the content, before Readability.js
+    /// retries with relaxed flags.
+    pub char_threshold: Option<usize>,
+    /// The number of top candidates which are compared to find a common
+    /// ancestor.
+    pub nb_top_candidates: Option<usize>,
+    /// Skip the metadata in JSON-LD scripts.
+    #[serde(rename = "disableJSONLD")]
+    pub disable_json_ld: bool,
+}
+
+impl ReadabilityJsOptions {
+    /// Map the options to extract options with the scoring of Readability.js,
+    /// i.e. [`CandidateScore::ReadabilityJs`].
+    ///
+    /// `charThreshold`, `nbTopCandidates`, and `disableJSONLD` are ignored,
+    /// since the content is extracted in a single pass from the top
+    /// candidate, and JSON-LD is only used for metadata which is not part of
+    /// the content.
+    pub fn to_extract_options(&self) -> ExtractOptions<'static> {
+        let preserved_attributes: &'static [&'static str] =
+            if self.keep_classes || !self.classes_to_preserve.is_empty() {
+                &["class"]
+            } else {
+                &[]
+            };
+
+        ExtractOptions {
+            scorer_options: ScorerOptions {
+                candidate_score: CandidateScore::ReadabilityJs,
+                clean_options: CleanOptions {
+                    preserved_attributes,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// The expected metadata of a test page, from `expected-metadata.json`.
+#[derive(Debug, Default, serde::Deserialize)]
+struct ExpectedMetadata {
+    title: Option<String>,
+}
+
+/// Extract the content of the test pages of Readability.js in a directory,
+/// e.g. `test/test-pages` of mozilla/readability, and compare it with the
+/// expected output.
+///
+/// A test page is a subdirectory containing
+///
+/// - `source.html`, the HTML of the page,
+/// - `expected.html`, the expected content, whose text is compared with the
+///   extracted text, and
+/// - `expected-metadata.json`, the expected metadata, whose title is compared
+///   with the extracted title.
+///
+/// The pages are extracted with the url [`READABILITY_JS_URL`]. Other files
+/// and directories without `source.html` are skipped.
+pub fn run_readability_js_corpus(
+    dir: impl AsRef<Path>,
+    options: &ExtractOptions,
+) -> Result<CorpusReport, ReadabilityError> {
+    let url = Url::parse(READABILITY_JS_URL)?;
+    let mut pages = vec![];
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let source_path = path.join("source.html");
+
+        if !source_path.is_file() {
+            continue;
+        }
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let source = fs::read(source_path)?;
+        let expected_text = read_optional(&path.join("expected.html"))?
+            .map(|expected_html| html_text(&expected_html))
+            .transpose()?;
+        let metadata = match read_optional(&path.join("expected-metadata.json"))? {
+            Some(json) => match serde_json::from_str::<ExpectedMetadata>(&json) {
+                Ok(metadata) => metadata,
+                Err(err) => {
+                    pages.push(PageReport::failed(name, err.to_string()));
+                    continue;
+                }
+            },
+            None => ExpectedMetadata::default(),
+        };
+
+        pages.push(PageReport::new(
+            name,
+            extract_from_bytes(&source, &url, options.clone()),
+            expected_text.as_deref(),
+            metadata.title.as_deref(),
+        ));
+    }
+
+    pages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(CorpusReport { pages })
+}
+
+/// Extract the text of the expected HTML like the text of the content.
+fn html_text(html: &str) -> Result<String, ReadabilityError> {
+    let dom = parse_document(RcDom::default(), Default::default())
+        .from_utf8()
+        .read_from(&mut html.as_bytes())?;
+    let mut text = String::new();
+    extract_text(&dom.document, &mut text, true);
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_extract_options() {
+        let options: ReadabilityJsOptions =
+            serde_json::from_str(r#"{"classesToPreserve": ["caption"], "charThreshold": 20}"#)
+                .unwrap();
+        let extract_options = options.to_extract_options();
+
+        assert_eq!(options.char_threshold, Some(20));
+        assert!(matches!(
+            extract_options.scorer_options.candidate_score,
+            CandidateScore::ReadabilityJs
+        ));
+        assert_eq!(
+            extract_options
+                .scorer_options
+                .clean_options
+                .preserved_attributes,
+            ["class"]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Check if a document probably has readable content without extracting it,
+//! like `isProbablyReaderable` of Readability.js.
+
+use crate::{
+    charset, compression,
+    extractor::check_html,
+    scan::{scan, HeadScan},
+    scorer::Scorer,
+};
+use html5ever::Attribute;
+
+/// The minimum number of characters of a paragraph to be counted.
+const MIN_PARAGRAPH_LENGTH: usize = 140;
+/// The minimum score of the paragraphs of a readable document.
+const MIN_SCORE: f64 = 20.0;
+
+/// Check if the HTML bytes probably contain an article, e.g. to decide
+/// whether to offer a reader view before extracting the content.
+///
+/// The input is only tokenized until enough long `p` and `pre` elements are
+/// seen, without building the DOM. Each paragraph with more than 140
+/// characters adds the square root of the excess to the score, unless its
+/// `class` or `id` is an unlikely candidate, and the document is readable if
+/// the score exceeds 20.
+///
+/// Returns `false` if the input isn't HTML.
+pub fn is_probably_readerable(bytes: &[u8]) -> bool {
+    let Ok(bytes) = compression::decompress(bytes) else {
+        return false;
+    };
+    if check_html(&bytes).is_err() {
+        return false;
+    }
+
+    let html = charset::decode(&bytes);
+    let scorer = Scorer::default();
+    let scan = scan(&html, |scan| score(scan, &scorer) > MIN_SCORE);
+
+    score(&scan, &scorer) > MIN_SCORE
+}
+
+fn score(scan: &HeadScan, scorer: &Scorer) -> f64 {
+    scan.paragraphs
+        .iter()
+        .filter(|(_, attrs)| !is_hidden(attrs))
+        .filter(|(_, attrs)| {
+            let match_string = attrs
+                .iter()
+                .filter(|attr| matches!(&*attr.name.local, "class" | "id"))
+                .map(|attr| &*attr.value)
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            !scorer.is_unlikely(&match_string)
+        })
+        .map(|(text, _)| text.trim().chars().count())
+        .filter(|length| *length >= MIN_PARAGRAPH_LENGTH)
+        .map(|length| ((length - MIN_PARAGRAPH_LENGTH) as f64).sqrt())
+        .sum()
+}
+
+fn is_hidden(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| match &*attr.name.local {
+        "hidden" => true,
+        "aria-hidden" => &*attr.value == "true",
+        "style" => attr
+            .value
+            .replace(' ', "")
+            .to_lowercase()
+            .contains("display:none"),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_probably_readerable() {
+        let paragraph = "Lorem ipsum dolor sit amet. ".repeat(20);
+        let readable = format!(
+            "<html><body>{}</body></html>",
+            format!("<p>{paragraph}</p>").repeat(3)
+        );
+        assert!(is_probably_readerable(readable.as_bytes()));
+
+        let short = "<html><body><p>Short text</p><p>Another short text</p></body></html>";
+        assert!(!is_probably_readerable(short.as_bytes()));
+
+        let unlikely = format!(
+            r#"<html><body>{}</body></html>"#,
+            format!(r#"<p class="comment">{paragraph}</p>"#).repeat(3)
+        );
+        assert!(!is_probably_readerable(unlikely.as_bytes()));
+
+        let hidden = format!(
+            r#"<html><body>{}</body></html>"#,
+            format!(r#"<p style="display: none">{paragraph}</p>"#).repeat(3)
+        );
+        assert!(!is_probably_readerable(hidden.as_bytes()));
+
+        assert!(!is_probably_readerable(b""));
+    }
+}
@@ -0,0 +1,486 @@
+//! Render the content as HTML and text in a single pass over the DOM, or on
+//! demand from the retained content node.
+
+use crate::{
+    dom::{ArenaDom, Handle, NodeId, RcDom, SerializableHandle},
+    error::ReadabilityError,
+    extractor::Readable,
+    text::{render_text, TextOptions},
+    xhtml::{to_xhtml, write_xhtml},
+};
+use html5ever::{
+    local_name, namespace_url, ns, parse_document,
+    serialize::{AttrRef, HtmlSerializer, Serialize, SerializeOpts, Serializer},
+    tendril::TendrilSink,
+    LocalName, QualName,
+};
+use std::{fmt, io};
+
+/// The rendered content.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RenderedContent {
+    /// The serialized HTML, if requested.
+    pub html: Option<String>,
+    /// The text as extracted by [`extract_text`](crate::extract_text), if
+    /// requested.
+    pub text: Option<String>,
+}
+
+/// The block elements which are put on separate lines if pretty printing.
+pub(crate) const BLOCK_ELEMENTS: [&str; 33] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "th",
+    "tr",
+];
+
+/// The options to serialize the content as HTML.
+///
+/// Attribute values are always quoted with double quotes, and the content of
+/// `script` and `style` elements is written as is.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct SerializeOptions {
+    /// Serialize the content of `noscript` elements as raw text, like a
+    /// browser with scripting enabled.
+    pub scripting_enabled: bool,
+    /// Pretty print the HTML, i.e. put block elements on separate lines
+    /// indented by this number of spaces per level.
+    ///
+    /// Whitespace between block elements is replaced, and the content of `pre`
+    /// elements is kept as is.
+    pub indent: Option<usize>,
+    /// Serialize the content as well-formed XHTML, e.g. for EPUB, i.e. with
+    /// self-closed void elements, escaped text, and without comments.
+    ///
+    /// The content is not pretty printed as XHTML.
+    pub xhtml: bool,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        Self {
+            scripting_enabled: true,
+            indent: None,
+            xhtml: false,
+        }
+    }
+}
+
+impl From<&SerializeOptions> for SerializeOpts {
+    fn from(options: &SerializeOptions) -> Self {
+        SerializeOpts {
+            scripting_enabled: options.scripting_enabled,
+            ..Default::default()
+        }
+    }
+}
+
+/// Render the children of the content node as HTML and/or text.
+///
+/// In contrast to serializing the content and extracting the text
+/// separately, the DOM is only traversed once.
+pub fn render_content(
+    handle: &Handle,
+    html: bool,
+    text: bool,
+) -> Result<RenderedContent, ReadabilityError> {
+    render_content_with(handle, html, text, true, &SerializeOptions::default())
+}
+
+/// Render the content, and map `br` elements to newlines in the text unless
+/// `line_breaks` is `false`.
+pub(crate) fn render_content_with(
+    handle: &Handle,
+    html: bool,
+    text: bool,
+    line_breaks: bool,
+    serialize_options: &SerializeOptions,
+) -> Result<RenderedContent, ReadabilityError> {
+    let mut bytes = vec![];
+    let mut content_text = String::new();
+    let opts = SerializeOpts::from(serialize_options);
+
+    let xhtml = (html && serialize_options.xhtml).then(|| {
+        let mut xhtml = String::new();
+        for child in handle.children.borrow().iter() {
+            write_xhtml(child, &ns!(html), &mut xhtml, &mut None);
+        }
+        xhtml
+    });
+
+    {
+        let mut serializer = ContentSerializer {
+            html: (html && xhtml.is_none()).then(|| HtmlSerializer::new(&mut bytes, opts.clone())),
+            text: text.then_some(&mut content_text),
+            last_tag_names: vec![None],
+            line_breaks,
+            indent: serialize_options.indent,
+            pre_depth: 0,
+            block_children: vec![false],
+            pending_whitespace: None,
+            is_empty: true,
+        };
+
+        SerializableHandle::from(handle.clone())
+            .serialize(&mut serializer, opts.traversal_scope)?;
+    }
+
+    Ok(RenderedContent {
+        html: xhtml.or_else(|| html.then(|| String::from_utf8(bytes).unwrap_or_default())),
+        text: text.then_some(content_text),
+    })
+}
+
+/// The content node of a [`Readable`], which owns its DOM and can be sent to
+/// other threads.
+#[derive(Debug, Clone)]
+pub struct ContentNode {
+    pub dom: ArenaDom,
+    pub node: NodeId,
+}
+
+impl Readable {
+    /// Render the content as HTML, which is [`Readable::content`] unless the
+    /// content node is not retained.
+    pub fn to_html(&self) -> String {
+        match self.content_handle() {
+            Some(handle) => render_content(&handle, true, false)
+                .ok()
+                .and_then(|rendered| rendered.html)
+                .unwrap_or_default(),
+            None => self.content.clone(),
+        }
+    }
+
+    /// Render the content as HTML with the given serialize options.
+    ///
+    /// If the content node is not retained, [`Readable::content`] is returned,
+    /// or converted to XHTML if [`SerializeOptions::xhtml`] is set.
+    pub fn to_html_with(&self, serialize_options: &SerializeOptions) -> String {
+        match self.content_handle() {
+            Some(handle) => render_content_with(&handle, true, false, true, serialize_options)
+                .ok()
+                .and_then(|rendered| rendered.html)
+                .unwrap_or_default(),
+            None if serialize_options.xhtml => to_xhtml(&self.content, None),
+            None => self.content.clone(),
+        }
+    }
+
+    /// Render the content as text like [`Readable::text`], but without
+    /// [`ExtractOptions::text_options`](crate::ExtractOptions::text_options)
+    /// or [`ExtractOptions::normalize_options`](crate::ExtractOptions::normalize_options).
+    pub fn to_text(&self) -> String {
+        match self.content_handle() {
+            Some(handle) => render_content(&handle, false, true)
+                .ok()
+                .and_then(|rendered| rendered.text)
+                .unwrap_or_default(),
+            None => self.text.clone(),
+        }
+    }
+
+    /// Render the content as Markdown, see [`TextOptions::markdown`].
+    ///
+    /// If the content node is not retained, [`Readable::content`] is parsed
+    /// again.
+    pub fn to_markdown(&self) -> String {
+        match self.content_handle() {
+            Some(handle) => render_text(&handle, &TextOptions::markdown()),
+            None => {
+                let dom =
+                    parse_document(RcDom::default(), Default::default()).one(self.content.as_str());
+                render_text(&dom.document, &TextOptions::markdown())
+            }
+        }
+    }
+
+    fn content_handle(&self) -> Option<Handle> {
+        self.content_node
+            .as_ref()
+            .map(|content_node| content_node.dom.to_handle(content_node.node))
+    }
+}
+
+impl fmt::Display for Readable {
+    /// Format the content as text, see [`Readable::to_text`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_text())
+    }
+}
+
+/// A serializer which writes the HTML and collects the text at the same time.
+struct ContentSerializer<'a, W: io::Write> {
+    html: Option<HtmlSerializer<W>>,
+    text: Option<&'a mut String>,
+    /// The tag name of the last element child of each open element, which
+    /// separates paragraphs by a newline.
+    last_tag_names: Vec<Option<LocalName>>,
+    /// Map `br` elements to newlines.
+    line_breaks: bool,
+    /// The indentation of block elements if pretty printing.
+    indent: Option<usize>,
+    /// The number of open `pre` elements, whose whitespace is kept.
+    pre_depth: usize,
+    /// Whether each open element has block children.
+    block_children: Vec<bool>,
+    /// Whitespace which is written unless it's replaced by a line break before
+    /// a block element.
+    pending_whitespace: Option<String>,
+    /// Whether no HTML was written yet.
+    is_empty: bool,
+}
+
+impl<W: io::Write> ContentSerializer<'_, W> {
+    fn is_pretty(&self) -> bool {
+        self.indent.is_some() && self.pre_depth == 0
+    }
+
+    fn write_pending_whitespace(&mut self) -> io::Result<()> {
+        match (self.pending_whitespace.take(), self.html.as_mut()) {
+            (Some(whitespace), Some(html)) => html.write_text(&whitespace),
+            _ => Ok(()),
+        }
+    }
+
+    /// Replace the pending whitespace by a line break and the indentation of
+    /// the given level.
+    fn write_line_break(&mut self, level: usize) -> io::Result<()> {
+        self.pending_whitespace = None;
+        let indent = self.indent.unwrap_or_default();
+
+        match self.html {
+            Some(ref mut html) if !self.is_empty => {
+                html.write_text(&format!("\n{}", " ".repeat(indent * level)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<W: io::Write> Serializer for ContentSerializer<'_, W> {
+    fn start_elem<'a, AttrIter>(&mut self, name: QualName, attrs: AttrIter) -> io::Result<()>
+    where
+        AttrIter: Iterator<Item = AttrRef<'a>>,
+    {
+        if let Some(ref mut text) = self.text {
+            let after_paragraph = matches!(
+                self.last_tag_names.last(),
+                Some(Some(last_tag_name)) if last_tag_name.as_ref() == "p"
+            );
+            if after_paragraph || (self.line_breaks && name.local == local_name!("br")) {
+                text.push('\n');
+            }
+        }
+
+        self.last_tag_names.push(None);
+
+        if self.is_pretty() {
+            if is_block(&name) {
+                self.write_line_break(self.block_children.len() - 1)?;
+                if let Some(has_block_children) = self.block_children.last_mut() {
+                    *has_block_children = true;
+                }
+            } else {
+                self.write_pending_whitespace()?;
+            }
+        }
+        self.block_children.push(false);
+        if name.local == local_name!("pre") {
+            self.pre_depth += 1;
+        }
+        self.is_empty = false;
+
+        match self.html {
+            Some(ref mut html) => html.start_elem(name, attrs),
+            None => Ok(()),
+        }
+    }
+
+    fn end_elem(&mut self, name: QualName) -> io::Result<()> {
+        self.last_tag_names.pop();
+
+        if let Some(last_tag_name) = self.last_tag_names.last_mut() {
+            *last_tag_name = Some(name.local.clone());
+        }
+
+        if name.local == local_name!("pre") {
+            self.pre_depth = self.pre_depth.saturating_sub(1);
+        }
+        let has_block_children = self.block_children.pop().unwrap_or_default();
+        if self.is_pretty() {
+            if has_block_children {
+                self.write_line_break(self.block_children.len() - 1)?;
+            } else {
+                self.write_pending_whitespace()?;
+            }
+        }
+
+        match self.html {
+            Some(ref mut html) => html.end_elem(name),
+            None => Ok(()),
+        }
+    }
+
+    fn write_text(&mut self, text: &str) -> io::Result<()> {
+        if let Some(ref mut content_text) = self.text {
+            content_text.push_str(text);
+        }
+
+        if self.is_pretty() {
+            if text.trim().is_empty() {
+                self.pending_whitespace
+                    .get_or_insert_with(String::new)
+                    .push_str(text);
+                return Ok(());
+            }
+            self.write_pending_whitespace()?;
+        }
+        self.is_empty = false;
+
+        match self.html {
+            Some(ref mut html) => html.write_text(text),
+            None => Ok(()),
+        }
+    }
+
+    fn write_comment(&mut self, text: &str) -> io::Result<()> {
+        if self.is_pretty() {
+            self.write_pending_whitespace()?;
+        }
+        self.is_empty = false;
+
+        match self.html {
+            Some(ref mut html) => html.write_comment(text),
+            None => Ok(()),
+        }
+    }
+
+    fn write_doctype(&mut self, name: &str) -> io::Result<()> {
+        match self.html {
+            Some(ref mut html) => html.write_doctype(name),
+            None => Ok(()),
+        }
+    }
+
+    fn write_processing_instruction(&mut self, target: &str, data: &str) -> io::Result<()> {
+        match self.html {
+            Some(ref mut html) => html.write_processing_instruction(target, data),
+            None => Ok(()),
+        }
+    }
+}
+
+fn is_block(name: &QualName) -> bool {
+    BLOCK_ELEMENTS.contains(&name.local.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dom::RcDom, extract_text};
+    use html5ever::{parse_document, serialize, tendril::TendrilSink};
+
+    #[test]
+    fn test_render_content() {
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(
+                &mut r#"<article><p>First &amp; <em>second</em></p><p>Third</p>
+                <!-- comment --><div><p>Fourth</p>Fifth<br>Sixth</div></article>"#
+                    .as_bytes(),
+            )
+            .unwrap();
+        let mut bytes = vec![];
+        serialize(
+            &mut bytes,
+            &SerializableHandle::from(dom.document.clone()),
+            Default::default(),
+        )
+        .unwrap();
+        let mut text = String::new();
+        extract_text(&dom.document, &mut text, true);
+
+        let rendered = render_content(&dom.document, true, true).unwrap();
+
+        assert_eq!(rendered.html, Some(String::from_utf8(bytes).unwrap()));
+        assert_eq!(rendered.text, Some(text));
+        assert!(rendered.text.unwrap().contains("Fifth\nSixth"));
+        assert_eq!(
+            render_content(&dom.document, false, true).unwrap().html,
+            None
+        );
+    }
+
+    #[test]
+    fn test_render_content_pretty() {
+        let dom = parse_document(RcDom::default(), Default::default()).one(
+            "<article>\n  <p>First <em>and</em> <b>second</b></p><div><p>Third</p></div>\
+             <pre>  code\n  block</pre>\n</article>",
+        );
+        let article = crate::dom::find_by_tag(&dom.document, "article")[0].clone();
+        let options = SerializeOptions {
+            indent: Some(2),
+            ..Default::default()
+        };
+
+        let rendered = render_content_with(&article, true, true, true, &options).unwrap();
+        assert_eq!(
+            rendered.html.unwrap(),
+            "<p>First <em>and</em> <b>second</b></p>\n<div>\n  <p>Third</p>\n</div>\n\
+             <pre>  code\n  block</pre>"
+        );
+        assert_eq!(
+            rendered.text,
+            render_content(&article, false, true).unwrap().text
+        );
+    }
+
+    #[test]
+    fn test_render_content_xhtml() {
+        let dom = parse_document(RcDom::default(), Default::default())
+            .one("<p>A&nbsp;&amp; B<br><img src=a.png></p><!-- comment -->");
+        let options = SerializeOptions {
+            xhtml: true,
+            ..Default::default()
+        };
+
+        let rendered = render_content_with(&dom.document, true, true, true, &options).unwrap();
+        assert_eq!(
+            rendered.html.unwrap(),
+            "<html><head></head><body><p>A\u{a0}&amp; B<br/><img src=\"a.png\"/></p>\
+             </body></html>"
+        );
+        assert_eq!(rendered.text.unwrap(), "A\u{a0}& B\n");
+    }
+}
@@ -0,0 +1,359 @@
+//! Scan the tokens of a document without building a tree, and stop as soon as
+//! the needed information is seen, e.g. the end of the `head` or the first
+//! paragraphs.
+
+use crate::dom::RcDom;
+use html5ever::{
+    local_name, namespace_url, ns,
+    tendril::StrTendril,
+    tokenizer::{
+        states::RawKind, BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer,
+    },
+    tree_builder::{ElementFlags, NodeOrText, TreeSink},
+    Attribute, LocalName, QualName,
+};
+use std::cell::RefCell;
+
+/// The number of bytes which are tokenized before checking if the scan is
+/// done.
+const CHUNK_SIZE: usize = 8192;
+/// The elements which may be part of the `head`. Other start tags end the
+/// `head`, like in the tree builder.
+const HEAD_ELEMENTS: [&str; 11] = [
+    "html", "head", "title", "base", "basefont", "bgsound", "link", "meta", "noscript", "script",
+    "style",
+];
+/// The elements whose start tag closes an open paragraph.
+const BLOCK_ELEMENTS: [&str; 17] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "div",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "header",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// The information seen by the scan so far.
+#[derive(Debug, Default)]
+pub(crate) struct HeadScan {
+    /// Whether the end of the `head` was seen.
+    pub head_ended: bool,
+    /// Whether the whole input was scanned.
+    pub finished: bool,
+    /// The text of the first `title` element.
+    pub title: Option<String>,
+    /// The attributes of the `meta` elements.
+    pub metas: Vec<Vec<Attribute>>,
+    /// The attributes of the `link` elements.
+    pub links: Vec<Vec<Attribute>>,
+    /// The content of the JSON-LD scripts, including scripts in the `body`.
+    pub json_ld: Vec<String>,
+    /// The text of the first `h1` element, once it is closed.
+    pub h1: Option<String>,
+    /// The text of the closed `p` and `pre` elements, along with their `id`
+    /// and `class`.
+    pub paragraphs: Vec<(String, Vec<Attribute>)>,
+}
+
+impl HeadScan {
+    /// Get the value of the first attribute of the `meta` elements whose
+    /// `name` or `property` is one of the names.
+    pub fn meta_content(&self, names: &[&str]) -> Option<String> {
+        names.iter().find_map(|name| {
+            self.metas.iter().find_map(|attrs| {
+                let has_name = attrs.iter().any(|attr| {
+                    matches!(&*attr.name.local, "name" | "property")
+                        && attr.value.trim().eq_ignore_ascii_case(name)
+                });
+
+                has_name
+                    .then(|| attr(attrs, "content"))
+                    .flatten()
+                    .filter(|content| !content.trim().is_empty())
+            })
+        })
+    }
+
+    /// Build a document with the `title`, `meta`, and `link` elements in the
+    /// `head`, and the JSON-LD scripts, e.g. for
+    /// [`document_metadata`](crate::metadata::document_metadata).
+    pub fn to_dom(&self) -> RcDom {
+        let dom = RcDom::default();
+        let element = |name: &str, attrs: Vec<Attribute>| {
+            let name = QualName::new(None, ns!(html), LocalName::from(name));
+            dom.create_element(name, attrs, ElementFlags::default())
+        };
+        let html = element("html", vec![]);
+        let head = element("head", vec![]);
+        dom.append(&dom.document, NodeOrText::AppendNode(html.clone()));
+        dom.append(&html, NodeOrText::AppendNode(head.clone()));
+
+        if let Some(ref title) = self.title {
+            let node = element("title", vec![]);
+            dom.append(&node, NodeOrText::AppendText(title.as_str().into()));
+            dom.append(&head, NodeOrText::AppendNode(node));
+        }
+        for attrs in self.metas.iter() {
+            dom.append(
+                &head,
+                NodeOrText::AppendNode(element("meta", attrs.clone())),
+            );
+        }
+        for attrs in self.links.iter() {
+            dom.append(
+                &head,
+                NodeOrText::AppendNode(element("link", attrs.clone())),
+            );
+        }
+        for json in self.json_ld.iter() {
+            let script = element(
+                "script",
+                vec![Attribute {
+                    name: QualName::new(None, ns!(), local_name!("type")),
+                    value: "application/ld+json".into(),
+                }],
+            );
+            dom.append(&script, NodeOrText::AppendText(json.as_str().into()));
+            dom.append(&head, NodeOrText::AppendNode(script));
+        }
+
+        dom
+    }
+}
+
+/// Tokenize the HTML in chunks until the scan is done, or the input ends.
+pub(crate) fn scan(html: &str, is_done: impl Fn(&HeadScan) -> bool) -> HeadScan {
+    let tokenizer = Tokenizer::new(ScanSink::default(), Default::default());
+    let input = BufferQueue::default();
+
+    for chunk in chunks(html, CHUNK_SIZE) {
+        input.push_back(StrTendril::from_slice(chunk));
+        let _ = tokenizer.feed(&input);
+
+        if is_done(&tokenizer.sink.scan.borrow()) {
+            return tokenizer.sink.scan.into_inner();
+        }
+    }
+
+    tokenizer.end();
+    let mut scan = tokenizer.sink.scan.into_inner();
+    scan.finished = true;
+
+    scan
+}
+
+/// The element whose text is captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Capture {
+    Title,
+    JsonLd,
+    H1,
+    Paragraph,
+}
+
+#[derive(Debug, Default)]
+struct ScanSink {
+    scan: RefCell<HeadScan>,
+    /// The captured element and its text, and the attributes of paragraphs.
+    capture: RefCell<Option<(Capture, String, Vec<Attribute>)>>,
+}
+
+impl ScanSink {
+    fn start_tag(&self, tag: Tag) -> TokenSinkResult<()> {
+        let mut scan = self.scan.borrow_mut();
+        let name: &str = &tag.name;
+
+        if !HEAD_ELEMENTS.contains(&name) {
+            scan.head_ended = true;
+        }
+        if BLOCK_ELEMENTS.contains(&name) {
+            drop(scan);
+            self.end_capture(Capture::Paragraph);
+            scan = self.scan.borrow_mut();
+        }
+
+        let capture = match name {
+            "title" if scan.title.is_none() && !scan.head_ended => Some(Capture::Title),
+            "script"
+                if attr(&tag.attrs, "type").is_some_and(|value| {
+                    value.trim().eq_ignore_ascii_case("application/ld+json")
+                }) =>
+            {
+                Some(Capture::JsonLd)
+            }
+            "h1" if scan.h1.is_none() => Some(Capture::H1),
+            "p" | "pre" => Some(Capture::Paragraph),
+            "meta" => {
+                scan.metas.push(tag.attrs.clone());
+                None
+            }
+            "link" => {
+                scan.links.push(tag.attrs.clone());
+                None
+            }
+            _ => None,
+        };
+        if let Some(capture) = capture {
+            *self.capture.borrow_mut() = Some((capture, String::new(), tag.attrs));
+        }
+
+        match name {
+            "script" => TokenSinkResult::RawData(RawKind::ScriptData),
+            "title" | "textarea" => TokenSinkResult::RawData(RawKind::Rcdata),
+            "style" | "xmp" | "iframe" | "noembed" | "noframes" | "noscript" => {
+                TokenSinkResult::RawData(RawKind::Rawtext)
+            }
+            _ => TokenSinkResult::Continue,
+        }
+    }
+
+    fn end_tag(&self, tag: Tag) {
+        match &*tag.name {
+            "head" => self.scan.borrow_mut().head_ended = true,
+            "title" => self.end_capture(Capture::Title),
+            "script" => self.end_capture(Capture::JsonLd),
+            "h1" => self.end_capture(Capture::H1),
+            "p" | "pre" | "body" => self.end_capture(Capture::Paragraph),
+            _ => (),
+        }
+    }
+
+    /// End the capture of an element, and store its text.
+    fn end_capture(&self, capture: Capture) {
+        let mut current = self.capture.borrow_mut();
+
+        if current
+            .as_ref()
+            .is_none_or(|(current, _, _)| *current != capture)
+        {
+            return;
+        }
+
+        let Some((_, text, attrs)) = current.take() else {
+            return;
+        };
+        let mut scan = self.scan.borrow_mut();
+
+        match capture {
+            Capture::Title => scan.title = Some(text),
+            Capture::JsonLd => scan.json_ld.push(text),
+            Capture::H1 => scan.h1 = Some(text),
+            Capture::Paragraph => scan.paragraphs.push((text, attrs)),
+        }
+    }
+}
+
+impl TokenSink for ScanSink {
+    type Handle = ();
+
+    fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        match token {
+            Token::TagToken(tag) => match tag.kind {
+                TagKind::StartTag => return self.start_tag(tag),
+                TagKind::EndTag => self.end_tag(tag),
+            },
+            Token::CharacterTokens(text) => {
+                if let Some((_, ref mut captured, _)) = *self.capture.borrow_mut() {
+                    captured.push_str(&text);
+                }
+            }
+            Token::EOFToken => self.end_capture(Capture::Paragraph),
+            _ => (),
+        }
+
+        TokenSinkResult::Continue
+    }
+}
+
+fn attr(attrs: &[Attribute], name: &str) -> Option<String> {
+    attrs
+        .iter()
+        .find(|attr| &*attr.name.local == name)
+        .map(|attr| attr.value.to_string())
+}
+
+/// Split the text into chunks of about the given size at char boundaries.
+fn chunks(text: &str, size: usize) -> impl Iterator<Item = &str> {
+    let mut start = 0;
+
+    std::iter::from_fn(move || {
+        if start >= text.len() {
+            return None;
+        }
+
+        let mut end = (start + size).min(text.len());
+        while !text.is_char_boundary(end) {
+            end += 1;
+        }
+        let chunk = &text[start..end];
+        start = end;
+
+        Some(chunk)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan() {
+        let html = r#"<html><head><title>A &amp; B</title>
+            <meta name="description" content="Summary">
+            <script>var title = "<title>No</title>";</script>
+            </head><body><h1>Heading <em>one</em></h1>
+            <p class="intro">First paragraph<p>Second paragraph</p>
+            <script type="application/ld+json">{"a": "</p>"}</script>
+            </body></html>"#;
+
+        let scan = scan(html, |_| false);
+        assert!(scan.head_ended);
+        assert!(scan.finished);
+        assert_eq!(scan.title.as_deref(), Some("A & B"));
+        assert_eq!(
+            scan.meta_content(&["description"]).as_deref(),
+            Some("Summary")
+        );
+        assert_eq!(scan.h1.as_deref(), Some("Heading one"));
+        assert_eq!(
+            scan.paragraphs
+                .iter()
+                .map(|(text, _)| text.as_str())
+                .collect::<Vec<_>>(),
+            ["First paragraph", "Second paragraph"]
+        );
+        assert_eq!(scan.json_ld, [r#"{"a": "</p>"}"#]);
+    }
+
+    #[test]
+    fn test_scan_early() {
+        let html = format!(
+            "<title>Title</title><body>{}",
+            "<p>Paragraph</p>".repeat(CHUNK_SIZE)
+        );
+
+        let scan = scan(&html, |scan| scan.head_ended);
+        assert!(!scan.finished);
+        assert_eq!(scan.title.as_deref(), Some("Title"));
+        assert!(scan.paragraphs.len() < CHUNK_SIZE);
+    }
+
+    #[test]
+    fn test_chunks() {
+        let text = "aé".repeat(3);
+        let chunks = chunks(&text, 2).collect::<Vec<_>>();
+        assert_eq!(chunks.concat(), text);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 3));
+    }
+}
@@ -0,0 +1,576 @@
+//! Score a document once, and render the content of its candidates many times.
+
+use crate::{
+    cleaner::CleanOptions,
+    compression,
+    diff::{diff_nodes, CleanReason, NodeDiff},
+    dom::{self, ArenaDom, Handle, Node, NodeData, RcDom},
+    error::ReadabilityError,
+    extractor::{
+        clean_content, fallback_content, parse_html, readable_from_content, score_document,
+        Content, DocumentInfo, ExtractOptions, Readable, Scoring,
+    },
+    features::NodeFeatures,
+    locator::Locators,
+    scorer::{CandidateStats, Scorer},
+};
+use html5ever::tree_builder::TreeSink;
+use std::io::Read;
+use url::Url;
+
+/// A preprocessed and scored document.
+///
+/// The content of the top candidate, or of any other candidate, can be
+/// rendered with different clean options, without parsing and scoring the
+/// document again.
+///
+/// ```
+/// use readability::{CleanOptions, ScoredDocument};
+/// use url::Url;
+///
+/// let html =
+///     "<html><body><p>This is a paragraph, with more than 25 characters.</p></body></html>";
+/// let url = Url::parse("https://example.com").unwrap();
+///
+/// let document = ScoredDocument::from_str(html, &url, Default::default()).unwrap();
+/// let content = document.render(&CleanOptions::default()).unwrap();
+///
+/// assert_eq!(content.title, document.title());
+/// ```
+pub struct ScoredDocument<'a> {
+    dom: RcDom,
+    url: Url,
+    options: ExtractOptions<'a>,
+    scorer: Scorer<'a>,
+    document: DocumentInfo,
+    scoring: Scoring,
+}
+
+impl<'a> ScoredDocument<'a> {
+    /// Parse and score UTF-8 encoded HTML from a reader.
+    pub fn parse<R>(
+        input: &mut R,
+        url: &Url,
+        opts: ExtractOptions<'a>,
+    ) -> Result<Self, ReadabilityError>
+    where
+        R: Read,
+    {
+        let mut bytes = vec![];
+        input.read_to_end(&mut bytes)?;
+
+        Self::from_bytes(&bytes, url, opts)
+    }
+
+    /// Parse and score an HTML string.
+    pub fn from_str(
+        html: &str,
+        url: &Url,
+        opts: ExtractOptions<'a>,
+    ) -> Result<Self, ReadabilityError> {
+        Self::from_bytes(html.as_bytes(), url, opts)
+    }
+
+    /// Parse and score UTF-8 encoded HTML.
+    pub fn from_bytes(
+        bytes: &[u8],
+        url: &Url,
+        opts: ExtractOptions<'a>,
+    ) -> Result<Self, ReadabilityError> {
+        let bytes = compression::decompress(bytes)?;
+        let dom = parse_html(&bytes, &opts.parse_options)?;
+
+        Self::from_dom(dom, url, opts)
+    }
+
+    /// Score a parsed document.
+    pub fn from_dom(
+        mut dom: RcDom,
+        url: &Url,
+        opts: ExtractOptions<'a>,
+    ) -> Result<Self, ReadabilityError> {
+        let scorer = Scorer::new(opts.scorer_options.clone())?;
+        let document = DocumentInfo::new(&dom.document, url);
+        let locators = Locators::collect_if(opts.outputs.selectors, &dom.document);
+        let scoring = score_document(&mut dom, &scorer, locators);
+
+        Ok(Self {
+            dom,
+            url: url.clone(),
+            options: opts,
+            scorer,
+            document,
+            scoring,
+        })
+    }
+
+    /// The title of the document.
+    pub fn title(&self) -> &str {
+        &self.scoring.title
+    }
+
+    /// The author of the article.
+    pub fn byline(&self) -> Option<&str> {
+        self.scoring.byline.as_deref()
+    }
+
+    /// The id of the top candidate, if any.
+    pub fn top_candidate(&self) -> Option<&str> {
+        self.scoring
+            .top_candidate
+            .as_ref()
+            .map(|(id, _, _)| id.as_str())
+    }
+
+    /// The statistics of the candidate detection.
+    pub fn candidate_stats(&self) -> &CandidateStats {
+        &self.scoring.stats
+    }
+
+    /// The candidates, ordered by descending score.
+    pub fn candidates(&self) -> impl Iterator<Item = CandidateView<'_>> {
+        let mut candidates = self
+            .scoring
+            .candidates
+            .iter()
+            .map(|(id, candidate)| CandidateView {
+                id,
+                tag: dom::tag_name(&candidate.node).unwrap_or_default(),
+                attributes: match candidate.node.data {
+                    NodeData::Element { ref attrs, .. } => attrs
+                        .borrow()
+                        .iter()
+                        .map(|attr| (attr.name.local.to_string(), attr.value.to_string()))
+                        .collect(),
+                    _ => vec![],
+                },
+                depth: id.split('/').filter(|index| !index.is_empty()).count(),
+                score: candidate.score.get(),
+                handle: &candidate.node,
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        candidates.into_iter()
+    }
+
+    /// The features of the elements of the preprocessed document in document
+    /// order, keyed by their id like [`CandidateView::id`], e.g. to label
+    /// the content nodes and train a [`NodeClassifier`].
+    ///
+    /// [`NodeClassifier`]: crate::NodeClassifier
+    pub fn node_features(&self) -> Vec<(String, NodeFeatures)> {
+        let mut features = vec![];
+        collect_features(&self.scorer, "", &self.dom.document, 0, &mut features);
+
+        features
+    }
+
+    /// Render the content of the top candidate.
+    ///
+    /// If no candidate was found, the content depends on
+    /// [`ExtractOptions::fallback_policy`].
+    pub fn render(&self, clean_options: &CleanOptions<'a>) -> Result<Content, ReadabilityError> {
+        match self.scoring.top_candidate {
+            Some((ref id, _, _)) => Ok(self
+                .render_candidate(id, clean_options)
+                .expect("top candidate exists")),
+            None => {
+                match fallback_content(
+                    &self.scoring,
+                    &self.dom.document,
+                    self.options.fallback_policy,
+                )? {
+                    Some((id, _)) => Ok(self
+                        .render_candidate(&id, clean_options)
+                        .expect("fallback node exists")),
+                    None => Ok(Content {
+                        node: Node::new(NodeData::Document),
+                        title: self.scoring.title.clone(),
+                        byline: self.scoring.byline.clone(),
+                        confidence: 0.0,
+                        candidate_stats: self.scoring.stats,
+                        selector: String::new(),
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Extract the content of the top candidate with different clean options,
+    /// e.g. to preview the effect of a threshold while tuning, without parsing
+    /// and scoring the document again.
+    ///
+    /// The content is rendered to HTML and text like by
+    /// [`extract`](crate::extract) with the other extract options of the
+    /// scored document, except that the author bio is left in place.
+    ///
+    /// ```
+    /// use readability::{CleanOptions, ScoredDocument};
+    /// use url::Url;
+    ///
+    /// let html = "<article><p>This is a paragraph, with more than 25 characters, \
+    ///     and <a href=\"https://example.org/?utm_source=feed\">a link</a>.</p></article>";
+    /// let url = Url::parse("https://example.com").unwrap();
+    ///
+    /// let document = ScoredDocument::from_str(html, &url, Default::default()).unwrap();
+    /// let readable = document.extract(&CleanOptions::default()).unwrap();
+    /// assert_eq!(
+    ///     readable.links[0].url,
+    ///     "https://example.org/?utm_source=feed"
+    /// );
+    ///
+    /// let clean_options = CleanOptions {
+    ///     strip_tracking_params: true,
+    ///     ..Default::default()
+    /// };
+    /// let readable = document.extract(&clean_options).unwrap();
+    /// assert_eq!(readable.links[0].url, "https://example.org/");
+    /// ```
+    pub fn extract(&self, clean_options: &CleanOptions<'a>) -> Result<Readable, ReadabilityError> {
+        let content = self.render(clean_options)?;
+        let (readable, _) = readable_from_content(
+            &mut RcDom::default(),
+            content,
+            self.document.clone(),
+            &self.url,
+            &self.options,
+        )?;
+
+        Ok(readable)
+    }
+
+    /// Render the content of a candidate, e.g. an alternative from
+    /// [`ScoredDocument::candidates`] by its [`CandidateView::id`], or `None`
+    /// if there is no node with this id.
+    ///
+    /// The scored document is left untouched, since the candidate is cleaned
+    /// in a copy of the document.
+    pub fn render_candidate(&self, id: &str, clean_options: &CleanOptions<'a>) -> Option<Content> {
+        self.clean_candidate(id, clean_options, &mut vec![])
+            .map(|(content, _)| content)
+    }
+
+    /// Compare the nodes of the top candidate before and after cleaning, see
+    /// [`ScoredDocument::diff_candidate`].
+    pub fn diff(&self, clean_options: &CleanOptions<'a>) -> Option<Vec<NodeDiff>> {
+        self.diff_candidate(self.top_candidate()?, clean_options)
+    }
+
+    /// Compare the nodes of a candidate before and after cleaning, i.e. which
+    /// nodes are kept, modified, or removed and why, or `None` if there is no
+    /// node with this id.
+    ///
+    /// ```
+    /// use readability::{CleanOptions, CleanReason, NodeChange, ScoredDocument};
+    /// use url::Url;
+    ///
+    /// let html = "<article><p>This is a paragraph, with more than 25 characters.</p>\
+    ///     <aside>Advertisement</aside></article>";
+    /// let url = Url::parse("https://example.com").unwrap();
+    ///
+    /// let document = ScoredDocument::from_str(html, &url, Default::default()).unwrap();
+    /// let diff = document.diff(&CleanOptions::default()).unwrap();
+    ///
+    /// let removed = diff
+    ///     .iter()
+    ///     .find(|node| node.tag.as_deref() == Some("aside"))
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     removed.change,
+    ///     NodeChange::Removed(CleanReason::UnlikelyTag)
+    /// );
+    /// ```
+    pub fn diff_candidate(
+        &self,
+        id: &str,
+        clean_options: &CleanOptions<'a>,
+    ) -> Option<Vec<NodeDiff>> {
+        let original = find_node(&self.dom.document, id)?;
+        let mut removed = vec![];
+        let (content, copy) = self.clean_candidate(id, clean_options, &mut removed)?;
+        let mut diffs = diff_nodes(id, &original, &copy, &content.node, &removed);
+
+        for diff in diffs.iter_mut() {
+            diff.locator = diff.id.as_deref().and_then(|id| self.locator(id));
+        }
+
+        Some(diffs)
+    }
+
+    /// The CSS selector of a node in the original document by its id, e.g. of
+    /// the [`ScoredDocument::top_candidate`], or `None` if the node is not an
+    /// element of the original document, or if
+    /// [`OutputSet::selectors`](crate::OutputSet::selectors) is not set.
+    ///
+    /// The selector is a chain of `nth-child` selectors, e.g. `html >
+    /// body:nth-child(2) > article:nth-child(1)`, which locates the element
+    /// with `document.querySelector` in a browser, unless the page was
+    /// modified by scripts.
+    ///
+    /// ```
+    /// use readability::{ExtractOptions, OutputSet, ScoredDocument};
+    /// use url::Url;
+    ///
+    /// let html = "<html><head></head><body><nav>Menu</nav>\
+    ///     <article><p>This is a paragraph, with more than 25 characters.</p></article></body></html>";
+    /// let url = Url::parse("https://example.com").unwrap();
+    /// let options = ExtractOptions {
+    ///     outputs: OutputSet {
+    ///         selectors: true,
+    ///         ..OutputSet::ALL
+    ///     },
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let document = ScoredDocument::from_str(html, &url, options).unwrap();
+    /// let top_candidate = document.top_candidate().unwrap();
+    /// assert_eq!(
+    ///     document.locator(top_candidate).as_deref(),
+    ///     Some("html > body:nth-child(2) > article:nth-child(2)")
+    /// );
+    /// ```
+    pub fn locator(&self, id: &str) -> Option<String> {
+        self.scoring
+            .locators
+            .selector(&find_node(&self.dom.document, id)?)
+    }
+
+    /// Clean a candidate in a copy of the document, and return the content
+    /// along with the pairs of original and copied nodes before cleaning.
+    fn clean_candidate(
+        &self,
+        id: &str,
+        clean_options: &CleanOptions<'a>,
+        removed: &mut Vec<(Handle, CleanReason)>,
+    ) -> Option<(Content, Vec<(Handle, Handle)>)> {
+        let (arena, document) = ArenaDom::from_handle(&self.dom.document);
+        let mut dom = RcDom::default();
+        dom.document = arena.to_handle(document);
+        let node = find_node(&dom.document, id)?;
+        let original = find_node(&self.dom.document, id)?;
+        let mut copy = vec![];
+        pair_nodes(&original, &node, &mut copy);
+
+        let scorer = self.scorer.with_clean_options(clean_options.clone());
+        let score = self
+            .scoring
+            .candidates
+            .get(id)
+            .map(|candidate| candidate.score.get())
+            .filter(|_| self.top_candidate().is_some());
+        let confidence = clean_content(
+            &mut dom,
+            &scorer,
+            id,
+            &node,
+            &self.url,
+            &self.scoring.candidates,
+            score,
+            removed,
+        );
+
+        // Detach the content node, otherwise its children are dropped along with the DOM.
+        dom.remove_from_parent(&node);
+
+        Some((
+            Content {
+                node,
+                title: self.scoring.title.clone(),
+                byline: self.scoring.byline.clone(),
+                confidence,
+                candidate_stats: self.scoring.stats,
+                selector: self
+                    .scoring
+                    .locators
+                    .closest_selector(&original)
+                    .unwrap_or_default(),
+            },
+            copy,
+        ))
+    }
+}
+
+/// A candidate of a [`ScoredDocument`].
+#[derive(Debug, Clone)]
+pub struct CandidateView<'a> {
+    /// The opaque id of the candidate, see [`ScoredDocument::render_candidate`].
+    pub id: &'a str,
+    /// The lowercase tag name.
+    pub tag: &'a str,
+    /// The names and values of the attributes.
+    pub attributes: Vec<(String, String)>,
+    /// The depth of the candidate, where the `html` element has depth `1`.
+    pub depth: usize,
+    /// The content score of the candidate.
+    pub score: f32,
+    /// The candidate node in the scored document, which is not cleaned.
+    pub handle: &'a Handle,
+}
+
+/// Find a node by its id, i.e. the path of child indices like `/1/0/3`.
+fn find_node(document: &Handle, id: &str) -> Option<Handle> {
+    id.split('/')
+        .filter(|index| !index.is_empty())
+        .try_fold(document.clone(), |node, index| {
+            let index = index.parse::<usize>().ok()?;
+            let child = node.children.borrow().get(index).cloned();
+            child
+        })
+}
+
+fn collect_features(
+    scorer: &Scorer,
+    id: &str,
+    handle: &Handle,
+    depth: usize,
+    features: &mut Vec<(String, NodeFeatures)>,
+) {
+    if let Some(node_features) = scorer.node_features(handle, depth) {
+        features.push((id.to_owned(), node_features));
+    }
+
+    for (index, child) in handle.children.borrow().iter().enumerate() {
+        collect_features(scorer, &format!("{id}/{index}"), child, depth + 1, features);
+    }
+}
+
+/// Pair the nodes of a subtree with the nodes of its copy.
+fn pair_nodes(original: &Handle, copy: &Handle, pairs: &mut Vec<(Handle, Handle)>) {
+    pairs.push((original.clone(), copy.clone()));
+
+    for (original, copy) in original
+        .children
+        .borrow()
+        .iter()
+        .zip(copy.children.borrow().iter())
+    {
+        pair_nodes(original, copy, pairs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{diff::NodeChange, extractor::OutputSet};
+
+    #[test]
+    fn test_render_candidate() {
+        let html = r#"<html><body>
+            <article><p>This is the first paragraph, with a comma, and more commas.</p>
+            <p>This is the second paragraph, with a comma, and more commas.</p>
+            <img src="a.png"></article>
+            <div class="comments"><p>This is a comment, with a comma, and more commas.</p></div>
+            </body></html>"#;
+        let url = Url::parse("https://example.com").unwrap();
+        let document = ScoredDocument::from_str(html, &url, Default::default()).unwrap();
+
+        let top_candidate = document.top_candidate().unwrap().to_owned();
+        let candidates = document.candidates().collect::<Vec<_>>();
+        assert_eq!(candidates[0].id, top_candidate);
+        assert_eq!(candidates[0].tag, "article");
+        assert_eq!(candidates[0].depth, 3);
+        assert!(candidates
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+
+        let content = document.render(&CleanOptions::default()).unwrap();
+        assert_eq!(content.node.children.borrow().len(), 3);
+        assert!(content.confidence > 0.0);
+
+        // Rendering doesn't change the scored document.
+        let content = document
+            .render_candidate(&top_candidate, &CleanOptions::default())
+            .unwrap();
+        assert_eq!(content.node.children.borrow().len(), 3);
+
+        assert!(document
+            .render_candidate("/0/99", &CleanOptions::default())
+            .is_none());
+    }
+
+    #[test]
+    fn test_node_features() {
+        let html = r#"<html><body><article class="post">
+            <p>This is the first paragraph, with a comma, and more commas.</p>
+            <p>This is the <a href="/">second paragraph</a>.</p>
+            </article></body></html>"#;
+        let url = Url::parse("https://example.com").unwrap();
+        let document = ScoredDocument::from_str(html, &url, Default::default()).unwrap();
+
+        let features = document.node_features();
+        let (id, article) = features
+            .iter()
+            .find(|(_, features)| features.tag == "article")
+            .unwrap();
+        assert_eq!(article.depth, 3);
+        assert_eq!(article.child_count, 2);
+        assert!(article.class_weight > 0.0);
+        assert!(document.candidates().any(|candidate| candidate.id == id));
+
+        let paragraphs = features
+            .iter()
+            .filter(|(_, features)| features.tag == "p")
+            .map(|(_, features)| features)
+            .collect::<Vec<_>>();
+        assert_eq!(paragraphs[0].punctuation_count, 2);
+        assert_eq!(paragraphs[0].link_density, 0.0);
+        assert!(paragraphs[1].link_density > 0.5);
+        assert_eq!(paragraphs[0].sibling_count, 1);
+        assert_eq!(paragraphs[0].sibling_text_length, paragraphs[1].text_length);
+    }
+
+    #[test]
+    fn test_diff_candidate() {
+        let html = r#"<html><body><article class="post">
+            <p>This is the first paragraph, with a comma, and more commas.</p>
+            <div class="share">Share</div>
+            <p>This is the second paragraph, with a comma, and more commas.</p>
+            <!-- comment --><aside>Advertisement</aside>
+            </article></body></html>"#;
+        let url = Url::parse("https://example.com").unwrap();
+        let options = ExtractOptions {
+            outputs: OutputSet {
+                selectors: true,
+                ..OutputSet::ALL
+            },
+            ..Default::default()
+        };
+        let document = ScoredDocument::from_str(html, &url, options).unwrap();
+
+        let diff = document.diff(&CleanOptions::default()).unwrap();
+        let changes = diff
+            .iter()
+            .map(|node| (node.tag.as_deref(), node.change))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            [
+                (Some("article"), NodeChange::Modified),
+                (Some("p"), NodeChange::Kept),
+                (None, NodeChange::Kept),
+                // The `div` is converted to a paragraph before scoring.
+                (Some("p"), NodeChange::Removed(CleanReason::ShareElement)),
+                (Some("p"), NodeChange::Kept),
+                (None, NodeChange::Kept),
+                (None, NodeChange::Removed(CleanReason::Comment)),
+                (Some("aside"), NodeChange::Removed(CleanReason::UnlikelyTag)),
+            ]
+        );
+        assert_eq!(diff[0].id.as_deref(), document.top_candidate());
+        assert_eq!(diff[3].text, "Share");
+        assert_eq!(
+            diff[0].locator.as_deref(),
+            Some("html > body:nth-child(2) > article:nth-child(1)")
+        );
+        assert_eq!(
+            diff[7].locator.as_deref(),
+            Some("html > body:nth-child(2) > article:nth-child(1) > aside:nth-child(4)")
+        );
+        // The paragraph converted from the `div` is not in the original document.
+        assert_eq!(diff[3].locator, None);
+
+        assert!(document
+            .diff_candidate("/0/99", &CleanOptions::default())
+            .is_none());
+    }
+}
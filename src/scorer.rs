@@ -1,35 +1,92 @@
+#[cfg(feature = "parallel")]
+use crate::dom::{ArenaDom, ArenaNodeData, NodeId};
 use crate::{
-    dom::{Handle, Node, NodeData, RcDom},
-    extractor::{self, extract_text},
-    html,
+    cleaner::{CleanDecision, CleanOptions, UrlKind},
+    diff::CleanReason,
+    dom::{self, Handle, Node, NodeData, RcDom},
+    extractor::{self, extract_text, extract_text_with},
+    features::{NodeClassifier, NodeFeatures},
+    footnotes, html, microdata, microformats, stats,
 };
 use html5ever::{
-    namespace_url, ns,
+    local_name, namespace_url, ns, parse_document,
+    tendril::TendrilSink,
     tree_builder::{ElementFlags, NodeOrText, TreeSink},
-    LocalName, QualName,
+    Attribute, LocalName, QualName,
 };
 use lazy_static::lazy_static;
-use regex::Regex;
-use std::{borrow::Cow, cell::Cell, collections::BTreeMap, path::Path, rc::Rc};
+use regex::{Regex, RegexSet};
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    path::Path,
+    ptr,
+    rc::Rc,
+};
 use url::Url;
 
-const PUNCTUATIONS_REGEX: &str = r"([、。，．！？]|\.[^A-Za-z0-9]|,[^0-9]|!|\?)";
-// TODO: remove "comment" from unlikely candidates
-const UNLIKELY_CANDIDATES: &str = "combx|comment|community|disqus|extra|foot|header|menu\
-     |remark|rss|shoutbox|sidebar|sponsor|ad-break|agegate\
+/// The default pattern for [`ScorerOptions::punctuations`].
+pub const PUNCTUATIONS_REGEX: &str = r"([、。，．！？]|\.[^A-Za-z0-9]|,[^0-9]|!|\?)";
+/// The score of the top candidate at which the confidence in the content is
+/// `0.5`, i.e. roughly ten well-formed paragraphs.
+const CONFIDENT_SCORE: f32 = 50.0;
+/// The text length of the content at which the confidence is `0.5`.
+const CONFIDENT_LENGTH: f32 = 1000.0;
+/// The minimal text length of content hints, see [`Scorer::find_content_hint`].
+pub const MIN_CONTENT_HINT_LENGTH: usize = 140;
+/// The commas of multiple scripts as counted by Readability.js.
+const COMMAS_REGEX: &str =
+    "[\u{002C}\u{060C}\u{FE50}\u{FE10}\u{FE11}\u{2E41}\u{2E34}\u{2E32}\u{FF0C}]";
+/// The prefix of candidate patterns whose alternatives only match at the start
+/// of a word, i.e. at the start of the value or after a character which is not
+/// alphanumeric.
+const WORD_START: &str = "(?:^|[^A-Za-z0-9])(?:";
+/// The default pattern for [`ScorerOptions::unlikely_candidates`], which only
+/// matches at the start of a word.
+pub const UNLIKELY_CANDIDATES: &str = "(?:^|[^A-Za-z0-9])(?:combx|comment|community|disqus\
+     |extra|foot|header|menu|remark|rss|shoutbox|sidebar|sponsor|ad-break|agegate\
      |pagination|pager|popup|tweet|twitter\
-     |ssba";
-const LIKELY_CANDIDATES: &str = "and|article|body|column|main|shadow\
+     |ssba)";
+/// The default pattern for [`ScorerOptions::likely_candidates`].
+pub const LIKELY_CANDIDATES: &str = "and|article|body|column|main|shadow\
                                               |content|hentry";
-const POSITIVE_CANDIDATES: &str = "article|body|content|entry|hentry|main|page\
+/// The default pattern for [`ScorerOptions::positive_candidates`].
+pub const POSITIVE_CANDIDATES: &str = "article|body|content|entry|hentry|main|page\
      |pagination|post|text|blog|story";
-// TODO: remove "comment" and "com" from unlikely candidates
-const NEGATIVE_CANDIDATES: &str = "combx|comment|com|contact|foot|footer|footnote\
-     |masthead|media|meta|outbrain|promo|related\
+/// The default pattern for [`ScorerOptions::negative_candidates`], which only
+/// matches at the start of a word.
+pub const NEGATIVE_CANDIDATES: &str = "(?:^|[^A-Za-z0-9])(?:combx|comment|com-|contact|foot\
+     |footer|footnote|masthead|media|meta|outbrain|promo|related\
      |scroll|shoutbox|sidebar|sponsor|shopping\
      |tags|tool|widget|form|textfield\
-     |uiScale|hidden";
-const BLOCK_CHILD_TAGS: [&str; 10] = [
+     |uiScale|hidden)";
+/// The default pattern for [`ScorerOptions::byline_candidates`].
+pub const BYLINE_CANDIDATES: &str = "byline|author|dateline|writtenby|p-author";
+/// The default pattern for [`ScorerOptions::overlay_elements`], i.e. the
+/// containers of common consent managers, cookie banners, and modal overlays.
+pub const OVERLAY_ELEMENTS: &str =
+    "(?i)onetrust|optanon|qc-cmp|cookiebot|didomi|truste-|usercentrics\
+     |sp_message|cmp-(container|banner|modal)|cookie[-_]?(banner|consent|notice|bar|law)\
+     |consent[-_]?(banner|modal|manager|popup)|gdpr[-_]?(banner|consent|modal)\
+     |(modal|popup)[-_]?(overlay|backdrop)|(paywall|subscribe)[-_]?modal|interstitial";
+/// The attribute prefixes of consent managers, e.g. `data-cmp-host`.
+const CONSENT_ATTRIBUTES: [&str; 4] = [
+    "data-cmp",
+    "data-consent",
+    "data-cookieconsent",
+    "data-gdpr",
+];
+/// The attributes of ad slots, e.g. of AdSense or Google Publisher Tags.
+const AD_SLOT_ATTRIBUTES: [&str; 4] = [
+    "data-ad-slot",
+    "data-ad-client",
+    "data-ad-unit-path",
+    "data-google-query-id",
+];
+/// The default tags for [`ScorerOptions::block_child_tags`].
+pub const BLOCK_CHILD_TAGS: [&str; 10] = [
     "a",
     "blockquote",
     "dl",
@@ -41,12 +98,95 @@ const BLOCK_CHILD_TAGS: [&str; 10] = [
     "table",
     "ul",
 ];
+/// The default initial content scores for [`ScorerOptions::tag_scores`].
+pub const TAG_SCORES: [(&str, f32); 20] = [
+    ("article", 10.0),
+    ("div", 5.0),
+    ("pre", 3.0),
+    ("td", 3.0),
+    ("blockquote", 3.0),
+    ("address", -3.0),
+    ("ol", -3.0),
+    ("ul", -3.0),
+    ("dl", -3.0),
+    ("dd", -3.0),
+    ("dt", -3.0),
+    ("li", -3.0),
+    ("form", -3.0),
+    ("h1", -5.0),
+    ("h2", -5.0),
+    ("h3", -5.0),
+    ("h4", -5.0),
+    ("h5", -5.0),
+    ("h6", -5.0),
+    ("th", -5.0),
+];
+/// The AMP media elements which are translated to HTML in preprocessing.
+const AMP_ELEMENTS: [&str; 6] = [
+    "amp-img",
+    "amp-anim",
+    "amp-video",
+    "amp-audio",
+    "amp-iframe",
+    "amp-youtube",
+];
+/// The tags which prevent a `div` from being converted to a paragraph.
+const DIV_TO_P_BLOCK_TAGS: [&str; 25] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "img",
+    "ol",
+    "p",
+    "pre",
+    "table",
+    "ul",
+];
 lazy_static! {
     static ref PUNCTUATIONS: Regex = Regex::new(PUNCTUATIONS_REGEX).unwrap();
+    static ref COMMAS: Regex = Regex::new(COMMAS_REGEX).unwrap();
     static ref LIKELY: Regex = Regex::new(LIKELY_CANDIDATES).unwrap();
     static ref UNLIKELY: Regex = Regex::new(UNLIKELY_CANDIDATES).unwrap();
     static ref POSITIVE: Regex = Regex::new(POSITIVE_CANDIDATES).unwrap();
     static ref NEGATIVE: Regex = Regex::new(NEGATIVE_CANDIDATES).unwrap();
+    static ref BYLINE: Regex = Regex::new(BYLINE_CANDIDATES).unwrap();
+    static ref OVERLAY: Regex = Regex::new(OVERLAY_ELEMENTS).unwrap();
+    static ref LEGACY_UNLIKELY: Regex = Regex::new(&legacy_pattern(UNLIKELY_CANDIDATES)).unwrap();
+    static ref LEGACY_NEGATIVE: Regex = Regex::new(&legacy_pattern(NEGATIVE_CANDIDATES)).unwrap();
+    /// The default unlikely, likely, positive, negative, and byline candidates
+    /// in a single set.
+    static ref DEFAULT_CANDIDATES: RegexSet = RegexSet::new([
+        UNLIKELY_CANDIDATES,
+        LIKELY_CANDIDATES,
+        POSITIVE_CANDIDATES,
+        NEGATIVE_CANDIDATES,
+        BYLINE_CANDIDATES,
+    ])
+    .unwrap();
+    static ref LEGACY_CANDIDATES: RegexSet = RegexSet::new([
+        LEGACY_UNLIKELY.as_str(),
+        LIKELY_CANDIDATES,
+        POSITIVE_CANDIDATES,
+        LEGACY_NEGATIVE.as_str(),
+        BYLINE_CANDIDATES,
+    ])
+    .unwrap();
 }
 
 #[derive(Clone)]
@@ -87,13 +227,183 @@ impl<'a> TopCandidate<'a> {
 
 /// Distribution of the content score among parent nodes.
 #[derive(Debug, Clone)]
-pub enum CandidateScore {
+pub enum CandidateScore<'a> {
     /// The same weight for all parent nodes.
     EqualWeight,
     /// The weight decreases with the level of the parent node.
     ///
     /// For example, a parent node will be weighted more than a grandparent.
     LevelWeight,
+    /// The scoring of Readability.js, which is useful to cross-validate the
+    /// results with the reference implementation.
+    ///
+    /// The content score counts commas in multiple scripts instead of
+    /// punctuations, and the text length of whitespace-normalized text. The
+    /// parent gets the full content score, the grandparent half of it, and
+    /// further ancestors a third of it divided by their level.
+    ReadabilityJs,
+    /// The weights of [`CandidateScore::ReadabilityJs`], i.e. `1`, `1/2`, and
+    /// `1/(3 * level)`, without its content score.
+    ReadabilityJsWeight,
+    /// A custom weight by level, e.g. for site-specific tuning, where the
+    /// parent node is at level 2.
+    ///
+    /// ```
+    /// use readability::{CandidateScore, ScorerOptions};
+    ///
+    /// let decay = |level: usize| 0.5_f32.powi(level as i32 - 2);
+    /// let options = ScorerOptions {
+    ///     candidate_score: CandidateScore::Decay(&decay),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(options.candidate_score.weight(3), 0.5);
+    /// ```
+    Decay(&'a dyn ScoreDecay),
+}
+
+impl CandidateScore<'_> {
+    /// The weight of the content score for a parent node at the given level,
+    /// where the parent is at level 2.
+    pub fn weight(&self, level: usize) -> f32 {
+        match self {
+            Self::EqualWeight => 1.0,
+            Self::LevelWeight => 1.0 / level as f32,
+            Self::ReadabilityJs | Self::ReadabilityJsWeight => match level {
+                0..=2 => 1.0,
+                3 => 0.5,
+                _ => 1.0 / ((level - 2) as f32 * 3.0),
+            },
+            Self::Decay(decay) => decay.weight(level),
+        }
+    }
+}
+
+/// A custom weight of the content score by level, see
+/// [`CandidateScore::Decay`].
+///
+/// Implemented for closures `Fn(usize) -> f32`.
+pub trait ScoreDecay: Send + Sync {
+    /// Return the weight for a parent node at the given level.
+    fn weight(&self, level: usize) -> f32;
+}
+
+impl<F> ScoreDecay for F
+where
+    F: Fn(usize) -> f32 + Send + Sync,
+{
+    fn weight(&self, level: usize) -> f32 {
+        self(level)
+    }
+}
+
+impl fmt::Debug for dyn ScoreDecay + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ScoreDecay")
+    }
+}
+
+/// The metric to measure the length of text.
+///
+/// The length thresholds of the scorer assume space-delimited languages, i.e.
+/// a few characters per word. CJK characters are much denser, so articles in
+/// Chinese or Japanese are scored too low with [`LengthMetric::Chars`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LengthMetric {
+    /// Count characters.
+    #[default]
+    Chars,
+    /// Count characters, but weight CJK characters with the given multiplier.
+    CjkWeighted { multiplier: f32 },
+    /// Estimate the number of words, where each CJK character counts as a word,
+    /// and multiply them with the average word length so that the thresholds
+    /// in characters still apply. Use a `chars_per_word` of `1.0` to express
+    /// the thresholds in words.
+    Words { chars_per_word: f32 },
+    /// Count grapheme clusters, e.g. an emoji with a skin tone modifier counts
+    /// as a single grapheme.
+    ///
+    /// Requires the `unicode-segmentation` feature, and counts characters
+    /// otherwise.
+    Graphemes,
+}
+
+impl LengthMetric {
+    /// Measure the length of a text.
+    pub fn len(&self, text: &str) -> f32 {
+        match *self {
+            LengthMetric::Chars => text.chars().count() as f32,
+            LengthMetric::CjkWeighted { multiplier } => text
+                .chars()
+                .map(|c| if is_cjk(c) { multiplier } else { 1.0 })
+                .sum(),
+            LengthMetric::Words { chars_per_word } => {
+                stats::estimate_words(text) as f32 * chars_per_word
+            }
+            LengthMetric::Graphemes => stats::count_graphemes(text) as f32,
+        }
+    }
+}
+
+/// How the class weight is applied to an element whose `id` and `class` both
+/// match the positive or negative candidates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ClassWeightMode {
+    /// Apply the weight once per matching attribute, e.g. `id="post"` and
+    /// `class="post"` add the positive weight twice.
+    #[default]
+    PerAttribute,
+    /// Apply the positive and negative weights at most once per element.
+    PerElement,
+}
+
+/// Check if a character is a Chinese, Japanese, or Korean character.
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{1100}'..='\u{11ff}' // Hangul Jamo
+        | '\u{3040}'..='\u{30ff}' // Hiragana and Katakana
+        | '\u{3130}'..='\u{318f}' // Hangul Compatibility Jamo
+        | '\u{3400}'..='\u{4dbf}' // CJK Unified Ideographs Extension A
+        | '\u{4e00}'..='\u{9fff}' // CJK Unified Ideographs
+        | '\u{ac00}'..='\u{d7af}' // Hangul Syllables
+        | '\u{f900}'..='\u{faff}' // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{2fa1f}' // CJK Unified Ideographs Extension B-F
+    )
+}
+
+/// Stop scoring once the best candidate is confident enough, see
+/// [`ScorerOptions::early_termination`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct EarlyTermination {
+    /// The number of nodes which are scored before the best candidate is
+    /// checked, and between the following checks.
+    pub min_nodes: usize,
+    /// The confidence of the best candidate between `0.0` and `1.0` to stop
+    /// scoring at, see [`Readable::confidence`](crate::Readable::confidence).
+    pub min_confidence: f32,
+}
+
+/// The statistics of the candidate detection, which show if the scoring was
+/// cut short by [`ScorerOptions::max_candidates`] or
+/// [`ScorerOptions::early_termination`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CandidateStats {
+    /// The number of scored nodes.
+    pub nodes: usize,
+    /// The number of candidates.
+    pub candidates: usize,
+    /// Whether candidates were dropped because of
+    /// [`ScorerOptions::max_candidates`].
+    pub candidate_limit_reached: bool,
+    /// Whether the remaining nodes were skipped because of
+    /// [`ScorerOptions::early_termination`].
+    pub terminated_early: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -103,7 +413,7 @@ pub struct ScorerOptions<'a> {
     /// The maximal number of parent nodes that will be traversed.
     pub max_candidate_parents: usize,
     /// Distribution of the content score among parent nodes.
-    pub candidate_score: CandidateScore,
+    pub candidate_score: CandidateScore<'a>,
     /// The regex for punctuations.
     pub punctuations: &'a Regex,
     /// The regex for unlikely candidates.
@@ -118,7 +428,110 @@ pub struct ScorerOptions<'a> {
     pub negative_candidates: &'a Regex,
     /// The weight of negative candidates to determine the content score.
     pub negative_candidate_weight: f32,
+    /// Apply the class weight per matching attribute or per element.
+    pub class_weight_mode: ClassWeightMode,
+    /// Bound the absolute class weight of an element, so the naming of an
+    /// element can't outweigh its content.
+    pub class_weight_cap: Option<f32>,
+    /// The regex for bylines, which are removed from the content.
+    pub byline_candidates: &'a Regex,
+    /// The options to remove boilerplate from the content.
+    pub clean_options: CleanOptions<'a>,
     pub block_child_tags: &'a [&'a str],
+    /// The initial content score of candidates by tag name, which is `0.0`
+    /// for other tags. The score of `article` elements is ignored for
+    /// [`CandidateScore::ReadabilityJs`].
+    ///
+    /// Add e.g. `("section", 5.0)` to score `section` elements like `div`
+    /// elements, or `("article-body", 10.0)` for a custom element.
+    pub tag_scores: &'a [(&'a str, f32)],
+    /// The metric to measure the length of text for `min_candidate_length`,
+    /// the content score, and the removal of useless nodes.
+    pub length_metric: LengthMetric,
+    /// Replace `iframe` elements with a `srcdoc` attribute by a `div` with the
+    /// parsed `srcdoc` content during preprocessing, so the embedded content
+    /// can be scored.
+    pub inline_iframe_srcdoc: bool,
+    /// Replace declarative shadow roots, i.e. `template` elements with a
+    /// `shadowrootmode` attribute, by their content during preprocessing, so
+    /// the content can be scored. Open shadow roots are usually inlined by
+    /// the parser already, but closed and legacy `shadowroot` templates are
+    /// not.
+    pub hoist_shadow_roots: bool,
+    /// Remove cookie consent banners and modal overlays during preprocessing,
+    /// so their text can't be scored. Overlays are elements whose `id` or
+    /// `class` matches [`ScorerOptions::overlay_elements`], elements with
+    /// attributes of consent managers like `data-cmp-host`, modal dialogs,
+    /// and fixed full-screen elements, unless they contain an `article` or
+    /// `main` element.
+    pub remove_overlays: bool,
+    /// The pattern of the `id` or `class` of overlays, see
+    /// [`ScorerOptions::remove_overlays`].
+    pub overlay_elements: &'a Regex,
+    /// Remove ad slots by their attributes during preprocessing, e.g.
+    /// `data-ad-slot`, `data-google-query-id`, an `id` starting with
+    /// `div-gpt-ad`, `aria-label="advertisement"`, or `ins.adsbygoogle`,
+    /// since their classes are often hashed and don't match the unlikely
+    /// candidates.
+    pub remove_ad_slots: bool,
+    /// Convert `div` elements without block elements to paragraphs, and
+    /// replace `div` elements containing a single paragraph by the paragraph
+    /// during preprocessing.
+    pub convert_divs_to_paragraphs: bool,
+    /// Score and clean custom elements, i.e. unknown elements with a hyphen in
+    /// their name like `article-content`, like `div` elements, unless their
+    /// tag name is in [`ScorerOptions::tag_scores`].
+    pub custom_elements_as_divs: bool,
+    /// The weight of the text of fragment links, e.g. footnote markers like
+    /// `href="#note-1"`, for the link density. Links to other pages have a
+    /// weight of `1.0`.
+    pub hash_link_coefficient: f32,
+    /// Match the unlikely and negative candidates anywhere in an `id` or
+    /// `class` instead of at the start of a word, and `com-` like `com`, as in
+    /// previous versions.
+    ///
+    /// By default, `comment` matches `post-comments` but not `postcomments`,
+    /// and `com-` matches `com-box` but not `telecom-box`. This applies to the
+    /// default patterns and to patterns composed from them with
+    /// [`CandidatePatterns`]. Other patterns, e.g. compiled with
+    /// [`candidate_regex`], are always matched as given.
+    pub legacy_candidate_matching: bool,
+    /// Convert the text after any second `br` element to a paragraph, even if
+    /// the `br` elements are separated by text, and drop the `br` elements in
+    /// the text output, as in previous versions.
+    ///
+    /// By default, only the text after two consecutive `br` elements is
+    /// converted to a paragraph, and single `br` elements are kept as line
+    /// breaks, e.g. in poems and addresses.
+    pub legacy_line_breaks: bool,
+    /// Calculate the content scores of candidates in parallel, which speeds up
+    /// the scoring of very large documents.
+    ///
+    /// The document is copied before scoring, which makes the scoring slower
+    /// on a single core, see the `extract parallel` benchmark.
+    ///
+    /// Requires the `parallel` feature, and is ignored otherwise.
+    pub parallel: bool,
+    /// Use the content of a single `h-entry` or `hentry`, i.e. the `e-content`
+    /// or `entry-content` element, or the `articleBody` of a single schema.org
+    /// article as top candidate if it's long enough.
+    pub content_hints: bool,
+    /// Score the nodes with a classifier instead of the candidate detection
+    /// and the content score, e.g. a model trained on
+    /// [`ScoredDocument::node_features`](crate::ScoredDocument::node_features).
+    ///
+    /// The content scores are not calculated in parallel if a classifier is
+    /// set.
+    pub classifier: Option<&'a dyn NodeClassifier>,
+    /// The maximal number of candidates, which limits the memory on
+    /// pathological pages with many thousands of paragraphs.
+    ///
+    /// Once the limit is reached, the scores of existing candidates are still
+    /// updated, but no new candidates are created.
+    pub max_candidates: Option<usize>,
+    /// Stop scoring the remaining nodes once the best candidate is confident
+    /// enough.
+    pub early_termination: Option<EarlyTermination>,
 }
 
 impl Default for ScorerOptions<'_> {
@@ -134,66 +547,452 @@ impl Default for ScorerOptions<'_> {
             positive_candidate_weight: 25.0,
             negative_candidates: &NEGATIVE,
             negative_candidate_weight: 25.0,
+            class_weight_mode: ClassWeightMode::default(),
+            class_weight_cap: None,
+            byline_candidates: &BYLINE,
+            clean_options: CleanOptions::default(),
             block_child_tags: &BLOCK_CHILD_TAGS,
+            tag_scores: &TAG_SCORES,
+            length_metric: LengthMetric::default(),
+            inline_iframe_srcdoc: false,
+            hoist_shadow_roots: false,
+            remove_overlays: true,
+            overlay_elements: &OVERLAY,
+            remove_ad_slots: true,
+            convert_divs_to_paragraphs: true,
+            custom_elements_as_divs: false,
+            hash_link_coefficient: 0.3,
+            legacy_candidate_matching: false,
+            legacy_line_breaks: false,
+            parallel: false,
+            content_hints: true,
+            classifier: None,
+            max_candidates: None,
+            early_termination: None,
+        }
+    }
+}
+
+/// Compile a list of patterns into a single regex matching any of them.
+pub fn candidate_regex<S>(patterns: &[S]) -> Result<Regex, regex::Error>
+where
+    S: AsRef<str>,
+{
+    let patterns = patterns
+        .iter()
+        .map(|pattern| pattern.as_ref())
+        .collect::<Vec<_>>();
+    Regex::new(&patterns.join("|"))
+}
+
+/// Owned candidate regexes, which are composed from the default patterns and
+/// borrowed by [`ScorerOptions`].
+///
+/// The unlikely and negative candidates keep matching only at the start of a
+/// word, including added alternatives, see
+/// [`ScorerOptions::legacy_candidate_matching`].
+///
+/// ```
+/// use readability::{CandidatePatterns, ScorerOptions};
+///
+/// let patterns = CandidatePatterns::default()
+///     .without_unlikely(&["comment"])
+///     .and_then(|patterns| patterns.with_extra_unlikely("newsletter|promo"))
+///     .unwrap();
+/// let options = ScorerOptions::from(&patterns);
+///
+/// assert!(options.unlikely_candidates.is_match("newsletter"));
+/// assert!(!options.unlikely_candidates.is_match("comment"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CandidatePatterns {
+    pub unlikely: Regex,
+    pub likely: Regex,
+    pub positive: Regex,
+    pub negative: Regex,
+}
+
+impl Default for CandidatePatterns {
+    fn default() -> Self {
+        Self {
+            unlikely: UNLIKELY.clone(),
+            likely: LIKELY.clone(),
+            positive: POSITIVE.clone(),
+            negative: NEGATIVE.clone(),
         }
     }
 }
 
+impl CandidatePatterns {
+    /// Add patterns to the unlikely candidates, e.g. `"foo|bar"`.
+    pub fn with_extra_unlikely(mut self, patterns: &str) -> Result<Self, regex::Error> {
+        self.unlikely = with_patterns(&self.unlikely, patterns)?;
+        Ok(self)
+    }
+
+    /// Add patterns to the likely candidates, e.g. `"foo|bar"`.
+    pub fn with_extra_likely(mut self, patterns: &str) -> Result<Self, regex::Error> {
+        self.likely = with_patterns(&self.likely, patterns)?;
+        Ok(self)
+    }
+
+    /// Add patterns to the positive candidates, e.g. `"foo|bar"`.
+    pub fn with_extra_positive(mut self, patterns: &str) -> Result<Self, regex::Error> {
+        self.positive = with_patterns(&self.positive, patterns)?;
+        Ok(self)
+    }
+
+    /// Add patterns to the negative candidates, e.g. `"foo|bar"`.
+    pub fn with_extra_negative(mut self, patterns: &str) -> Result<Self, regex::Error> {
+        self.negative = with_patterns(&self.negative, patterns)?;
+        Ok(self)
+    }
+
+    /// Remove alternatives from the unlikely candidates, e.g. `["comment"]`.
+    pub fn without_unlikely(mut self, patterns: &[&str]) -> Result<Self, regex::Error> {
+        self.unlikely = without_patterns(&self.unlikely, patterns)?;
+        Ok(self)
+    }
+
+    /// Remove alternatives from the likely candidates, e.g. `["shadow"]`.
+    pub fn without_likely(mut self, patterns: &[&str]) -> Result<Self, regex::Error> {
+        self.likely = without_patterns(&self.likely, patterns)?;
+        Ok(self)
+    }
+
+    /// Remove alternatives from the positive candidates, e.g. `["page"]`.
+    pub fn without_positive(mut self, patterns: &[&str]) -> Result<Self, regex::Error> {
+        self.positive = without_patterns(&self.positive, patterns)?;
+        Ok(self)
+    }
+
+    /// Remove alternatives from the negative candidates, e.g. `["com-"]`.
+    pub fn without_negative(mut self, patterns: &[&str]) -> Result<Self, regex::Error> {
+        self.negative = without_patterns(&self.negative, patterns)?;
+        Ok(self)
+    }
+}
+
+impl<'a> From<&'a CandidatePatterns> for ScorerOptions<'a> {
+    fn from(patterns: &'a CandidatePatterns) -> Self {
+        Self {
+            unlikely_candidates: &patterns.unlikely,
+            likely_candidates: &patterns.likely,
+            positive_candidates: &patterns.positive,
+            negative_candidates: &patterns.negative,
+            ..Default::default()
+        }
+    }
+}
+
+/// Split a pattern into its alternatives, and whether they only match at the
+/// start of a word like the default unlikely and negative candidates.
+fn split_word_start(pattern: &str) -> (&str, bool) {
+    match pattern
+        .strip_prefix(WORD_START)
+        .and_then(|alternatives| alternatives.strip_suffix(')'))
+    {
+        Some(alternatives) => (alternatives, true),
+        None => (pattern, false),
+    }
+}
+
+/// Compile alternatives into a single regex, which only matches at the start
+/// of a word if `word_start` is set.
+fn compose_patterns(alternatives: &[&str], word_start: bool) -> Result<Regex, regex::Error> {
+    if word_start {
+        Regex::new(&format!("{WORD_START}{})", alternatives.join("|")))
+    } else {
+        candidate_regex(alternatives)
+    }
+}
+
+/// Add alternatives to a regex, which keeps matching only at the start of a
+/// word if it did.
+fn with_patterns(regex: &Regex, patterns: &str) -> Result<Regex, regex::Error> {
+    let (alternatives, word_start) = split_word_start(regex.as_str());
+    compose_patterns(&[alternatives, patterns], word_start)
+}
+
+/// Remove top-level alternatives from a regex, which keeps matching only at
+/// the start of a word if it did.
+fn without_patterns(regex: &Regex, patterns: &[&str]) -> Result<Regex, regex::Error> {
+    let (alternatives, word_start) = split_word_start(regex.as_str());
+    let retained = alternatives
+        .split('|')
+        .filter(|alternative| !patterns.contains(&alternative.trim()))
+        .collect::<Vec<_>>();
+    compose_patterns(&retained, word_start)
+}
+
+/// The pattern for legacy matching, i.e. the alternatives of a pattern
+/// matching at the start of a word match anywhere, and `com-` matches like
+/// `com`. Other patterns are matched as given.
+fn legacy_pattern(pattern: &str) -> Cow<'_, str> {
+    match split_word_start(pattern) {
+        (alternatives, true) => {
+            let alternatives = alternatives
+                .split('|')
+                .map(|alternative| match alternative.trim() {
+                    "com-" => "com",
+                    _ => alternative,
+                })
+                .collect::<Vec<_>>();
+            Cow::Owned(alternatives.join("|"))
+        }
+        (pattern, false) => Cow::Borrowed(pattern),
+    }
+}
+
+/// Classification of an `id` or `class` attribute.
+#[derive(Debug, Default, Clone, Copy)]
+struct Classes {
+    unlikely: bool,
+    likely: bool,
+    positive: bool,
+    negative: bool,
+    byline: bool,
+}
+
+/// The candidate patterns of a [`Scorer`].
+#[derive(Debug, Clone)]
+enum Classifier<'a> {
+    /// The default candidates in a single set.
+    Default(&'static RegexSet),
+    /// Custom candidates, which are matched one by one, so they aren't
+    /// compiled again and keep the flags of a `RegexBuilder`. Only the unlikely
+    /// and negative candidates are compiled again for legacy matching if they
+    /// match at the start of a word.
+    Custom {
+        unlikely: Cow<'a, Regex>,
+        negative: Cow<'a, Regex>,
+    },
+}
+
+#[derive(Debug, Clone)]
 pub struct Scorer<'a> {
     options: ScorerOptions<'a>,
+    classifier: Classifier<'a>,
+}
+
+impl Default for Scorer<'_> {
+    fn default() -> Self {
+        Scorer {
+            options: ScorerOptions::default(),
+            classifier: Classifier::Default(&DEFAULT_CANDIDATES),
+        }
+    }
 }
 
 impl<'a> Scorer<'a> {
-    pub fn new(options: ScorerOptions<'a>) -> Self {
-        Scorer { options }
+    /// Create a scorer, which prepares the candidate patterns once.
+    ///
+    /// Returns an error if the unlikely or negative candidates can't be
+    /// compiled for [`ScorerOptions::legacy_candidate_matching`].
+    pub fn new(options: ScorerOptions<'a>) -> Result<Self, regex::Error> {
+        let legacy = options.legacy_candidate_matching;
+        let is_default = ptr::eq(options.unlikely_candidates, &*UNLIKELY)
+            && ptr::eq(options.likely_candidates, &*LIKELY)
+            && ptr::eq(options.positive_candidates, &*POSITIVE)
+            && ptr::eq(options.negative_candidates, &*NEGATIVE)
+            && ptr::eq(options.byline_candidates, &*BYLINE);
+        let candidates = |regex: &'a Regex| -> Result<Cow<'a, Regex>, regex::Error> {
+            match legacy_pattern(regex.as_str()) {
+                Cow::Owned(pattern) if legacy => Regex::new(&pattern).map(Cow::Owned),
+                _ => Ok(Cow::Borrowed(regex)),
+            }
+        };
+        let classifier = match (is_default, legacy) {
+            (true, false) => Classifier::Default(&DEFAULT_CANDIDATES),
+            (true, true) => Classifier::Default(&LEGACY_CANDIDATES),
+            (false, _) => Classifier::Custom {
+                unlikely: candidates(options.unlikely_candidates)?,
+                negative: candidates(options.negative_candidates)?,
+            },
+        };
+
+        Ok(Scorer {
+            options,
+            classifier,
+        })
+    }
+
+    /// A scorer with other clean options, which reuses the candidate patterns.
+    pub(crate) fn with_clean_options(&self, clean_options: CleanOptions<'a>) -> Self {
+        Scorer {
+            options: ScorerOptions {
+                clean_options,
+                ..self.options.clone()
+            },
+            classifier: self.classifier.clone(),
+        }
+    }
+
+    fn classify(&self, value: &str) -> Classes {
+        match self.classifier {
+            Classifier::Default(candidates) => {
+                let matches = candidates.matches(value);
+
+                Classes {
+                    unlikely: matches.matched(0),
+                    likely: matches.matched(1),
+                    positive: matches.matched(2),
+                    negative: matches.matched(3),
+                    byline: matches.matched(4),
+                }
+            }
+            Classifier::Custom {
+                ref unlikely,
+                ref negative,
+            } => Classes {
+                unlikely: unlikely.is_match(value),
+                likely: self.options.likely_candidates.is_match(value),
+                positive: self.options.positive_candidates.is_match(value),
+                negative: negative.is_match(value),
+                byline: self.options.byline_candidates.is_match(value),
+            },
+        }
+    }
+
+    /// Check if an `id` or `class` matches the unlikely candidates, but not
+    /// the likely candidates.
+    pub(crate) fn is_unlikely(&self, value: &str) -> bool {
+        let classes = self.classify(value);
+        classes.unlikely && !classes.likely
     }
 
-    pub fn preprocess(&self, dom: &mut RcDom, handle: Handle, title: &mut String) -> bool {
+    /// Find the byline, i.e. the author of the article, and remove its node.
+    ///
+    /// A byline is an element with `rel="author"`, an `itemprop` containing
+    /// `author`, or an `id` or `class` matching the byline candidates, and a
+    /// text shorter than 100 characters.
+    pub fn find_byline(&self, dom: &mut RcDom, handle: &Handle) -> Option<String> {
+        let children = handle.children.borrow().clone();
+
+        for child in children.iter() {
+            let NodeData::Element {
+                ref name,
+                ref attrs,
+                ..
+            } = child.data
+            else {
+                continue;
+            };
+
+            match name.local {
+                local_name!("script") | local_name!("style") | local_name!("head") => continue,
+                local_name!("html") | local_name!("body") => (),
+                _ => {
+                    let is_byline = {
+                        let attrs = attrs.borrow();
+                        let rel = html::attr("rel", &attrs);
+                        let itemprop = html::attr("itemprop", &attrs);
+                        let match_string = [html::attr("class", &attrs), html::attr("id", &attrs)]
+                            .into_iter()
+                            .flatten()
+                            .collect::<Vec<_>>()
+                            .join(" ");
+
+                        rel.as_deref() == Some("author")
+                            || itemprop.is_some_and(|itemprop| itemprop.contains("author"))
+                            || self.classify(&match_string).byline
+                    };
+
+                    if is_byline {
+                        let mut text = String::new();
+                        extract_text(child, &mut text, true);
+                        let byline = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+                        if !byline.is_empty() && byline.chars().count() < 100 {
+                            dom.remove_from_parent(child);
+                            return Some(byline);
+                        }
+                    }
+                }
+            }
+
+            if let Some(byline) = self.find_byline(dom, child) {
+                return Some(byline);
+            }
+        }
+
+        None
+    }
+
+    pub fn preprocess(&self, dom: &mut RcDom, handle: &Handle, title: &mut String) -> bool {
         if let NodeData::Element {
             ref name,
             ref attrs,
             ..
-        } = handle.clone().data
+        } = handle.data
         {
-            let tag_name = name.local.as_ref();
-            match tag_name.to_lowercase().as_ref() {
-                "script" | "link" | "style" => return true,
-                "title" => extractor::extract_text(handle.clone(), title, true),
+            // SVG and MathML keep their styles and are never unlikely candidates.
+            let is_foreign = is_foreign(name);
+            match name.local {
+                local_name!("script") => return true,
+                local_name!("link") | local_name!("style") if !is_foreign => return true,
+                local_name!("title") if !is_foreign => extractor::extract_text(handle, title, true),
                 _ => (),
             }
+            let is_body = name.local == local_name!("body");
             for name in ["id", "class"].iter() {
                 if let Some(val) = html::attr(name, &attrs.borrow()) {
-                    if tag_name != "body"
-                        && self.options.unlikely_candidates.is_match(&val)
-                        && !self.options.likely_candidates.is_match(&val)
-                    {
+                    if !is_body && !is_foreign && self.is_unlikely(&val) {
                         return true;
                     }
                 }
             }
+            if self.options.remove_ad_slots && !is_foreign && is_ad_slot(name, &attrs.borrow()) {
+                return true;
+            }
+            if self.options.remove_overlays
+                && !is_foreign
+                && !matches!(name.local, local_name!("html") | local_name!("body"))
+                && self.is_overlay(handle, &attrs.borrow())
+            {
+                return true;
+            }
+        }
+        if self.options.inline_iframe_srcdoc {
+            inline_iframe_srcdoc(dom, handle);
+        }
+        if self.options.hoist_shadow_roots {
+            hoist_shadow_roots(dom, handle);
         }
         let mut useless_nodes = vec![];
         let mut paragraph_nodes = vec![];
+        let mut amp_elements = vec![];
+        let mut div_nodes = vec![];
         let mut br_count = 0;
         for child in handle.children.borrow().iter() {
-            if self.preprocess(dom, child.clone(), title) {
+            let useless = self.preprocess(dom, child, title);
+            if useless {
                 useless_nodes.push(child.clone());
             }
-            let c = child.clone();
-            match c.data {
+            match child.data {
                 NodeData::Element { ref name, .. } => {
-                    let tag_name = name.local.as_ref();
-                    if "br" == tag_name.to_lowercase() {
+                    if name.local == local_name!("br") {
                         br_count += 1
                     } else {
                         br_count = 0
                     }
+                    if AMP_ELEMENTS.contains(&&*name.local) {
+                        amp_elements.push(child.clone());
+                    }
+                    if name.local == local_name!("div") && !useless {
+                        div_nodes.push(child.clone());
+                    }
                 }
                 NodeData::Text { ref contents } => {
                     let s = contents.borrow();
-                    if br_count >= 2 && !s.trim().is_empty() {
-                        paragraph_nodes.push(child.clone());
-                        br_count = 0
+                    if !s.trim().is_empty() {
+                        if br_count >= 2 {
+                            paragraph_nodes.push(child.clone());
+                            br_count = 0
+                        } else if !self.options.legacy_line_breaks {
+                            // Lines separated by single `br` elements, e.g. of a poem.
+                            br_count = 0
+                        }
                     }
                 }
                 _ => (),
@@ -207,22 +1006,90 @@ impl<'a> Scorer<'a> {
             let p = dom.create_element(name, vec![], ElementFlags::default());
             dom.append_before_sibling(node, NodeOrText::AppendNode(p.clone()));
             dom.remove_from_parent(node);
-            if let NodeData::Text { ref contents } = node.clone().data {
-                let text = contents.clone().into_inner().clone();
+            if let NodeData::Text { ref contents } = node.data {
+                let text = contents.borrow().clone();
                 dom.append(&p, NodeOrText::AppendText(text))
             }
         }
+        for node in amp_elements.iter() {
+            translate_amp_element(dom, node);
+        }
+        if self.options.convert_divs_to_paragraphs {
+            for node in div_nodes.iter() {
+                convert_div_to_paragraph(dom, node);
+            }
+        }
         false
     }
 
+    /// Check if an element is a consent banner or a modal overlay, see
+    /// [`ScorerOptions::remove_overlays`].
+    fn is_overlay(&self, handle: &Handle, attrs: &[Attribute]) -> bool {
+        let is_overlay = attrs.iter().any(|attr| {
+            let name: &str = &attr.name.local;
+            match name {
+                "id" | "class" => self.options.overlay_elements.is_match(&attr.value),
+                "style" => is_full_screen_style(&attr.value),
+                "aria-modal" => attr.value.eq_ignore_ascii_case("true"),
+                _ => CONSENT_ATTRIBUTES
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix)),
+            }
+        });
+
+        // Keep fixed wrappers of the whole page, e.g. of single-page apps.
+        is_overlay && !html::has_nodes(handle, &["article", "main"])
+    }
+
     /// Find candidate tags in DOM node, and distribute score among them.
     pub fn find_candidates(
         &self,
         node_id: &Path,
-        handle: Handle,
+        handle: &Handle,
+        candidates: &mut BTreeMap<String, Candidate>,
+        nodes: &mut BTreeMap<String, Rc<Node>>,
+    ) -> CandidateStats {
+        #[cfg(feature = "parallel")]
+        let content_scores = self
+            .options
+            .parallel
+            .then(|| self.parallel_content_scores(node_id, handle))
+            .filter(|_| self.options.classifier.is_none());
+        #[cfg(not(feature = "parallel"))]
+        let content_scores = None;
+
+        let mut stats = CandidateStats::default();
+        self.distribute_content_scores(
+            node_id,
+            handle,
+            candidates,
+            nodes,
+            content_scores.as_ref(),
+            &mut stats,
+        );
+        stats.candidates = candidates.len();
+
+        stats
+    }
+
+    /// Distribute the content scores of candidates among their parent nodes.
+    ///
+    /// The content scores are calculated on the fly unless they were computed
+    /// in advance.
+    fn distribute_content_scores(
+        &self,
+        node_id: &Path,
+        handle: &Handle,
         candidates: &mut BTreeMap<String, Candidate>,
         nodes: &mut BTreeMap<String, Rc<Node>>,
+        content_scores: Option<&HashMap<String, f32>>,
+        stats: &mut CandidateStats,
     ) {
+        if stats.terminated_early {
+            return;
+        }
+        stats.nodes += 1;
+
         if let Some(id) = node_id
             .to_str()
             .map(|candidate_id| candidate_id.to_string())
@@ -230,9 +1097,26 @@ impl<'a> Scorer<'a> {
             nodes.insert(id, handle.clone());
         }
 
-        if self.is_candidate(handle.clone()) {
-            let content_score = self.calculate_content_score(handle.clone());
+        let content_score = match content_scores {
+            Some(content_scores) => node_id
+                .to_str()
+                .and_then(|id| content_scores.get(id))
+                .copied(),
+            None => match self.options.classifier {
+                Some(classifier) => {
+                    let depth = node_id.to_str().map_or(0, |id| {
+                        id.split('/').filter(|index| !index.is_empty()).count()
+                    });
+                    self.node_features(handle, depth)
+                        .and_then(|features| classifier.score(&features))
+                }
+                None => self
+                    .is_candidate(handle)
+                    .then(|| self.calculate_content_score(handle)),
+            },
+        };
 
+        if let Some(content_score) = content_score {
             let mut current_node_id = Some(node_id.to_path_buf());
             let mut level = 1;
 
@@ -245,24 +1129,29 @@ impl<'a> Scorer<'a> {
 
                 if let Some(candidate) =
                     current_id.to_str().map(|id| id.to_string()).and_then(|id| {
+                        let is_limit_reached = self
+                            .options
+                            .max_candidates
+                            .is_some_and(|max| candidates.len() >= max)
+                            && !candidates.contains_key(&id);
+                        stats.candidate_limit_reached |= is_limit_reached;
+
                         // Only parent nodes are valid candidates.
-                        if current_id != node_id {
+                        if current_id != node_id && !is_limit_reached {
                             self.find_or_create_candidate(Path::new(&id), candidates, nodes)
                         } else {
                             None
                         }
                     })
                 {
-                    let adjusted_content_score = match self.options.candidate_score {
-                        CandidateScore::EqualWeight => content_score,
-                        CandidateScore::LevelWeight => content_score / (level as f32),
-                    };
+                    let adjusted_content_score =
+                        content_score * self.options.candidate_score.weight(level);
                     candidate
                         .score
                         .set(candidate.score.get() + adjusted_content_score);
 
                     // Ignore candidates above the `body` node.
-                    if html::get_tag_name(candidate.node.clone()).as_deref() == Some("body") {
+                    if html::get_tag_name(&candidate.node) == Some("body") {
                         break;
                     }
                 }
@@ -272,17 +1161,96 @@ impl<'a> Scorer<'a> {
             }
         }
 
+        if self.is_confident(candidates, stats.nodes) {
+            stats.terminated_early = true;
+            return;
+        }
+
         for (i, child) in handle.children.borrow().iter().enumerate() {
-            self.find_candidates(
+            self.distribute_content_scores(
                 node_id.join(i.to_string()).as_path(),
-                child.clone(),
+                child,
                 candidates,
                 nodes,
+                content_scores,
+                stats,
             )
         }
     }
 
+    /// Check if the best candidate is confident enough to stop scoring, every
+    /// [`EarlyTermination::min_nodes`] nodes.
+    fn is_confident(&self, candidates: &BTreeMap<String, Candidate>, nodes: usize) -> bool {
+        let Some(early_termination) = self.options.early_termination else {
+            return false;
+        };
+        if early_termination.min_nodes == 0 || nodes % early_termination.min_nodes != 0 {
+            return false;
+        }
+
+        candidates
+            .values()
+            .max_by(|a, b| a.score.get().total_cmp(&b.score.get()))
+            .is_some_and(|candidate| {
+                self.confidence(candidate.score.get(), &candidate.node)
+                    >= early_termination.min_confidence
+            })
+    }
+
+    /// Calculate the content scores of all candidates below the node in
+    /// parallel, keyed by the node id.
+    ///
+    /// The subtree is copied into an [`ArenaDom`] first, which can be shared
+    /// between threads.
+    #[cfg(feature = "parallel")]
+    fn parallel_content_scores(&self, node_id: &Path, handle: &Handle) -> HashMap<String, f32> {
+        use rayon::prelude::*;
+
+        let mut dom = ArenaDom::default();
+        let mut elements = vec![];
+        let mut stack = vec![(handle.clone(), node_id.to_path_buf(), dom.document())];
+
+        while let Some((handle, node_id, id)) = stack.pop() {
+            if let (Some(_), Some(node_id)) = (html::get_tag_name(&handle), node_id.to_str()) {
+                elements.push((node_id.to_owned(), id));
+            }
+
+            for (i, child) in handle.children.borrow().iter().enumerate() {
+                let child_id = dom.push(ArenaNodeData::from(&child.data));
+                dom.append(id, child_id);
+                stack.push((child.clone(), node_id.join(i.to_string()), child_id));
+            }
+        }
+
+        elements
+            .into_par_iter()
+            .filter(|(_, id)| self.is_candidate(&ArenaCandidate::new(&dom, *id)))
+            .map(|(node_id, id)| {
+                let mut text = String::new();
+                arena_extract_text(&dom, id, &mut text, !self.options.legacy_line_breaks);
+                (node_id, self.calculate_text_score(text))
+            })
+            .collect()
+    }
+
+    /// Find the element which is marked as content by the document, if
+    /// [`ScorerOptions::content_hints`] is set and its text is at least
+    /// [`MIN_CONTENT_HINT_LENGTH`] long.
+    pub fn find_content_hint(&self, handle: &Handle) -> Option<Handle> {
+        if !self.options.content_hints {
+            return None;
+        }
+
+        microformats::entry_content(handle)
+            .or_else(|| microdata::article_body(handle))
+            .filter(|node| self.text_len(node) >= MIN_CONTENT_HINT_LENGTH)
+    }
+
     // TODO: find top candidates with similar score.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(candidates = candidates.len()))
+    )]
     pub fn find_top_candidate(
         &self,
         candidates: &'a BTreeMap<String, Candidate>,
@@ -290,12 +1258,12 @@ impl<'a> Scorer<'a> {
         let mut top_candidate: Option<TopCandidate> = None;
 
         for (id, candidate) in candidates.iter() {
-            let score = candidate.score.get() * (1.0 - get_link_density(candidate.node.clone()));
+            let score = candidate.score.get() * (1.0 - self.link_density(&candidate.node));
             candidate.score.set(score);
 
             if top_candidate
                 .as_ref()
-                .map_or(true, |top| score > top.candidate.score.get())
+                .is_none_or(|top| score > top.candidate.score.get())
             {
                 top_candidate = Some(TopCandidate {
                     id: Cow::Borrowed(id),
@@ -311,105 +1279,335 @@ impl<'a> Scorer<'a> {
         &self,
         dom: &mut RcDom,
         id: &Path,
-        handle: Handle,
+        handle: &Handle,
+        url: &Url,
+        candidates: &BTreeMap<String, Candidate>,
+    ) -> bool {
+        self.clean_with_reasons(dom, id, handle, url, candidates, &mut vec![])
+    }
+
+    /// Clean the content like [`Scorer::clean`], and collect the removed nodes
+    /// along with the reason of their removal.
+    pub(crate) fn clean_with_reasons(
+        &self,
+        dom: &mut RcDom,
+        id: &Path,
+        handle: &Handle,
+        url: &Url,
+        candidates: &BTreeMap<String, Candidate>,
+        removed: &mut Vec<(Handle, CleanReason)>,
+    ) -> bool {
+        let clean_options = &self.options.clean_options;
+        // Remove tracking images first, so they don't count as content images.
+        for image in clean_options.remove_tracking_images(dom, handle, url) {
+            removed.push((image, CleanReason::TrackingImage));
+        }
+
+        let fragment_targets = if !clean_options.keep_fragment_links {
+            HashSet::new()
+        } else if clean_options.preserve_footnotes {
+            // Footnotes outside of the content link back to their references
+            fragment_targets(&dom.document)
+        } else {
+            fragment_targets(handle)
+        };
+
+        self.clean_node(dom, id, handle, url, candidates, &fragment_targets, removed)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn clean_node(
+        &self,
+        dom: &mut RcDom,
+        id: &Path,
+        handle: &Handle,
         url: &Url,
         candidates: &BTreeMap<String, Candidate>,
+        fragment_targets: &HashSet<String>,
+        removed: &mut Vec<(Handle, CleanReason)>,
     ) -> bool {
-        let mut useless = false;
+        let mut reason = None;
+        let mut is_kept = false;
         match handle.data {
             NodeData::Document => (),
             NodeData::Doctype { .. } => (),
             NodeData::Text { ref contents } => {
                 let s = contents.borrow();
                 if s.trim().is_empty() {
-                    useless = true
+                    reason = Some(CleanReason::Empty)
                 }
             }
-            NodeData::Comment { .. } => useless = true,
+            NodeData::Comment { .. } => reason = Some(CleanReason::Comment),
             NodeData::Element {
                 ref name,
                 ref attrs,
                 ..
             } => {
-                let tag_name = name.local.as_ref();
-                match tag_name.to_lowercase().as_ref() {
-                    "script" | "link" | "style" | "noscript" | "meta" | "h1" | "object"
-                    | "header" | "footer" | "aside" => useless = true,
-                    "form" | "table" | "ul" | "div" => {
-                        useless = self.is_useless(id, handle.clone(), candidates)
+                match self.options.clean_options.decide(&name.local, handle) {
+                    CleanDecision::Remove => {
+                        removed.push((handle.clone(), CleanReason::Predicate));
+                        return true;
+                    }
+                    CleanDecision::Keep => is_kept = true,
+                    CleanDecision::Continue => (),
+                }
+                // Keep SVG and MathML intact, including their attributes.
+                if is_foreign(name) {
+                    let useless = self.options.clean_options.remove_svg_and_math && !is_kept;
+                    if useless {
+                        removed.push((handle.clone(), CleanReason::SvgOrMath));
+                    }
+                    return useless;
+                }
+                let is_div = self.candidate_tag_name(&name.local) == "div";
+                match name.local {
+                    _ if self.options.clean_options.is_share_element(handle) => {
+                        reason = Some(CleanReason::ShareElement)
+                    }
+                    _ if self.options.clean_options.is_signup_element(handle) => {
+                        reason = Some(CleanReason::SignupElement)
+                    }
+                    // Keep the attribution of a quote, e.g. its `cite` element.
+                    local_name!("footer")
+                        if dom::parent(handle)
+                            .is_some_and(|parent| dom::is_tag(&parent, "blockquote")) => {}
+                    local_name!("script")
+                    | local_name!("link")
+                    | local_name!("style")
+                    | local_name!("noscript")
+                    | local_name!("meta")
+                    | local_name!("h1")
+                    | local_name!("object")
+                    | local_name!("header")
+                    | local_name!("footer")
+                    | local_name!("aside") => reason = Some(CleanReason::UnlikelyTag),
+                    // Keep quotes, even if their wrapper matches a negative pattern.
+                    _ if is_div && is_quote_wrapper(handle) => (),
+                    _ if is_div => {
+                        reason = self
+                            .is_useless(id, handle, candidates)
+                            .then_some(CleanReason::LowScore)
+                    }
+                    local_name!("form") | local_name!("table") | local_name!("ul") => {
+                        reason = self
+                            .is_useless(id, handle, candidates)
+                            .then_some(CleanReason::LowScore)
+                    }
+                    local_name!("blockquote") | local_name!("q") => fix_cite_path(handle, url),
+                    local_name!("img") => {
+                        let useless = !self.options.clean_options.fix_data_uri(handle)
+                            || !fix_img_path(handle, url, &self.options.clean_options);
+                        reason = useless.then_some(CleanReason::InvalidImage)
+                    }
+                    local_name!("a") => {
+                        let useless = !fix_anchor_path(handle, url, &self.options.clean_options);
+                        reason = useless.then_some(CleanReason::InvalidLink)
                     }
-                    "img" => useless = !fix_img_path(handle.clone(), url),
-                    "a" => useless = !fix_anchor_path(handle.clone(), url),
                     _ => (),
                 }
-                html::clean_attr("id", &mut attrs.borrow_mut());
-                html::clean_attr("class", &mut attrs.borrow_mut());
-                html::clean_attr("style", &mut attrs.borrow_mut());
+                if is_kept {
+                    reason = None;
+                }
+                // Keep the targets of fragment links.
+                let is_fragment_target = html::attr("id", &attrs.borrow())
+                    .is_some_and(|id| fragment_targets.contains(&id));
+                self.options
+                    .clean_options
+                    .clean_attrs(&mut attrs.borrow_mut(), is_fragment_target);
             }
             NodeData::ProcessingInstruction { .. } => unreachable!(),
         }
         let mut useless_nodes = vec![];
         for (i, child) in handle.children.borrow().iter().enumerate() {
             let pid = id.join(i.to_string());
-            if self.clean(dom, pid.as_path(), child.clone(), url, candidates) {
+            if self.clean_node(
+                dom,
+                pid.as_path(),
+                child,
+                url,
+                candidates,
+                fragment_targets,
+                removed,
+            ) {
                 useless_nodes.push(child.clone());
             }
         }
         for node in useless_nodes.iter() {
             dom.remove_from_parent(node);
         }
-        if html::is_empty(handle) {
-            useless = true
+        if reason.is_none() && !is_kept && html::is_empty(handle) {
+            reason = Some(CleanReason::Empty)
+        }
+        match reason {
+            Some(reason) => {
+                removed.push((handle.clone(), reason));
+                true
+            }
+            None => false,
         }
-        useless
     }
 
-    fn calculate_content_score(&self, handle: Handle) -> f32 {
-        let mut score: f32 = 1.0;
-        let mut text = String::new();
-        extract_text(handle.clone(), &mut text, true);
-        let mat = self.options.punctuations.find_iter(&text);
-        score += mat.count() as f32;
-        score += f32::min(f32::floor(text.chars().count() as f32 / 100.0), 3.0);
-        score
+    /// Remove empty paragraphs and unwrap redundant wrappers in the content.
+    pub fn postprocess(&self, dom: &mut RcDom, handle: &Handle) {
+        self.options.clean_options.tidy(dom, handle);
     }
 
-    fn get_class_weight(&self, handle: Handle) -> f32 {
-        let mut weight: f32 = 0.0;
-        if let NodeData::Element {
-            name: _, ref attrs, ..
-        } = handle.data
-        {
-            for name in ["id", "class"].iter() {
-                if let Some(val) = html::attr(name, &attrs.borrow()) {
-                    if self.options.positive_candidates.is_match(&val) {
-                        weight += self.options.positive_candidate_weight
-                    };
-                    if self.options.negative_candidates.is_match(&val) {
-                        weight -= self.options.negative_candidate_weight
-                    }
-                }
-            }
-        };
-        weight
-    }
-
-    fn init_content_score(&self, handle: Handle) -> f32 {
-        let tag_name = html::get_tag_name(handle.clone()).unwrap_or_default();
-        let score = match tag_name.as_ref() {
-            "article" => 10.0,
-            "div" => 5.0,
-            "pre" | "td" | "blockquote" => 3.0,
-            "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
-            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
-            _ => 0.0,
-        };
-        score + self.get_class_weight(handle.clone())
+    /// Remove sections of related articles from the end of the content.
+    pub fn remove_related_sections(&self, dom: &mut RcDom, handle: &Handle, url: &Url) {
+        self.options
+            .clean_options
+            .remove_related_sections(dom, handle, url);
     }
 
-    fn find_or_create_candidate(
-        &self,
-        id: &Path,
+    /// Append the footnotes referenced from the content in a footnotes
+    /// section.
+    pub fn append_footnotes(&self, dom: &mut RcDom, handle: &Handle, url: &Url) {
+        let clean_options = &self.options.clean_options;
+        if clean_options.preserve_footnotes && clean_options.keep_fragment_links {
+            footnotes::append_footnotes(dom, handle, url, clean_options);
+        }
+    }
+
+    /// Estimate the confidence in the cleaned content of the top candidate
+    /// between `0.0` and `1.0`.
+    ///
+    /// The score and the text length saturate towards `1.0`, i.e. a score of
+    /// [`CONFIDENT_SCORE`] and a length of [`CONFIDENT_LENGTH`] both count as
+    /// `0.5`, and their mean is discounted by the link density.
+    pub(crate) fn confidence(&self, score: f32, handle: &Handle) -> f32 {
+        let score = score.max(0.0);
+        let text_len = self.text_len(handle) as f32;
+        let score_confidence = score / (score + CONFIDENT_SCORE);
+        let length_confidence = text_len / (text_len + CONFIDENT_LENGTH);
+        let confidence =
+            (score_confidence + length_confidence) / 2.0 * (1.0 - self.link_density(handle));
+
+        confidence.clamp(0.0, 1.0)
+    }
+
+    /// Describe an element by its features, or `None` for other nodes, see
+    /// [`NodeClassifier`].
+    pub fn node_features(&self, handle: &Handle, depth: usize) -> Option<NodeFeatures> {
+        let tag = dom::tag_name(handle)?.to_owned();
+        let text = self.extract_text(handle);
+        let siblings = dom::parent(handle)
+            .map(|parent| {
+                parent
+                    .children
+                    .borrow()
+                    .iter()
+                    .filter(|sibling| {
+                        !dom::same_node(sibling, handle)
+                            && matches!(sibling.data, NodeData::Element { .. })
+                    })
+                    .map(|sibling| self.text_len(sibling))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        Some(NodeFeatures {
+            tag,
+            depth,
+            text_length: self.text_len(handle),
+            link_density: self.link_density(handle),
+            punctuation_count: self.options.punctuations.find_iter(&text).count(),
+            class_weight: self.get_class_weight(handle),
+            child_count: handle
+                .children
+                .borrow()
+                .iter()
+                .filter(|child| matches!(child.data, NodeData::Element { .. }))
+                .count(),
+            sibling_count: siblings.len(),
+            sibling_text_length: siblings.iter().sum(),
+        })
+    }
+
+    fn calculate_content_score(&self, handle: &Handle) -> f32 {
+        self.calculate_text_score(self.extract_text(handle))
+    }
+
+    /// Extract the text of a node, honoring [`ScorerOptions::legacy_line_breaks`].
+    fn extract_text(&self, handle: &Handle) -> String {
+        let mut text = String::new();
+        extract_text_with(handle, &mut text, true, !self.options.legacy_line_breaks);
+        text
+    }
+
+    fn calculate_text_score(&self, mut text: String) -> f32 {
+        let mut score: f32 = 1.0;
+        if let CandidateScore::ReadabilityJs = self.options.candidate_score {
+            text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            // Readability.js adds the number of comma-separated parts.
+            score += COMMAS.split(&text).count() as f32;
+        } else {
+            score += self.options.punctuations.find_iter(&text).count() as f32;
+        }
+        score += f32::min(
+            f32::floor(self.options.length_metric.len(&text) / 100.0),
+            3.0,
+        );
+        score
+    }
+
+    fn get_class_weight(&self, handle: &Handle) -> f32 {
+        let mut positive = 0;
+        let mut negative = 0;
+        if let NodeData::Element {
+            name: _, ref attrs, ..
+        } = handle.data
+        {
+            for name in ["id", "class"].iter() {
+                if let Some(val) = html::attr(name, &attrs.borrow()) {
+                    let classes = self.classify(&val);
+
+                    if classes.positive {
+                        positive += 1;
+                    };
+                    if classes.negative {
+                        negative += 1;
+                    }
+                }
+            }
+        };
+        if let ClassWeightMode::PerElement = self.options.class_weight_mode {
+            positive = positive.min(1);
+            negative = negative.min(1);
+        }
+        let weight = positive as f32 * self.options.positive_candidate_weight
+            - negative as f32 * self.options.negative_candidate_weight;
+        match self.options.class_weight_cap {
+            Some(cap) => weight.clamp(-cap.abs(), cap.abs()),
+            None => weight,
+        }
+    }
+
+    fn init_content_score(&self, handle: &Handle) -> f32 {
+        let tag_name = html::get_tag_name(handle).unwrap_or_default();
+        let score = match tag_name {
+            "article" if matches!(self.options.candidate_score, CandidateScore::ReadabilityJs) => {
+                0.0
+            }
+            _ => {
+                let tag_name = self.candidate_tag_name(tag_name);
+                self.options
+                    .tag_scores
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(tag_name))
+                    .map_or(0.0, |(_, score)| *score)
+            }
+        };
+        // Glossaries and FAQs are content rather than lists of links.
+        let score = match tag_name {
+            "dl" if self.is_text_rich_definition_list(handle) => score.max(0.0),
+            _ => score,
+        };
+        score + self.get_class_weight(handle)
+    }
+
+    fn find_or_create_candidate(
+        &self,
+        id: &Path,
         candidates: &'a mut BTreeMap<String, Candidate>,
         nodes: &BTreeMap<String, Rc<Node>>,
     ) -> Option<&'a Candidate> {
@@ -420,7 +1618,7 @@ impl<'a> Scorer<'a> {
                         id.clone(),
                         Candidate {
                             node: node.clone(),
-                            score: Cell::new(self.init_content_score(node.clone())),
+                            score: Cell::new(self.init_content_score(node)),
                         },
                     );
                 }
@@ -433,11 +1631,11 @@ impl<'a> Scorer<'a> {
     fn is_useless(
         &self,
         id: &Path,
-        handle: Handle,
+        handle: &Handle,
         candidates: &BTreeMap<String, Candidate>,
     ) -> bool {
-        let tag_name = &html::get_tag_name(handle.clone()).unwrap_or_default();
-        let weight = self.get_class_weight(handle.clone());
+        let tag_name = html::get_tag_name(handle).unwrap_or_default();
+        let weight = self.get_class_weight(handle);
         let score = id
             .to_str()
             .and_then(|id| candidates.get(id))
@@ -446,24 +1644,26 @@ impl<'a> Scorer<'a> {
         if weight + score < 0.0 {
             return true;
         }
-        let text_nodes_len = html::text_children_count(handle.clone());
+        let text_nodes_len = html::text_children_count(handle);
         let mut p_nodes: Vec<Rc<Node>> = vec![];
         let mut img_nodes: Vec<Rc<Node>> = vec![];
         let mut li_nodes: Vec<Rc<Node>> = vec![];
         let mut input_nodes: Vec<Rc<Node>> = vec![];
         let mut embed_nodes: Vec<Rc<Node>> = vec![];
-        html::find_node(handle.clone(), "p", &mut p_nodes);
-        html::find_node(handle.clone(), "img", &mut img_nodes);
-        html::find_node(handle.clone(), "li", &mut li_nodes);
-        html::find_node(handle.clone(), "input", &mut input_nodes);
-        html::find_node(handle.clone(), "embed", &mut embed_nodes);
+        html::find_node(handle, "p", &mut p_nodes);
+        html::find_node(handle, "img", &mut img_nodes);
+        html::find_node(handle, "li", &mut li_nodes);
+        html::find_node(handle, "input", &mut input_nodes);
+        html::find_node(handle, "embed", &mut embed_nodes);
         let p_count = p_nodes.len();
         let img_count = img_nodes.len();
+        // SVG figures and MathML formulas are content without much text.
+        let has_figures = html::has_nodes(handle, &["svg", "math"]);
         let li_count = li_nodes.len() as i32 - 100;
         let input_count = input_nodes.len();
         let embed_count = embed_nodes.len();
-        let link_density = get_link_density(handle.clone());
-        let content_length = html::text_len(handle.clone());
+        let link_density = self.link_density(handle);
+        let content_length = self.text_len(handle);
         let para_count = text_nodes_len + p_count;
 
         if img_count > para_count + text_nodes_len {
@@ -475,7 +1675,7 @@ impl<'a> Scorer<'a> {
         if input_count as f32 > f32::floor(para_count as f32 / 3.0) {
             return true;
         }
-        if content_length < 25 && (img_count == 0 || img_count > 2) {
+        if content_length < 25 && !has_figures && (img_count == 0 || img_count > 2) {
             return true;
         }
         if weight < 25.0 && link_density > 0.2 {
@@ -487,71 +1687,919 @@ impl<'a> Scorer<'a> {
         false
     }
 
-    fn is_candidate(&self, handle: Handle) -> bool {
-        let text_len = html::text_len(handle.clone());
+    /// Measure the link density with the configured hash link coefficient.
+    fn link_density(&self, handle: &Handle) -> f32 {
+        link_density(handle, self.options.hash_link_coefficient)
+    }
+
+    /// Measure the text length of a node with the configured [`LengthMetric`].
+    fn text_len(&self, handle: &Handle) -> usize {
+        match self.options.length_metric {
+            LengthMetric::Chars => html::text_len(handle),
+            metric => html::text_len_with(handle, &|text| metric.len(text)).round() as usize,
+        }
+    }
+
+    /// Get the tag name of an element for candidacy and cleaning, i.e. `div`
+    /// for custom elements if [`ScorerOptions::custom_elements_as_divs`] is
+    /// set and their tag name is not in [`ScorerOptions::tag_scores`].
+    fn candidate_tag_name<'t>(&self, tag_name: &'t str) -> &'t str {
+        let is_div = self.options.custom_elements_as_divs
+            && is_custom_element(tag_name)
+            && !self
+                .options
+                .tag_scores
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case(tag_name));
+
+        if is_div {
+            "div"
+        } else {
+            tag_name
+        }
+    }
+
+    /// Check if a node contains custom elements which are scored like `div`
+    /// elements, and thus are block children.
+    fn has_custom_divs(&self, node: &impl CandidateNode) -> bool {
+        self.options.custom_elements_as_divs
+            && node.has_descendant(&|tag_name| self.candidate_tag_name(tag_name) != tag_name)
+    }
+
+    /// Check if a definition list is rich in text, i.e. at least half of its
+    /// descriptions are long enough to be candidates.
+    fn is_text_rich_definition_list(&self, handle: &Handle) -> bool {
+        let descriptions = handle
+            .children
+            .borrow()
+            .iter()
+            .filter(|child| html::get_tag_name(child) == Some("dd"))
+            .map(|child| self.text_len(child) >= self.options.min_candidate_length)
+            .collect::<Vec<_>>();
+        let long_descriptions = descriptions.iter().filter(|is_long| **is_long).count();
+
+        long_descriptions > 0 && 2 * long_descriptions >= descriptions.len()
+    }
+
+    /// Check if a node is a candidate, i.e. a paragraph, or a `div`, `article`,
+    /// `center`, or `section` element without block children, whose text is at
+    /// least [`ScorerOptions::min_candidate_length`] long.
+    ///
+    /// The check is shared by the sequential and the parallel scoring, see
+    /// [`ScorerOptions::parallel`].
+    fn is_candidate(&self, node: &impl CandidateNode) -> bool {
+        let tag_name = self.candidate_tag_name(node.tag_name().unwrap_or_default());
+        if !matches!(
+            tag_name,
+            "p" | "dd" | "div" | "article" | "center" | "section"
+        ) {
+            return false;
+        }
+        let text_len = match self.options.length_metric {
+            LengthMetric::Chars => node.text_len(),
+            metric => node.text_len_with(&|text| metric.len(text)).round() as usize,
+        };
         if text_len < self.options.min_candidate_length {
             return false;
         }
-        let n: &str = &html::get_tag_name(handle.clone()).unwrap_or_default();
-        match n {
-            "p" => true,
-            "div" | "article" | "center" | "section" => {
-                !html::has_nodes(handle.clone(), self.options.block_child_tags)
-            }
-            _ => false,
+
+        match tag_name {
+            // Descriptions of definition lists are scored like paragraphs.
+            "p" | "dd" => true,
+            _ => !node.has_nodes(self.options.block_child_tags) && !self.has_custom_divs(node),
         }
     }
 }
 
-pub fn fix_img_path(handle: Handle, url: &Url) -> bool {
-    let src = html::get_attr("src", handle.clone());
+pub fn fix_img_path(handle: &Handle, url: &Url, options: &CleanOptions) -> bool {
+    let src = html::get_attr("src", handle);
     let s = match src {
         Some(src) => src,
         None => return false,
     };
-    if !s.starts_with("//") && !s.starts_with("http://") && !s.starts_with("https://") {
-        if let Ok(new_url) = url.join(&s) {
-            html::set_attr("src", new_url.as_str(), handle)
+    // Protocol-relative urls are resolved with the scheme of the document.
+    let is_absolute = s.starts_with("http://") || s.starts_with("https://");
+    if let Ok(mut new_url) = url.join(&s) {
+        let is_upgraded = options.upgrade_insecure_url(&mut new_url, url);
+        match options.url_rewriter {
+            Some(rewriter) => match rewriter.rewrite(new_url, UrlKind::Image) {
+                Some(new_url) => html::set_attr("src", new_url.as_str(), handle),
+                None => return false,
+            },
+            None if !is_absolute || is_upgraded => html::set_attr("src", new_url.as_str(), handle),
+            None => (),
         }
     }
     true
 }
 
-pub fn fix_anchor_path(handle: Handle, url: &Url) -> bool {
-    let src = html::get_attr("href", handle.clone());
+pub fn fix_anchor_path(handle: &Handle, url: &Url, options: &CleanOptions) -> bool {
+    let src = html::get_attr("href", handle);
     let s = match src {
         Some(src) => src,
         None => return false,
     };
-    if !s.starts_with("//") && !s.starts_with("http://") && !s.starts_with("https://") {
-        if let Ok(new_url) = url.join(&s) {
-            html::set_attr("href", new_url.as_str(), handle)
+    if options.keep_fragment_links && s.starts_with('#') {
+        return true;
+    }
+    let is_absolute = s.starts_with("http://") || s.starts_with("https://");
+    if let Ok(mut new_url) = url.join(&s) {
+        let is_stripped = options.strip_tracking_params(&mut new_url);
+        match options.url_rewriter {
+            Some(rewriter) => match rewriter.rewrite(new_url, UrlKind::Link) {
+                Some(new_url) => html::set_attr("href", new_url.as_str(), handle),
+                // Keep the text of the link.
+                None => {
+                    dom::remove_attr(handle, "href");
+                }
+            },
+            None if !is_absolute || is_stripped => html::set_attr("href", new_url.as_str(), handle),
+            None => (),
         }
     }
     true
 }
 
-pub fn get_link_density(handle: Handle) -> f32 {
-    let text_length = html::text_len(handle.clone()) as f32;
+/// Check if a tag name is a valid custom element name, i.e. contains a hyphen
+/// and is not reserved by SVG or MathML.
+fn is_custom_element(tag_name: &str) -> bool {
+    tag_name.contains('-')
+        && !matches!(
+            tag_name,
+            "annotation-xml"
+                | "color-profile"
+                | "font-face"
+                | "font-face-src"
+                | "font-face-uri"
+                | "font-face-format"
+                | "font-face-name"
+                | "missing-glyph"
+        )
+}
+
+/// Check if an element is an ad slot, see [`ScorerOptions::remove_ad_slots`].
+fn is_ad_slot(name: &QualName, attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| match &*attr.name.local {
+        "id" => attr.value.starts_with("div-gpt-ad") || attr.value.starts_with("google_ads_"),
+        "class" => {
+            name.local == local_name!("ins")
+                && attr
+                    .value
+                    .split_whitespace()
+                    .any(|class| class == "adsbygoogle")
+        }
+        "aria-label" => matches!(
+            attr.value.trim().to_lowercase().as_str(),
+            "advertisement" | "advertisements"
+        ),
+        attr_name => AD_SLOT_ATTRIBUTES.contains(&attr_name),
+    })
+}
+
+/// Check if an inline style positions an element fixed over the whole
+/// viewport.
+fn is_full_screen_style(style: &str) -> bool {
+    let style = style
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let has = |declaration: &str| {
+        style
+            .split(';')
+            .any(|part| part.trim_end_matches("!important") == declaration)
+    };
+
+    let is_fixed = has("position:fixed");
+    let is_full_width = has("width:100%") || has("width:100vw") || has("left:0") && has("right:0");
+    let is_full_height =
+        has("height:100%") || has("height:100vh") || has("top:0") && has("bottom:0");
+
+    is_fixed && (has("inset:0") || is_full_width && is_full_height)
+}
+
+/// Translate an AMP media element to the equivalent HTML element, dropping
+/// its placeholders and fallbacks.
+fn translate_amp_element(dom: &mut RcDom, node: &Handle) {
+    let NodeData::Element {
+        ref name,
+        ref attrs,
+        ..
+    } = node.data
+    else {
+        return;
+    };
+    let mut attrs = attrs
+        .borrow()
+        .iter()
+        .filter(|attr| !matches!(&*attr.name.local, "layout" | "sizes" | "heights"))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let tag_name = match &*name.local {
+        "amp-img" | "amp-anim" => local_name!("img"),
+        "amp-video" => local_name!("video"),
+        "amp-audio" => local_name!("audio"),
+        "amp-iframe" => local_name!("iframe"),
+        "amp-youtube" => {
+            let Some(video_id) = html::attr("data-videoid", &attrs) else {
+                return;
+            };
+            let src = format!("https://www.youtube.com/embed/{video_id}");
+            attrs.retain(|attr| !attr.name.local.starts_with("data-"));
+            attrs.push(Attribute {
+                name: QualName::new(None, ns!(), local_name!("src")),
+                value: src.into(),
+            });
+            local_name!("iframe")
+        }
+        _ => return,
+    };
+
+    let name = QualName::new(None, ns!(html), tag_name.clone());
+    let element = dom.create_element(name, attrs, ElementFlags::default());
+    // Keep the sources and text tracks of media.
+    if matches!(tag_name, local_name!("video") | local_name!("audio")) {
+        let sources = node
+            .children
+            .borrow()
+            .iter()
+            .filter(|child| dom::is_tag(child, "source") || dom::is_tag(child, "track"))
+            .cloned()
+            .collect::<Vec<_>>();
+        for source in sources {
+            dom.remove_from_parent(&source);
+            dom.append(&element, NodeOrText::AppendNode(source));
+        }
+    }
+    dom.append_before_sibling(node, NodeOrText::AppendNode(element));
+    dom.remove_from_parent(node);
+}
+
+/// Make the `cite` url of a quote absolute.
+fn fix_cite_path(handle: &Handle, url: &Url) {
+    if let Some(cite) = html::get_attr("cite", handle) {
+        if let Ok(new_url) = url.join(cite.trim()) {
+            html::set_attr("cite", new_url.as_str(), handle)
+        }
+    }
+}
+
+/// Check if an element wraps a quote, i.e. at least half of its text is in
+/// `blockquote` elements.
+fn is_quote_wrapper(handle: &Handle) -> bool {
+    let mut quotes = vec![];
+    html::find_node(handle, "blockquote", &mut quotes);
+    if quotes.is_empty() {
+        return false;
+    }
+
+    let quote_length = quotes
+        .iter()
+        .map(|quote| dom::text_content(quote).trim().chars().count())
+        .sum::<usize>();
+    let text_length = dom::text_content(handle).trim().chars().count();
+
+    quote_length > 0 && 2 * quote_length >= text_length
+}
+
+/// Replace a `div` containing a single paragraph by the paragraph, or convert
+/// a `div` without block elements to a paragraph.
+fn convert_div_to_paragraph(dom: &mut RcDom, handle: &Handle) {
+    if has_single_paragraph(handle) && get_link_density(handle) < 0.25 {
+        let p = handle
+            .children
+            .borrow()
+            .iter()
+            .find(|child| dom::is_tag(child, "p"))
+            .cloned();
+        if let Some(p) = p {
+            dom.remove_from_parent(&p);
+            dom.append_before_sibling(handle, NodeOrText::AppendNode(p));
+            dom.remove_from_parent(handle);
+        }
+    } else if !html::has_nodes(handle, &DIV_TO_P_BLOCK_TAGS) {
+        let NodeData::Element { ref attrs, .. } = handle.data else {
+            return;
+        };
+        let name = QualName::new(None, ns!(html), local_name!("p"));
+        let p = dom.create_element(name, attrs.borrow().clone(), ElementFlags::default());
+        dom.reparent_children(handle, &p);
+        dom.append_before_sibling(handle, NodeOrText::AppendNode(p));
+        dom.remove_from_parent(handle);
+    }
+}
+
+/// Check if the only child of an element is a paragraph, ignoring whitespace.
+fn has_single_paragraph(handle: &Handle) -> bool {
+    let mut paragraphs = 0;
+
+    for child in handle.children.borrow().iter() {
+        match child.data {
+            NodeData::Element { .. } if dom::is_tag(child, "p") => paragraphs += 1,
+            NodeData::Element { .. } => return false,
+            NodeData::Text { ref contents } if !contents.borrow().trim().is_empty() => {
+                return false
+            }
+            _ => (),
+        }
+    }
+
+    paragraphs == 1
+}
+
+/// Replace the child `iframe` elements with a `srcdoc` attribute by a `div`
+/// containing the `body` of the parsed `srcdoc`.
+fn inline_iframe_srcdoc(dom: &mut RcDom, handle: &Handle) {
+    let iframes = handle
+        .children
+        .borrow()
+        .iter()
+        .filter(|child| dom::is_tag(child, "iframe"))
+        .filter_map(|iframe| dom::get_attr(iframe, "srcdoc").map(|srcdoc| (iframe.clone(), srcdoc)))
+        .collect::<Vec<_>>();
+
+    for (iframe, srcdoc) in iframes {
+        let srcdoc_dom = parse_document(RcDom::default(), Default::default()).one(srcdoc);
+        let Some(body) = dom::find_by_tag(&srcdoc_dom.document, "body")
+            .into_iter()
+            .next()
+        else {
+            continue;
+        };
+
+        let name = QualName::new(None, ns!(html), local_name!("div"));
+        let div = dom.create_element(name, vec![], ElementFlags::default());
+        dom.reparent_children(&body, &div);
+        dom.append_before_sibling(&iframe, NodeOrText::AppendNode(div));
+        dom.remove_from_parent(&iframe);
+    }
+}
+
+/// Replace the child `template` elements of declarative shadow roots by their
+/// content.
+fn hoist_shadow_roots(dom: &mut RcDom, handle: &Handle) {
+    let templates = handle
+        .children
+        .borrow()
+        .iter()
+        .filter(|child| dom::is_tag(child, "template"))
+        .filter(|template| {
+            dom::get_attr(template, "shadowrootmode")
+                .or_else(|| dom::get_attr(template, "shadowroot"))
+                .is_some()
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for template in templates {
+        if let NodeData::Element {
+            ref template_contents,
+            ..
+        } = template.data
+        {
+            if let Some(contents) = template_contents.borrow().as_ref() {
+                let children = contents.children.borrow().clone();
+                for child in children {
+                    dom.remove_from_parent(&child);
+                    dom.append_before_sibling(&template, NodeOrText::AppendNode(child));
+                }
+            }
+        }
+        dom.remove_from_parent(&template);
+    }
+}
+
+/// Check if an element is part of an SVG or MathML subtree.
+fn is_foreign(name: &QualName) -> bool {
+    name.ns == ns!(svg) || name.ns == ns!(mathml)
+}
+
+/// Collect the ids which are referenced by fragment links, e.g. `href="#note-1"`.
+fn fragment_targets(handle: &Handle) -> HashSet<String> {
+    std::iter::once(handle.clone())
+        .chain(dom::descendants(handle))
+        .filter(|node| dom::is_tag(node, "a"))
+        .filter_map(|node| dom::get_attr(&node, "href"))
+        .filter_map(|href| href.strip_prefix('#').map(|id| id.to_owned()))
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+pub fn get_link_density(handle: &Handle) -> f32 {
+    link_density(handle, 1.0)
+}
+
+/// Measure the link density, where the text of fragment links, e.g. footnote
+/// markers, is weighted with the given coefficient.
+fn link_density(handle: &Handle, hash_link_coefficient: f32) -> f32 {
+    let text_length = html::text_len(handle) as f32;
     if text_length == 0.0 {
         return 0.0;
     }
     let mut link_length = 0.0;
     let mut links: Vec<Rc<Node>> = vec![];
-    html::find_node(handle.clone(), "a", &mut links);
+    html::find_node(handle, "a", &mut links);
     for link in links.iter() {
-        link_length += html::text_len(link.clone()) as f32;
+        let coefficient = if html::get_attr("href", link).is_some_and(|href| href.starts_with('#'))
+        {
+            hash_link_coefficient
+        } else {
+            1.0
+        };
+        link_length += html::text_len(link) as f32 * coefficient;
     }
     link_length / text_length
 }
 
+/// A node which can be checked for candidacy, see [`Scorer::is_candidate`].
+trait CandidateNode {
+    fn tag_name(&self) -> Option<&str>;
+
+    /// Count the characters of the trimmed text of each text node.
+    fn text_len(&self) -> usize;
+
+    /// Measure the trimmed text of each text node with `len`.
+    fn text_len_with(&self, len: &dyn Fn(&str) -> f32) -> f32;
+
+    /// Check if the node contains any of the elements.
+    fn has_nodes(&self, tag_names: &[&str]) -> bool;
+
+    /// Check if the node contains an element whose tag name matches.
+    fn has_descendant(&self, predicate: &dyn Fn(&str) -> bool) -> bool;
+}
+
+impl CandidateNode for Handle {
+    fn tag_name(&self) -> Option<&str> {
+        html::get_tag_name(self)
+    }
+
+    fn text_len(&self) -> usize {
+        html::text_len(self)
+    }
+
+    fn text_len_with(&self, len: &dyn Fn(&str) -> f32) -> f32 {
+        html::text_len_with(self, len)
+    }
+
+    fn has_nodes(&self, tag_names: &[&str]) -> bool {
+        html::has_nodes(self, tag_names)
+    }
+
+    fn has_descendant(&self, predicate: &dyn Fn(&str) -> bool) -> bool {
+        dom::descendants(self).any(|child| dom::tag_name(&child).is_some_and(predicate))
+    }
+}
+
+/// A node of an [`ArenaDom`], which can be checked for candidacy on any thread.
+#[cfg(feature = "parallel")]
+struct ArenaCandidate<'d> {
+    dom: &'d ArenaDom,
+    id: NodeId,
+}
+
+#[cfg(feature = "parallel")]
+impl<'d> ArenaCandidate<'d> {
+    fn new(dom: &'d ArenaDom, id: NodeId) -> Self {
+        Self { dom, id }
+    }
+
+    fn children(&self) -> impl Iterator<Item = ArenaCandidate<'d>> + '_ {
+        self.dom
+            .get(self.id)
+            .children
+            .iter()
+            .map(|child| ArenaCandidate::new(self.dom, *child))
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl CandidateNode for ArenaCandidate<'_> {
+    fn tag_name(&self) -> Option<&str> {
+        self.dom.tag_name(self.id)
+    }
+
+    fn text_len(&self) -> usize {
+        self.children()
+            .map(|child| match child.dom.get(child.id).data {
+                ArenaNodeData::Text { ref contents } => contents.trim().chars().count(),
+                ArenaNodeData::Element { .. } => child.text_len(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn text_len_with(&self, len: &dyn Fn(&str) -> f32) -> f32 {
+        self.children()
+            .map(|child| match child.dom.get(child.id).data {
+                ArenaNodeData::Text { ref contents } => len(contents.trim()),
+                ArenaNodeData::Element { .. } => child.text_len_with(len),
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    fn has_nodes(&self, tag_names: &[&str]) -> bool {
+        self.children()
+            .any(|child| match child.dom.get(child.id).data {
+                ArenaNodeData::Element { ref name, .. } => {
+                    dom::matches_any_tag(name, tag_names) || child.has_nodes(tag_names)
+                }
+                _ => false,
+            })
+    }
+
+    fn has_descendant(&self, predicate: &dyn Fn(&str) -> bool) -> bool {
+        self.dom
+            .descendants(self.id)
+            .any(|child| self.dom.tag_name(child).is_some_and(predicate))
+    }
+}
+
+/// Like [`extract_text_with`] for a node of an [`ArenaDom`].
+#[cfg(feature = "parallel")]
+fn arena_extract_text(dom: &ArenaDom, id: NodeId, text: &mut String, line_breaks: bool) {
+    let mut last_tag_name = None;
+
+    for child in dom.get(id).children.iter() {
+        match dom.get(*child).data {
+            ArenaNodeData::Text { ref contents } => text.push_str(contents),
+            ArenaNodeData::Element { .. } => {
+                if last_tag_name == Some("p") || (line_breaks && dom.tag_name(*child) == Some("br"))
+                {
+                    text.push('\n');
+                }
+
+                arena_extract_text(dom, *child, text, line_breaks);
+
+                last_tag_name = dom.tag_name(*child);
+            }
+            _ => (),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::utils::{debug_candidates, CandidateTag};
     use html5ever::{parse_document, tendril::TendrilSink};
+    use regex::RegexBuilder;
     use std::{fs::File, io::Read};
 
+    #[test]
+    fn test_preprocess_div_to_p() {
+        let html = r#"<body>
+            <div class="a">Text with <em>phrasing</em> content</div>
+            <div><p>Single paragraph</p></div>
+            <div>Text<div>Nested</div></div>
+            </body>"#;
+        let scorer = Scorer::default();
+        let mut dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap();
+        let document = dom.document.clone();
+        let mut title = String::new();
+
+        scorer.preprocess(&mut dom, &document, &mut title);
+
+        let body = dom::find_by_tag(&dom.document, "body")[0].clone();
+        let mut bytes = vec![];
+        html5ever::serialize(
+            &mut bytes,
+            &dom::SerializableHandle::from(body),
+            Default::default(),
+        )
+        .unwrap();
+        let body = String::from_utf8(bytes).unwrap();
+
+        assert!(body.contains(r#"<p class="a">Text with <em>phrasing</em> content</p>"#));
+        assert!(body.contains("<p>Single paragraph</p>"));
+        assert!(body.contains("<div>Text<p>Nested</p></div>"));
+    }
+
+    #[test]
+    fn test_preprocess_amp_elements() {
+        let html = r#"<body><div>
+            <amp-img src="a.jpg" srcset="a.jpg 1x, b.jpg 2x" layout="responsive"><noscript><img src="a.jpg"></noscript></amp-img>
+            <amp-video poster="p.jpg" controls layout="responsive"><div placeholder></div><source src="v.mp4" type="video/mp4"><div fallback>No video</div></amp-video>
+            <amp-youtube data-videoid="abc123" width="480" height="270" layout="responsive"></amp-youtube>
+            </div></body>"#;
+        let scorer = Scorer::default();
+        let mut dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap();
+        let document = dom.document.clone();
+        let mut title = String::new();
+
+        scorer.preprocess(&mut dom, &document, &mut title);
+
+        let body = dom::find_by_tag(&dom.document, "body")[0].clone();
+        let mut bytes = vec![];
+        html5ever::serialize(
+            &mut bytes,
+            &dom::SerializableHandle::from(body),
+            Default::default(),
+        )
+        .unwrap();
+        let body = String::from_utf8(bytes).unwrap();
+
+        assert!(!body.contains("amp-"));
+        assert!(body.contains(r#"<img src="a.jpg" srcset="a.jpg 1x, b.jpg 2x">"#));
+        assert!(body.contains(
+            r#"<video poster="p.jpg" controls=""><source src="v.mp4" type="video/mp4"></video>"#
+        ));
+        assert!(body.contains(
+            r#"<iframe width="480" height="270" src="https://www.youtube.com/embed/abc123"></iframe>"#
+        ));
+    }
+
+    #[test]
+    fn test_is_full_screen_style() {
+        assert!(is_full_screen_style("position: fixed; inset: 0"));
+        assert!(is_full_screen_style(
+            "POSITION:FIXED !important; top:0; left:0; width:100%; height:100vh"
+        ));
+        assert!(!is_full_screen_style(
+            "position: fixed; bottom: 0; width: 100%"
+        ));
+        assert!(!is_full_screen_style("position: absolute; inset: 0"));
+    }
+
+    #[test]
+    fn test_find_candidates_readability_js() {
+        let html = r#"<body><div id="x3"><div id="x2"><div id="x1">
+            <p>This is a test paragraph, with commas، in multiple， scripts.</p>
+            </div></div></div></body>"#;
+        let options = ScorerOptions {
+            candidate_score: CandidateScore::ReadabilityJs,
+            ..Default::default()
+        };
+        let scorer = Scorer::new(options).unwrap();
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap();
+
+        let mut candidates = BTreeMap::new();
+        let mut nodes = BTreeMap::new();
+
+        scorer.find_candidates(Path::new("/"), &dom.document, &mut candidates, &mut nodes);
+
+        let tags = debug_candidates(&candidates);
+
+        // The content score of the paragraph is 1 + 4 comma-separated parts.
+        assert!(tags.contains(&CandidateTag::new("div", Some("x1"), 10.0)));
+        assert!(tags.contains(&CandidateTag::new("div", Some("x2"), 7.5)));
+        assert!(tags.contains(&CandidateTag::new("div", Some("x3"), 5.0 + 5.0 / 6.0)));
+        assert!(tags.contains(&CandidateTag::new("body", None, 5.0 / 9.0)));
+    }
+
+    #[test]
+    fn test_calculate_text_score_readability_js() {
+        let scorer = Scorer::new(ScorerOptions {
+            candidate_score: CandidateScore::ReadabilityJs,
+            ..Default::default()
+        })
+        .unwrap();
+
+        // Readability.js: 1 + "a, b, c".split(commas).length (3) + min(floor(7 / 100), 3) (0)
+        assert_eq!(scorer.calculate_text_score("a, b, c".to_owned()), 4.0);
+        // 1 + 1 part without commas + 2 for 250 characters
+        assert_eq!(scorer.calculate_text_score("a".repeat(250)), 4.0);
+    }
+
+    #[test]
+    fn test_calculate_content_score_line_breaks() {
+        let dom =
+            parse_document(RcDom::default(), Default::default()).one("<p>First.<br>Second</p>");
+        let paragraph = &dom::find_by_tag(&dom.document, "p")[0];
+
+        // The `br` is a line break, so that "First." is followed by punctuation.
+        let scorer = Scorer::default();
+        assert_eq!(scorer.calculate_content_score(paragraph), 2.0);
+
+        let scorer = Scorer::new(ScorerOptions {
+            legacy_line_breaks: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(scorer.calculate_content_score(paragraph), 1.0);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_find_candidates_parallel() {
+        let mut file = File::open("benches/wikipedia.html").unwrap();
+        let mut html = String::new();
+        file.read_to_string(&mut html).unwrap();
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap();
+
+        let find_candidates = |parallel| {
+            let scorer = Scorer::new(ScorerOptions {
+                parallel,
+                ..Default::default()
+            })
+            .unwrap();
+            let mut candidates = BTreeMap::new();
+            let mut nodes = BTreeMap::new();
+            scorer.find_candidates(Path::new("/"), &dom.document, &mut candidates, &mut nodes);
+            debug_candidates(&candidates)
+        };
+
+        let tags = find_candidates(true);
+
+        assert!(!tags.is_empty());
+        assert_eq!(tags, find_candidates(false));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_find_candidates_parallel_line_breaks() {
+        let line = "A line of a poem, which ends with a period.<br>";
+        let html = format!(
+            "<body><div><p>{}</p><p>{}</p></div></body>",
+            line.repeat(5),
+            line.repeat(3)
+        );
+        let dom = parse_document(RcDom::default(), Default::default()).one(html);
+
+        for legacy_line_breaks in [false, true] {
+            let find_candidates = |parallel| {
+                let scorer = Scorer::new(ScorerOptions {
+                    parallel,
+                    legacy_line_breaks,
+                    ..Default::default()
+                })
+                .unwrap();
+                let mut candidates = BTreeMap::new();
+                let mut nodes = BTreeMap::new();
+                scorer.find_candidates(Path::new("/"), &dom.document, &mut candidates, &mut nodes);
+                debug_candidates(&candidates)
+            };
+
+            let tags = find_candidates(true);
+
+            assert!(!tags.is_empty());
+            assert_eq!(tags, find_candidates(false));
+        }
+    }
+
+    #[test]
+    fn test_classify_word_start() {
+        let scorer = Scorer::default();
+        for value in [
+            "comments",
+            "post-comments",
+            "comment_list",
+            "com-box",
+            "sidebar",
+        ] {
+            let classes = scorer.classify(value);
+            assert!(classes.unlikely || classes.negative, "{value}");
+        }
+        for value in ["recommended", "company", "common", "commerce-article"] {
+            let classes = scorer.classify(value);
+            assert!(!classes.unlikely && !classes.negative, "{value}");
+        }
+
+        let scorer = Scorer::new(ScorerOptions {
+            legacy_candidate_matching: true,
+            ..Default::default()
+        })
+        .unwrap();
+        for value in ["recommended", "company", "common"] {
+            assert!(scorer.classify(value).negative, "{value}");
+        }
+
+        // Composed patterns keep matching at the start of a word.
+        let patterns = CandidatePatterns::default()
+            .with_extra_unlikely("newsletter")
+            .and_then(|patterns| patterns.without_negative(&["media"]))
+            .unwrap();
+        let scorer = Scorer::new(ScorerOptions::from(&patterns)).unwrap();
+        assert!(scorer.classify("site-newsletter").unlikely);
+        assert!(!scorer.classify("sitenewsletter").unlikely);
+        assert!(scorer.classify("com-box").negative);
+        assert!(!scorer.classify("telecom-box").negative);
+        assert!(!scorer.classify("media-box").negative);
+
+        let scorer = Scorer::new(ScorerOptions {
+            legacy_candidate_matching: true,
+            ..ScorerOptions::from(&patterns)
+        })
+        .unwrap();
+        assert!(scorer.classify("sitenewsletter").unlikely);
+        assert!(scorer.classify("telecom").negative);
+
+        // Custom patterns are matched as given.
+        let regex = candidate_regex(&["comment", "com-"]).unwrap();
+        let scorer = Scorer::new(ScorerOptions {
+            unlikely_candidates: &regex,
+            negative_candidates: &regex,
+            ..Default::default()
+        })
+        .unwrap();
+        for value in ["postcomments", "telecom-box"] {
+            let classes = scorer.classify(value);
+            assert!(classes.unlikely && classes.negative, "{value}");
+            assert_eq!(classes.unlikely, regex.is_match(value));
+        }
+
+        // The flags of a `RegexBuilder` are kept.
+        let regex = RegexBuilder::new("comment")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        let scorer = Scorer::new(ScorerOptions {
+            unlikely_candidates: &regex,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(scorer.classify("POST-COMMENTS").unlikely);
+    }
+
+    #[test]
+    fn test_class_weight() {
+        let html =
+            r#"<div id="post" class="post">Text</div><div id="x" class="comment">Text</div>"#;
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap();
+        let divs = dom::find_by_tag(&dom.document, "div");
+        let class_weights = |options: ScorerOptions| {
+            let scorer = Scorer::new(options).unwrap();
+            (
+                scorer.get_class_weight(&divs[0]),
+                scorer.get_class_weight(&divs[1]),
+            )
+        };
+
+        assert_eq!(class_weights(ScorerOptions::default()), (50.0, -25.0));
+        assert_eq!(
+            class_weights(ScorerOptions {
+                class_weight_mode: ClassWeightMode::PerElement,
+                ..Default::default()
+            }),
+            (25.0, -25.0)
+        );
+        assert_eq!(
+            class_weights(ScorerOptions {
+                class_weight_cap: Some(10.0),
+                ..Default::default()
+            }),
+            (10.0, -10.0)
+        );
+    }
+
+    #[test]
+    fn test_init_content_score() {
+        let html = concat!(
+            "<section>Text</section><dl><dt>Term</dt><dd>A description of the term.</dd></dl>",
+            "<dl><dt>Author</dt><dd>Jane Doe</dd><dt>Date</dt><dd>Today</dd></dl>"
+        );
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap();
+        let section = &dom::find_by_tag(&dom.document, "section")[0];
+        let lists = dom::find_by_tag(&dom.document, "dl");
+
+        let scorer = Scorer::default();
+        assert_eq!(scorer.init_content_score(section), 0.0);
+        assert_eq!(scorer.init_content_score(&lists[0]), 0.0);
+        assert_eq!(scorer.init_content_score(&lists[1]), -3.0);
+
+        let scorer = Scorer::new(ScorerOptions {
+            tag_scores: &[("section", 5.0), ("dl", 2.0)],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(scorer.init_content_score(section), 5.0);
+        assert_eq!(scorer.init_content_score(&lists[0]), 2.0);
+        assert_eq!(scorer.init_content_score(&lists[1]), 2.0);
+    }
+
+    #[test]
+    fn test_link_density() {
+        let html = r##"<p>1234567890<a href="#fn1">1234567890</a></p>"##;
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap();
+        let p = dom::find_by_tag(&dom.document, "p")[0].clone();
+
+        assert_eq!(get_link_density(&p), 0.5);
+        let link_density = Scorer::default().link_density(&p);
+        assert!((link_density - 0.15).abs() < f32::EPSILON);
+    }
+
     #[test]
     fn test_find_candidates_basic() {
         let html = r#"
@@ -564,7 +2612,7 @@ mod tests {
             </body>
         </html>"#;
         let options = ScorerOptions::default();
-        let scorer = Scorer::new(options);
+        let scorer = Scorer::new(options).unwrap();
         let dom = parse_document(RcDom::default(), Default::default())
             .from_utf8()
             .read_from(&mut html.as_bytes())
@@ -575,7 +2623,7 @@ mod tests {
         let mut candidates = BTreeMap::new();
         let mut nodes = BTreeMap::new();
 
-        scorer.find_candidates(Path::new("/"), dom.document, &mut candidates, &mut nodes);
+        scorer.find_candidates(Path::new("/"), &dom.document, &mut candidates, &mut nodes);
 
         let tags = dbg!(debug_candidates(&candidates));
 
@@ -583,6 +2631,151 @@ mod tests {
         assert!(tags.contains(&CandidateTag::new("body", None, 1.0)));
     }
 
+    #[test]
+    fn test_find_candidates_limits() {
+        let paragraph = "<p>This is a test paragraph, with more than 25 characters.</p>";
+        let html = format!(
+            "<html><body>{}</body></html>",
+            format!("<div>{}</div>", paragraph.repeat(4)).repeat(20)
+        );
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap();
+        let find_candidates = |options: ScorerOptions| {
+            let mut candidates = BTreeMap::new();
+            let mut nodes = BTreeMap::new();
+            let stats = Scorer::new(options).unwrap().find_candidates(
+                Path::new("/"),
+                &dom.document,
+                &mut candidates,
+                &mut nodes,
+            );
+            (candidates, stats)
+        };
+
+        let (candidates, stats) = find_candidates(ScorerOptions::default());
+        assert_eq!(candidates.len(), 21);
+        assert_eq!(stats.candidates, 21);
+        assert!(!stats.candidate_limit_reached);
+        assert!(!stats.terminated_early);
+
+        let (candidates, stats) = find_candidates(ScorerOptions {
+            max_candidates: Some(5),
+            ..Default::default()
+        });
+        assert_eq!(candidates.len(), 5);
+        assert!(stats.candidate_limit_reached);
+
+        let (_, stats) = find_candidates(ScorerOptions {
+            early_termination: Some(EarlyTermination {
+                min_nodes: 10,
+                min_confidence: 0.0,
+            }),
+            ..Default::default()
+        });
+        assert!(stats.terminated_early);
+        assert_eq!(stats.nodes, 10);
+
+        let (_, full_stats) = find_candidates(ScorerOptions::default());
+        let (_, stats) = find_candidates(ScorerOptions {
+            early_termination: Some(EarlyTermination {
+                min_nodes: 10,
+                min_confidence: 1.0,
+            }),
+            ..Default::default()
+        });
+        assert!(!stats.terminated_early);
+        assert_eq!(stats.nodes, full_stats.nodes);
+    }
+
+    #[test]
+    fn test_candidate_score_weight() {
+        let weights = |candidate_score: CandidateScore| {
+            (2..=5)
+                .map(|level| candidate_score.weight(level))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(weights(CandidateScore::EqualWeight), [1.0; 4]);
+        assert_eq!(
+            weights(CandidateScore::LevelWeight),
+            [0.5, 1.0 / 3.0, 0.25, 0.2]
+        );
+        assert_eq!(
+            weights(CandidateScore::ReadabilityJsWeight),
+            [1.0, 0.5, 1.0 / 6.0, 1.0 / 9.0]
+        );
+        assert_eq!(
+            weights(CandidateScore::ReadabilityJsWeight),
+            weights(CandidateScore::ReadabilityJs)
+        );
+        assert_eq!(
+            weights(CandidateScore::Decay(&|level| 1.0 / (level * level) as f32)),
+            [0.25, 1.0 / 9.0, 1.0 / 16.0, 1.0 / 25.0]
+        );
+    }
+
+    #[test]
+    fn test_length_metric() {
+        let text = "这是一个测试 with words";
+
+        assert_eq!(LengthMetric::Chars.len(text), 17.0);
+        assert_eq!(
+            LengthMetric::CjkWeighted { multiplier: 3.0 }.len(text),
+            29.0
+        );
+        assert_eq!(
+            LengthMetric::Words {
+                chars_per_word: 5.0
+            }
+            .len(text),
+            40.0
+        );
+    }
+
+    #[test]
+    fn test_find_candidates_cjk() {
+        let html = "<html><body><div><p>这是一个关于中文文章的测试段落。</p></div></body></html>";
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap();
+
+        let mut candidates = BTreeMap::new();
+        let mut nodes = BTreeMap::new();
+        let scorer = Scorer::default();
+        scorer.find_candidates(Path::new("/"), &dom.document, &mut candidates, &mut nodes);
+        assert!(candidates.is_empty());
+
+        let scorer = Scorer::new(ScorerOptions {
+            length_metric: LengthMetric::CjkWeighted { multiplier: 2.0 },
+            ..Default::default()
+        })
+        .unwrap();
+        scorer.find_candidates(Path::new("/"), &dom.document, &mut candidates, &mut nodes);
+        assert!(!candidates.is_empty());
+    }
+
+    #[test]
+    fn test_candidate_regex() {
+        let regex = candidate_regex(&["comment", "disqus"]).unwrap();
+        assert!(regex.is_match("comment-list"));
+        assert!(regex.is_match("disqus_thread"));
+        assert!(!regex.is_match("article"));
+
+        let scorer = Scorer::new(ScorerOptions {
+            unlikely_candidates: &regex,
+            ..Default::default()
+        })
+        .unwrap();
+        let classes = scorer.classify("comment main");
+        assert!(classes.unlikely);
+        assert!(classes.likely);
+        assert!(classes.positive);
+        assert!(classes.negative);
+    }
+
     #[test]
     fn test_find_candidates_comments() {
         let mut file = File::open("data/comments/input.html").unwrap();
@@ -598,7 +2791,7 @@ mod tests {
             positive_candidates: &Regex::new("article|body|content|entry|hentry|main|page|pagination|post|blog|story").unwrap(),
             ..Default::default()
         };
-        let scorer = Scorer::new(options);
+        let scorer = Scorer::new(options).unwrap();
         let dom = parse_document(RcDom::default(), Default::default())
             .from_utf8()
             .read_from(&mut html.as_bytes())
@@ -609,7 +2802,7 @@ mod tests {
         let mut candidates = BTreeMap::new();
         let mut nodes = BTreeMap::new();
 
-        scorer.find_candidates(Path::new("/"), dom.document, &mut candidates, &mut nodes);
+        scorer.find_candidates(Path::new("/"), &dom.document, &mut candidates, &mut nodes);
 
         let tags = dbg!(debug_candidates(&candidates));
 
@@ -0,0 +1,129 @@
+//! Measure the text of the content in different units.
+
+use crate::{
+    dom::{Handle, NodeData},
+    scorer::is_cjk,
+};
+#[cfg(feature = "unicode-segmentation")]
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The length of a text in different units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TextStats {
+    /// The number of UTF-8 bytes.
+    pub bytes: usize,
+    /// The number of characters, i.e. Unicode scalar values.
+    pub chars: usize,
+    /// The number of grapheme clusters, e.g. an emoji with a skin tone
+    /// modifier counts as one grapheme but two characters.
+    ///
+    /// Requires the `unicode-segmentation` feature, and is the number of
+    /// characters otherwise.
+    pub graphemes: usize,
+    /// The number of words, where each CJK character counts as a word.
+    ///
+    /// With the `unicode-segmentation` feature, words are segmented according
+    /// to Unicode Standard Annex #29, and estimated otherwise.
+    pub words: usize,
+}
+
+/// Measure the text of a node.
+///
+/// Like the text length of the scorer, the bytes, characters, and graphemes
+/// are measured for the trimmed text nodes, while the words are counted for
+/// the whole text.
+pub fn text_stats(handle: &Handle) -> TextStats {
+    let mut stats = TextStats::default();
+    let mut text = String::new();
+    collect_text(handle, &mut stats, &mut text);
+    stats.words = count_words(&text);
+    stats
+}
+
+fn collect_text(handle: &Handle, stats: &mut TextStats, text: &mut String) {
+    for child in handle.children.borrow().iter() {
+        match child.data {
+            NodeData::Text { ref contents } => {
+                let contents = contents.borrow();
+                let trimmed = contents.trim();
+                stats.bytes += trimmed.len();
+                stats.chars += trimmed.chars().count();
+                stats.graphemes += count_graphemes(trimmed);
+                text.push_str(&contents);
+            }
+            NodeData::Element { .. } => collect_text(child, stats, text),
+            _ => (),
+        }
+    }
+}
+
+/// Count the grapheme clusters of a text.
+pub(crate) fn count_graphemes(text: &str) -> usize {
+    #[cfg(feature = "unicode-segmentation")]
+    return text.graphemes(true).count();
+    #[cfg(not(feature = "unicode-segmentation"))]
+    return text.chars().count();
+}
+
+/// Count the words of a text, where each CJK character counts as a word.
+pub(crate) fn count_words(text: &str) -> usize {
+    #[cfg(feature = "unicode-segmentation")]
+    return text
+        .unicode_words()
+        .map(|word| word.chars().filter(|c| is_cjk(*c)).count().max(1))
+        .sum();
+    #[cfg(not(feature = "unicode-segmentation"))]
+    return estimate_words(text);
+}
+
+/// Estimate the words of a text, which are separated by whitespace.
+pub(crate) fn estimate_words(text: &str) -> usize {
+    let mut words = 0;
+    let mut in_word = false;
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            words += 1;
+            in_word = false;
+        } else if c.is_whitespace() {
+            in_word = false;
+        } else if !in_word {
+            words += 1;
+            in_word = true;
+        }
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    #[test]
+    fn test_text_stats() {
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut "<p> Café <em>au lait</em> 👍🏽 这是 </p>".as_bytes())
+            .unwrap();
+
+        let stats = text_stats(&dom.document);
+
+        assert_eq!(stats.bytes, 27);
+        assert_eq!(stats.chars, 16);
+        #[cfg(feature = "unicode-segmentation")]
+        {
+            assert_eq!(stats.graphemes, 15);
+            // The emoji is not a word.
+            assert_eq!(stats.words, 5);
+        }
+        #[cfg(not(feature = "unicode-segmentation"))]
+        {
+            assert_eq!(stats.graphemes, 16);
+            assert_eq!(stats.words, 6);
+        }
+    }
+}
@@ -0,0 +1,751 @@
+//! Render the content as plain text which preserves the document structure.
+
+use crate::dom::{get_attr, is_tag, Handle, NodeData};
+use html5ever::local_name;
+use unicode_normalization::UnicodeNormalization;
+
+/// The style of headings in the text output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeadingStyle {
+    /// Underline `h1` with `=` and all other headings with `-`.
+    #[default]
+    Underline,
+    /// Prefix headings with `#` according to their level, as in Markdown.
+    Prefix,
+    /// Render headings as plain paragraphs.
+    Plain,
+}
+
+#[derive(Debug, Clone)]
+pub struct TextOptions {
+    pub heading_style: HeadingStyle,
+    /// The bullet of unordered list items.
+    pub bullet: char,
+    /// The prefix of lines in a blockquote.
+    pub blockquote_prefix: String,
+    /// The separator between table cells.
+    pub cell_separator: char,
+    /// Wrap the content of `pre` elements in ```` ``` ```` fences, including the
+    /// language of a `language-*` class.
+    pub fence_code_blocks: bool,
+    /// Append a `[1]` style marker to links and list their urls as footnotes
+    /// at the end of the text.
+    pub link_footnotes: bool,
+    /// Render emphasis, inline code, links, and images as Markdown, unless
+    /// links are rendered as footnotes.
+    pub inline_markup: bool,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            heading_style: HeadingStyle::default(),
+            bullet: '*',
+            blockquote_prefix: "> ".to_owned(),
+            cell_separator: '\t',
+            fence_code_blocks: false,
+            link_footnotes: false,
+            inline_markup: false,
+        }
+    }
+}
+
+impl TextOptions {
+    /// The options to render the content as Markdown.
+    pub fn markdown() -> Self {
+        Self {
+            heading_style: HeadingStyle::Prefix,
+            bullet: '-',
+            fence_code_blocks: true,
+            inline_markup: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// The normalization of the text output.
+#[derive(Debug, Clone)]
+pub struct NormalizeOptions {
+    /// Collapse runs of whitespace within a line into a single space, or a
+    /// single tab if the run contains a tab, and runs of blank lines into a
+    /// single blank line.
+    pub collapse_whitespace: bool,
+    /// Convert non-breaking spaces to regular spaces.
+    pub replace_nbsp: bool,
+    /// Trim whitespace at the start and end of each line, and blank lines at
+    /// the start and end of the text.
+    pub trim_lines: bool,
+    /// Apply the Unicode normalization form C.
+    pub nfc: bool,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            collapse_whitespace: true,
+            replace_nbsp: true,
+            trim_lines: true,
+            nfc: false,
+        }
+    }
+}
+
+/// Normalize whitespace and Unicode in the text output.
+///
+/// Note that collapsing whitespace also applies to preformatted text.
+pub fn normalize_text(text: &str, opts: &NormalizeOptions) -> String {
+    let mut text = if opts.nfc {
+        text.nfc().collect::<String>()
+    } else {
+        text.to_owned()
+    };
+
+    if opts.replace_nbsp {
+        text = text.replace(['\u{a0}', '\u{202f}'], " ");
+    }
+
+    let mut lines = vec![];
+
+    for line in text.split('\n') {
+        let mut line = if opts.collapse_whitespace {
+            collapse_whitespace(line)
+        } else {
+            line.to_owned()
+        };
+
+        if opts.trim_lines {
+            line = line.trim().to_owned();
+        }
+
+        let is_blank = line.trim().is_empty();
+        let last_is_blank = lines
+            .last()
+            .is_some_and(|last: &String| last.trim().is_empty());
+
+        if opts.collapse_whitespace && is_blank && last_is_blank {
+            continue;
+        }
+        if opts.trim_lines && is_blank && lines.is_empty() {
+            continue;
+        }
+
+        lines.push(line);
+    }
+
+    if opts.trim_lines {
+        while lines.last().is_some_and(|last| last.trim().is_empty()) {
+            lines.pop();
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn collapse_whitespace(line: &str) -> String {
+    let mut collapsed = String::with_capacity(line.len());
+    let mut whitespace: Option<char> = None;
+
+    for c in line.chars() {
+        if c.is_whitespace() && c != '\u{a0}' && c != '\u{202f}' {
+            whitespace = match whitespace {
+                Some('\t') => Some('\t'),
+                _ if c == '\t' => Some('\t'),
+                _ => Some(' '),
+            };
+        } else {
+            if let Some(whitespace) = whitespace.take() {
+                collapsed.push(whitespace);
+            }
+            collapsed.push(c);
+        }
+    }
+
+    if let Some(whitespace) = whitespace {
+        collapsed.push(whitespace);
+    }
+
+    collapsed
+}
+
+/// Render a node as plain text.
+///
+/// In contrast to [`extract_text`](crate::extract_text), headings, lists,
+/// blockquotes, tables and line breaks are preserved. Whitespace is collapsed
+/// as in rendered HTML, except in `pre` and `code` elements.
+pub fn render_text(handle: &Handle, opts: &TextOptions) -> String {
+    let mut renderer = Renderer {
+        opts,
+        lines: vec![],
+        line: String::new(),
+        prefixes: vec![],
+        marker: None,
+        lists: vec![],
+        blank_line: None,
+        whitespace: Whitespace::Normal,
+        links: vec![],
+    };
+
+    renderer.render_children(handle);
+    renderer.flush();
+
+    if !renderer.links.is_empty() {
+        renderer.block();
+
+        for (index, link) in std::mem::take(&mut renderer.links).into_iter().enumerate() {
+            renderer.push_line(format!("[{}] {link}", index + 1));
+        }
+    }
+
+    renderer.lines.join("\n")
+}
+
+struct Renderer<'a> {
+    opts: &'a TextOptions,
+    lines: Vec<String>,
+    /// The inline text of the current line.
+    line: String,
+    /// The prefixes of nested blockquotes and list items.
+    prefixes: Vec<String>,
+    /// The list marker which replaces the last prefix in the next line.
+    marker: Option<String>,
+    /// The counters of nested lists, which are `None` for unordered lists.
+    lists: Vec<Option<usize>>,
+    /// Separate the next line by a blank line with the given prefix.
+    blank_line: Option<String>,
+    whitespace: Whitespace,
+    /// The urls of the link footnotes.
+    links: Vec<String>,
+}
+
+/// The handling of whitespace in text nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Whitespace {
+    /// Collapse whitespace.
+    Normal,
+    /// Preserve whitespace and line breaks, i.e. in `pre` elements.
+    Pre,
+    /// Preserve whitespace but not line breaks, i.e. in inline `code` elements.
+    Code,
+}
+
+impl Renderer<'_> {
+    fn render_children(&mut self, handle: &Handle) {
+        for child in handle.children.borrow().iter() {
+            self.render(child);
+        }
+    }
+
+    fn render(&mut self, handle: &Handle) {
+        match handle.data {
+            NodeData::Text { ref contents } => self.push_text(&contents.borrow()),
+            NodeData::Element { ref name, .. } => match name.local {
+                local_name!("script") | local_name!("style") | local_name!("template") => (),
+                local_name!("br") if self.whitespace == Whitespace::Pre => self.line.push('\n'),
+                local_name!("br") => self.flush_line(),
+                local_name!("pre") => self.render_pre(handle),
+                local_name!("a") if self.opts.link_footnotes => {
+                    self.render_children(handle);
+
+                    if let Some(href) = get_attr(handle, "href").filter(|href| is_footnote(href)) {
+                        let index = match self.links.iter().position(|link| *link == href) {
+                            Some(index) => index,
+                            None => {
+                                self.links.push(href);
+                                self.links.len() - 1
+                            }
+                        };
+
+                        self.line.push_str(&format!("[{}]", index + 1));
+                    }
+                }
+                local_name!("a") if self.opts.inline_markup => {
+                    match get_attr(handle, "href").filter(|href| is_footnote(href)) {
+                        Some(href) => {
+                            self.render_inline(handle, "[", &format!("]({})", href.trim()))
+                        }
+                        None => self.render_children(handle),
+                    }
+                }
+                local_name!("strong") | local_name!("b")
+                    if self.opts.inline_markup && self.whitespace == Whitespace::Normal =>
+                {
+                    self.render_inline(handle, "**", "**")
+                }
+                local_name!("em") | local_name!("i")
+                    if self.opts.inline_markup && self.whitespace == Whitespace::Normal =>
+                {
+                    self.render_inline(handle, "*", "*")
+                }
+                local_name!("img") if self.opts.inline_markup => {
+                    if let Some(src) = get_attr(handle, "src").filter(|src| !src.trim().is_empty())
+                    {
+                        let alt = get_attr(handle, "alt").unwrap_or_default();
+                        self.push_text(&format!("![{}]({})", alt.trim(), src.trim()));
+                    }
+                }
+                local_name!("code") if self.whitespace == Whitespace::Normal => {
+                    self.whitespace = Whitespace::Code;
+                    if self.opts.inline_markup {
+                        self.render_inline(handle, "`", "`");
+                    } else {
+                        self.render_children(handle);
+                    }
+                    self.whitespace = Whitespace::Normal;
+                }
+                local_name!("hr") => {
+                    self.block();
+                    self.push_line("---".to_owned());
+                    self.block();
+                }
+                local_name!("h1")
+                | local_name!("h2")
+                | local_name!("h3")
+                | local_name!("h4")
+                | local_name!("h5")
+                | local_name!("h6") => {
+                    let level = name.local.as_bytes()[1] - b'0';
+                    self.render_heading(handle, level as usize);
+                }
+                local_name!("ul") | local_name!("ol") => {
+                    let counter = match name.local {
+                        local_name!("ol") => Some(
+                            get_attr(handle, "start")
+                                .and_then(|start| start.trim().parse().ok())
+                                .unwrap_or(1),
+                        ),
+                        _ => None,
+                    };
+
+                    // Nested lists continue the parent list item.
+                    let is_nested = !self.lists.is_empty();
+                    if is_nested {
+                        self.flush();
+                    } else {
+                        self.block();
+                    }
+                    self.lists.push(counter);
+                    self.render_children(handle);
+                    self.flush();
+                    self.lists.pop();
+                    if !is_nested {
+                        self.block();
+                    }
+                }
+                local_name!("li") => {
+                    self.flush();
+
+                    let marker = match self.lists.last_mut() {
+                        Some(Some(counter)) => {
+                            *counter += 1;
+                            format!("{}. ", *counter - 1)
+                        }
+                        _ => format!("{} ", self.opts.bullet),
+                    };
+
+                    self.prefixes.push(" ".repeat(marker.chars().count()));
+                    self.marker = Some(marker);
+                    self.render_children(handle);
+                    self.flush();
+                    self.marker = None;
+                    self.prefixes.pop();
+                }
+                local_name!("blockquote") => {
+                    self.block();
+                    self.prefixes.push(self.opts.blockquote_prefix.clone());
+                    self.render_children(handle);
+                    self.flush();
+                    self.prefixes.pop();
+                    self.block();
+                }
+                local_name!("tr") => {
+                    self.flush();
+                    let mut is_first_cell = true;
+
+                    for cell in handle.children.borrow().iter() {
+                        if !matches!(cell.data, NodeData::Element { .. }) {
+                            continue;
+                        }
+                        if !is_first_cell {
+                            self.line.push(self.opts.cell_separator);
+                        }
+                        is_first_cell = false;
+                        self.render_children(cell);
+                    }
+
+                    self.flush();
+                }
+                local_name!("table")
+                | local_name!("p")
+                | local_name!("div")
+                | local_name!("section")
+                | local_name!("article")
+                | local_name!("header")
+                | local_name!("footer")
+                | local_name!("main")
+                | local_name!("aside")
+                | local_name!("nav")
+                | local_name!("figure")
+                | local_name!("figcaption")
+                | local_name!("address")
+                | local_name!("dl")
+                | local_name!("dt")
+                | local_name!("dd")
+                | local_name!("form")
+                | local_name!("fieldset")
+                | local_name!("details")
+                | local_name!("summary") => {
+                    self.block();
+                    self.render_children(handle);
+                    self.block();
+                }
+                _ => self.render_children(handle),
+            },
+            NodeData::Document => self.render_children(handle),
+            _ => (),
+        }
+    }
+
+    fn render_heading(&mut self, handle: &Handle, level: usize) {
+        self.block();
+        self.render_children(handle);
+
+        let heading = self.line.trim().to_owned();
+        self.line.clear();
+
+        if !heading.is_empty() {
+            match self.opts.heading_style {
+                HeadingStyle::Underline => {
+                    let underline = if level == 1 { "=" } else { "-" };
+                    let len = heading.chars().count();
+                    self.push_line(heading);
+                    self.push_line(underline.repeat(len));
+                }
+                HeadingStyle::Prefix => {
+                    self.push_line(format!("{} {heading}", "#".repeat(level)));
+                }
+                HeadingStyle::Plain => self.push_line(heading),
+            }
+        }
+
+        self.block();
+    }
+
+    fn render_pre(&mut self, handle: &Handle) {
+        self.block();
+
+        self.whitespace = Whitespace::Pre;
+        self.render_children(handle);
+        self.whitespace = Whitespace::Normal;
+
+        let code = std::mem::take(&mut self.line);
+        let code = code.strip_suffix('\n').unwrap_or(&code);
+
+        if self.opts.fence_code_blocks {
+            let language = code_language(handle).unwrap_or_default();
+            self.push_line(format!("```{language}"));
+        }
+        for line in code.lines() {
+            self.push_line(line.to_owned());
+        }
+        if self.opts.fence_code_blocks {
+            self.push_line("```".to_owned());
+        }
+
+        self.block();
+    }
+
+    /// Wrap the inline text of the children in markup, e.g. `**` for strong
+    /// emphasis, unless it's empty or spans multiple lines.
+    fn render_inline(&mut self, handle: &Handle, open: &str, close: &str) {
+        let lines = self.lines.len();
+        let start = self.line.len();
+        self.render_children(handle);
+
+        if self.lines.len() != lines || self.line.len() < start {
+            return;
+        }
+
+        let inner = self.line.split_off(start);
+        let text = inner.trim();
+
+        if text.is_empty() {
+            self.line.push_str(&inner);
+            return;
+        }
+
+        if inner.starts_with(' ') {
+            self.line.push(' ');
+        }
+        self.line.push_str(open);
+        self.line.push_str(text);
+        self.line.push_str(close);
+        if inner.ends_with(' ') {
+            self.line.push(' ');
+        }
+    }
+
+    /// Append inline text, collapsing whitespace as in rendered HTML.
+    fn push_text(&mut self, text: &str) {
+        match self.whitespace {
+            Whitespace::Pre => return self.line.push_str(text),
+            Whitespace::Code => return self.line.push_str(&text.replace('\n', " ")),
+            Whitespace::Normal => (),
+        }
+
+        for (index, word) in text.split_ascii_whitespace().enumerate() {
+            let needs_space = index > 0 || text.starts_with(|c: char| c.is_ascii_whitespace());
+
+            if needs_space && !self.line.is_empty() && !self.line.ends_with(' ') {
+                self.line.push(' ');
+            }
+            self.line.push_str(word);
+        }
+
+        if text.ends_with(|c: char| c.is_ascii_whitespace()) && !self.line.is_empty() {
+            self.line.push(' ');
+        }
+    }
+
+    /// Start a new block, which is separated by a blank line.
+    fn block(&mut self) {
+        self.flush();
+
+        // Consecutive block boundaries are separated by the outermost prefix.
+        let prefix = self.prefix(false);
+        if self
+            .blank_line
+            .as_ref()
+            .is_none_or(|blank_line| prefix.len() < blank_line.len())
+        {
+            self.blank_line = Some(prefix);
+        }
+    }
+
+    /// End the current line if it contains any text.
+    fn flush(&mut self) {
+        if !self.line.trim().is_empty() {
+            self.flush_line();
+        }
+        self.line.clear();
+    }
+
+    /// End the current line, even if it is empty.
+    fn flush_line(&mut self) {
+        let line = self.line.trim().to_owned();
+        self.line.clear();
+        self.push_line(line);
+    }
+
+    fn push_line(&mut self, text: String) {
+        if let Some(prefix) = self.blank_line.take() {
+            if !self.lines.is_empty() {
+                self.lines.push(prefix.trim_end().to_owned());
+            }
+        }
+
+        let mut line = self.prefix(true);
+        line.push_str(&text);
+        self.lines.push(line.trim_end().to_owned());
+    }
+
+    fn prefix(&mut self, use_marker: bool) -> String {
+        let mut prefix = String::new();
+        let marker = if use_marker { self.marker.take() } else { None };
+
+        for (index, item) in self.prefixes.iter().enumerate() {
+            match marker {
+                Some(ref marker) if index == self.prefixes.len() - 1 => prefix.push_str(marker),
+                _ => prefix.push_str(item),
+            }
+        }
+
+        prefix
+    }
+}
+
+/// Check if the link should be listed as footnote, i.e. it doesn't point to
+/// a fragment in the same document or run a script.
+fn is_footnote(href: &str) -> bool {
+    let href = href.trim();
+    !href.is_empty() && !href.starts_with('#') && !href.starts_with("javascript:")
+}
+
+/// Get the language of a code block from a `language-*` or `lang-*` class of
+/// the `pre` element or its `code` child.
+fn code_language(handle: &Handle) -> Option<String> {
+    let language = |node: &Handle| {
+        get_attr(node, "class")?
+            .split_ascii_whitespace()
+            .find_map(|class| {
+                class
+                    .strip_prefix("language-")
+                    .or_else(|| class.strip_prefix("lang-"))
+            })
+            .map(|language| language.to_owned())
+    };
+
+    language(handle).or_else(|| {
+        handle
+            .children
+            .borrow()
+            .iter()
+            .filter(|child| is_tag(child, "code"))
+            .find_map(language)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    fn render(html: &str, opts: &TextOptions) -> String {
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .unwrap();
+        render_text(&dom.document, opts)
+    }
+
+    #[test]
+    fn test_render_text() {
+        let html = r#"
+            <h1>Title</h1>
+            <p>First   paragraph
+               with <em>inline</em> text.</p>
+            <h2>Lists</h2>
+            <ul>
+                <li>One</li>
+                <li>Two
+                    <ol start="3"><li>Three</li><li>Four</li></ol>
+                </li>
+            </ul>
+            <blockquote><p>Quote</p><p>Line<br>break</p></blockquote>
+            <table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>
+        "#;
+
+        assert_eq!(
+            render(html, &TextOptions::default()),
+            "Title\n\
+             =====\n\
+             \n\
+             First paragraph with inline text.\n\
+             \n\
+             Lists\n\
+             -----\n\
+             \n\
+             * One\n\
+             * Two\n\
+             \x20 3. Three\n\
+             \x20 4. Four\n\
+             \n\
+             > Quote\n\
+             >\n\
+             > Line\n\
+             > break\n\
+             \n\
+             A\tB\n\
+             1\t2"
+        );
+    }
+
+    #[test]
+    fn test_render_text_code() {
+        let html = "<p>Call <code>foo(a,  b)</code>  now.</p>\
+            <pre class=\"language-rust\"><code>fn main() {\n    println!();\n}\n</code></pre>";
+
+        assert_eq!(
+            render(html, &TextOptions::default()),
+            "Call foo(a,  b) now.\n\nfn main() {\n    println!();\n}"
+        );
+
+        let opts = TextOptions {
+            fence_code_blocks: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            render(html, &opts),
+            "Call foo(a,  b) now.\n\n```rust\nfn main() {\n    println!();\n}\n```"
+        );
+    }
+
+    #[test]
+    fn test_normalize_text() {
+        let text = "\n  First  \u{a0}line \n\n\n\tSecond\t\tline\nCafe\u{301}\n\n";
+
+        assert_eq!(
+            normalize_text(text, &NormalizeOptions::default()),
+            "First line\n\nSecond\tline\nCafe\u{301}"
+        );
+        assert_eq!(
+            normalize_text(
+                text,
+                &NormalizeOptions {
+                    nfc: true,
+                    ..Default::default()
+                }
+            ),
+            "First line\n\nSecond\tline\nCaf\u{e9}"
+        );
+        assert_eq!(
+            normalize_text(
+                text,
+                &NormalizeOptions {
+                    collapse_whitespace: false,
+                    replace_nbsp: false,
+                    trim_lines: false,
+                    nfc: false,
+                }
+            ),
+            text
+        );
+    }
+
+    #[test]
+    fn test_render_text_link_footnotes() {
+        let html = r##"<p>See <a href="https://example.com/a">this</a>,
+            <a href="https://example.com/b">that</a>, and
+            <a href="https://example.com/a">this again</a>.
+            <a href="#top">Top</a></p>"##;
+        let opts = TextOptions {
+            link_footnotes: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            render(html, &opts),
+            "See this[1], that[2], and this again[1]. Top\n\n\
+             [1] https://example.com/a\n\
+             [2] https://example.com/b"
+        );
+    }
+
+    #[test]
+    fn test_render_text_markdown() {
+        let html = r#"<h2>Title</h2><p>Some <strong>bold</strong>, <em> emphasized </em> and
+            <code>code</code> with <a href="https://example.com"><b>a</b> link</a>.</p>
+            <p><img src="a.png" alt="An image"></p><ul><li>One</li></ul>"#;
+
+        assert_eq!(
+            render(html, &TextOptions::markdown()),
+            "## Title\n\n\
+             Some **bold**, *emphasized* and `code` with [**a** link](https://example.com).\n\n\
+             ![An image](a.png)\n\n\
+             - One"
+        );
+    }
+
+    #[test]
+    fn test_render_text_options() {
+        let html = "<h3>Title</h3><ul><li>One</li></ul><blockquote>Quote</blockquote>";
+        let opts = TextOptions {
+            heading_style: HeadingStyle::Prefix,
+            bullet: '-',
+            blockquote_prefix: "| ".to_owned(),
+            ..Default::default()
+        };
+
+        assert_eq!(render(html, &opts), "### Title\n\n- One\n\n| Quote");
+    }
+}
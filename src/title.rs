@@ -0,0 +1,123 @@
+//! Extract the title of a document without extracting the content, e.g. for
+//! crawlers which need the titles of many pages.
+
+use crate::{
+    charset, compression,
+    error::ReadabilityError,
+    extractor::check_html,
+    scan::{scan, HeadScan},
+};
+
+/// The separators of the article title and the site name in the `title`
+/// element, e.g. `Article | Site`.
+const TITLE_SEPARATORS: [&str; 7] = [" | ", " - ", " – ", " — ", " :: ", " / ", " » "];
+
+/// Extract the title of a document from HTML bytes, and stop tokenizing as
+/// soon as the title is determined, which is usually at the end of the
+/// `head`. No DOM is built.
+///
+/// Unlike [`Readable::title`](crate::Readable::title), which is the text of
+/// the `title` element, the title is refined:
+///
+/// - the `og:title` or `twitter:title` meta tag is preferred,
+/// - the site name is stripped from the `title` element, e.g. `Site` from
+///   `Article title | Site`, unless the rest has fewer than three words, and
+/// - the first `h1` element is used if there is no title in the `head`.
+///
+/// The input is decoded and decompressed like by
+/// [`extract_from_bytes`](crate::extract_from_bytes).
+pub fn extract_title(bytes: &[u8]) -> Result<String, ReadabilityError> {
+    let bytes = compression::decompress(bytes)?;
+    check_html(&bytes)?;
+    let html = charset::decode(&bytes);
+
+    let scan = scan(&html, |scan| find_title(scan).is_some());
+
+    Ok(find_title(&scan).unwrap_or_default())
+}
+
+/// Find the title, or `None` if the document isn't scanned far enough yet.
+fn find_title(scan: &HeadScan) -> Option<String> {
+    if !scan.head_ended && !scan.finished {
+        return None;
+    }
+
+    let meta_title = scan
+        .meta_content(&["og:title", "twitter:title"])
+        .map(|content| normalize(&content))
+        .filter(|content| !content.is_empty());
+    if meta_title.is_some() {
+        return meta_title;
+    }
+
+    let title = scan
+        .title
+        .as_deref()
+        .map(normalize)
+        .filter(|title| !title.is_empty());
+    if let Some(title) = title {
+        return Some(refine_title(&title));
+    }
+
+    match scan.h1 {
+        Some(ref h1) => Some(normalize(h1)),
+        None => scan.finished.then(String::new),
+    }
+}
+
+/// Strip the site name before the first or after the last separator, if the
+/// remaining title has at least three words.
+fn refine_title(title: &str) -> String {
+    let is_long = |title: &str| title.split_whitespace().count() >= 3;
+
+    TITLE_SEPARATORS
+        .iter()
+        .find_map(|separator| {
+            let (before_last, _) = title.rsplit_once(separator)?;
+            let (_, after_first) = title.split_once(separator)?;
+
+            if is_long(before_last) {
+                Some(before_last)
+            } else {
+                is_long(after_first).then_some(after_first)
+            }
+        })
+        .unwrap_or(title)
+        .to_owned()
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_title() {
+        let title = |html: &str| extract_title(html.as_bytes()).unwrap();
+
+        assert_eq!(
+            title(
+                r#"<head><title>Title | Site</title><meta property="og:title" content=" Open  Graph "></head>"#
+            ),
+            "Open Graph"
+        );
+        assert_eq!(
+            title("<title>The article title | Site</title>"),
+            "The article title"
+        );
+        assert_eq!(
+            title("<title>Site - The article title</title>"),
+            "The article title"
+        );
+        assert_eq!(title("<title>Short | Site</title>"), "Short | Site");
+        assert_eq!(title("<body><h1>Heading</h1><p>Text</p></body>"), "Heading");
+        assert_eq!(title("<p>Text</p>"), "");
+        assert!(matches!(
+            extract_title(b" "),
+            Err(ReadabilityError::EmptyInput)
+        ));
+    }
+}
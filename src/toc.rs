@@ -0,0 +1,163 @@
+//! Build a table of contents from the headings of the content.
+
+use crate::dom::{self, Handle};
+use std::collections::HashSet;
+
+/// An entry in the table of contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TocEntry {
+    /// The heading level, i.e. `1` for `h1` to `6` for `h6`.
+    pub level: u8,
+    /// The text of the heading.
+    pub text: String,
+    /// The `id` of the heading, which can be used as fragment link.
+    pub anchor: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TocOptions {
+    /// Add an `id` derived from the heading text to headings without an `id`,
+    /// e.g. `id="getting-started"`. Note that the cleaner removes the `id` of
+    /// headings which are not linked from the content.
+    pub inject_ids: bool,
+    /// Headings below this level, e.g. `h4` for a maximal level of `3`, are
+    /// skipped.
+    pub max_level: u8,
+}
+
+impl Default for TocOptions {
+    fn default() -> Self {
+        Self {
+            inject_ids: false,
+            max_level: 6,
+        }
+    }
+}
+
+/// Build a table of contents from the headings in document order.
+///
+/// Headings without text are skipped. If [`TocOptions::inject_ids`] is set,
+/// the injected ids are unique within the content, and stable as long as the
+/// headings don't change.
+pub fn table_of_contents(handle: &Handle, options: &TocOptions) -> Vec<TocEntry> {
+    let mut ids = dom::descendants(handle)
+        .filter_map(|node| dom::get_attr(&node, "id"))
+        .collect::<HashSet<_>>();
+    let mut entries = vec![];
+
+    for node in dom::descendants(handle) {
+        let Some(level) = heading_level(&node).filter(|level| *level <= options.max_level) else {
+            continue;
+        };
+
+        let text = dom::text_content(&node)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if text.is_empty() {
+            continue;
+        }
+
+        let anchor = match dom::get_attr(&node, "id").filter(|id| !id.is_empty()) {
+            Some(id) => Some(id),
+            None if options.inject_ids => {
+                let id = unique_id(&slugify(&text), &ids);
+                dom::set_attr(&node, "id", &id);
+                ids.insert(id.clone());
+                Some(id)
+            }
+            None => None,
+        };
+
+        entries.push(TocEntry {
+            level,
+            text,
+            anchor,
+        });
+    }
+
+    entries
+}
+
+fn heading_level(handle: &Handle) -> Option<u8> {
+    let level = dom::tag_name(handle)?.strip_prefix('h')?.parse().ok()?;
+    (1..=6).contains(&level).then_some(level)
+}
+
+/// Convert the text to lowercase words separated by hyphens, e.g. "Getting
+/// started!" to `getting-started`.
+fn slugify(text: &str) -> String {
+    let slug = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() {
+        "section".to_owned()
+    } else {
+        slug
+    }
+}
+
+fn unique_id(slug: &str, ids: &HashSet<String>) -> String {
+    if !ids.contains(slug) {
+        return slug.to_owned();
+    }
+
+    (2..)
+        .map(|n| format!("{slug}-{n}"))
+        .find(|id| !ids.contains(id))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::RcDom;
+    use html5ever::{parse_document, tendril::TendrilSink};
+
+    #[test]
+    fn test_table_of_contents() {
+        let dom = parse_document(RcDom::default(), Default::default())
+            .from_utf8()
+            .read_from(
+                &mut r#"<article>
+                <h2>Getting  started</h2><p>Text</p>
+                <h3 id="install">Install</h3><p>Text</p>
+                <h3>Getting started</h3><p>Text</p>
+                <h4>Details</h4><h2> </h2>
+                </article>"#
+                    .as_bytes(),
+            )
+            .unwrap();
+        let article = dom::find_by_tag(&dom.document, "article")[0].clone();
+        let options = TocOptions {
+            inject_ids: true,
+            max_level: 3,
+        };
+
+        let toc = table_of_contents(&article, &options);
+
+        let entry = |level, text: &str, anchor: &str| TocEntry {
+            level,
+            text: text.to_owned(),
+            anchor: Some(anchor.to_owned()),
+        };
+        assert_eq!(
+            toc,
+            vec![
+                entry(2, "Getting started", "getting-started"),
+                entry(3, "Install", "install"),
+                entry(3, "Getting started", "getting-started-2"),
+            ]
+        );
+        assert_eq!(
+            dom::find_by_attr(&article, "id", Some("getting-started-2")).len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Tune the thresholds of the scorer for a labeled corpus, e.g. for the pages
+//! of a specific domain.
+
+use crate::{
+    corpus::{read_fixtures, text_similarity, Fixture},
+    error::ReadabilityError,
+    extractor::ExtractOptions,
+    scorer::ScorerOptions,
+};
+use std::path::Path;
+
+/// The values of the thresholds which are searched by [`tune_thresholds`].
+///
+/// Every combination of the values is evaluated, so the number of
+/// extractions is the product of the number of values times the number of
+/// pages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningGrid {
+    /// See [`ScorerOptions::positive_candidate_weight`].
+    pub positive_candidate_weights: Vec<f32>,
+    /// See [`ScorerOptions::negative_candidate_weight`].
+    pub negative_candidate_weights: Vec<f32>,
+    /// See [`ScorerOptions::min_candidate_length`].
+    pub min_candidate_lengths: Vec<usize>,
+    /// See [`ScorerOptions::hash_link_coefficient`].
+    pub hash_link_coefficients: Vec<f32>,
+    /// See [`CleanOptions::related_link_density`](crate::CleanOptions::related_link_density).
+    pub related_link_densities: Vec<f32>,
+}
+
+impl Default for TuningGrid {
+    /// The defaults and a lower and a higher value for each threshold.
+    fn default() -> Self {
+        Self {
+            positive_candidate_weights: vec![15.0, 25.0, 35.0],
+            negative_candidate_weights: vec![15.0, 25.0, 35.0],
+            min_candidate_lengths: vec![10, 20, 40],
+            hash_link_coefficients: vec![0.0, 0.3, 1.0],
+            related_link_densities: vec![0.3, 0.5, 0.7],
+        }
+    }
+}
+
+/// The result of [`tune_thresholds`].
+#[derive(Debug, Clone)]
+pub struct TuningReport<'a> {
+    /// The scorer options with the best thresholds.
+    pub scorer_options: ScorerOptions<'a>,
+    /// The mean similarity of the best thresholds.
+    pub mean_similarity: f32,
+    /// The mean similarity of the given options, for comparison.
+    pub baseline_similarity: f32,
+    /// The number of evaluated combinations.
+    pub evaluated: usize,
+}
+
+/// Search the thresholds of the grid for the best mean similarity of the
+/// extracted text to the expected text of a corpus, see
+/// [`run_corpus`](crate::run_corpus) for the layout of the corpus.
+///
+/// Pages without `expected.txt` are skipped, and failed extractions have a
+/// similarity of `0.0`. The other options are taken from the given options,
+/// which are kept if no combination is better.
+///
+/// Returns [`ReadabilityError::EmptyInput`] if no page has an expected text.
+pub fn tune_thresholds<'a>(
+    dir: impl AsRef<Path>,
+    options: &ExtractOptions<'a>,
+    grid: &TuningGrid,
+) -> Result<TuningReport<'a>, ReadabilityError> {
+    let fixtures = read_fixtures(dir)?
+        .into_iter()
+        .filter(|fixture| fixture.expected_text.is_some())
+        .collect::<Vec<_>>();
+
+    if fixtures.is_empty() {
+        return Err(ReadabilityError::EmptyInput);
+    }
+
+    let baseline_similarity = mean_similarity(&fixtures, options);
+    let mut report = TuningReport {
+        scorer_options: options.scorer_options.clone(),
+        mean_similarity: baseline_similarity,
+        baseline_similarity,
+        evaluated: 0,
+    };
+
+    for &positive_candidate_weight in grid.positive_candidate_weights.iter() {
+        for &negative_candidate_weight in grid.negative_candidate_weights.iter() {
+            for &min_candidate_length in grid.min_candidate_lengths.iter() {
+                for &hash_link_coefficient in grid.hash_link_coefficients.iter() {
+                    for &related_link_density in grid.related_link_densities.iter() {
+                        let mut options = options.clone();
+                        let scorer_options = &mut options.scorer_options;
+                        scorer_options.positive_candidate_weight = positive_candidate_weight;
+                        scorer_options.negative_candidate_weight = negative_candidate_weight;
+                        scorer_options.min_candidate_length = min_candidate_length;
+                        scorer_options.hash_link_coefficient = hash_link_coefficient;
+                        scorer_options.clean_options.related_link_density = related_link_density;
+
+                        let similarity = mean_similarity(&fixtures, &options);
+                        report.evaluated += 1;
+
+                        // Prefer the given options and earlier combinations on ties.
+                        if similarity > report.mean_similarity {
+                            report.mean_similarity = similarity;
+                            report.scorer_options = options.scorer_options;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn mean_similarity(fixtures: &[Fixture], options: &ExtractOptions) -> f32 {
+    let similarity = fixtures
+        .iter()
+        .map(|fixture| {
+            let expected_text = fixture.expected_text.as_deref().unwrap_or_default();
+
+            fixture.extract(options).map_or(0.0, |readable| {
+                text_similarity(&readable.text, expected_text)
+            })
+        })
+        .sum::<f32>();
+
+    similarity / fixtures.len() as f32
+}
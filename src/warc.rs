@@ -0,0 +1,349 @@
+//! Extract the content of the HTML records of WARC files, e.g. crawl archives
+//! of Common Crawl or `wget --warc-file`.
+
+use crate::{
+    error::ReadabilityError,
+    extractor::{extract_from_bytes, ExtractOptions, Readable},
+};
+use flate2::bufread::{GzDecoder, MultiGzDecoder};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+};
+use url::Url;
+
+/// An iterator over the HTML records of a WARC file, which yields the target
+/// url of each record along with its extracted content.
+///
+/// `response` records with an HTTP response and `resource` records are
+/// extracted if their content type is HTML, with the target url as base url.
+/// Other records are skipped.
+///
+/// ```no_run
+/// use readability::WarcReader;
+///
+/// let records = WarcReader::from_path("crawl.warc.gz", Default::default()).unwrap();
+///
+/// for (url, result) in records {
+///     match result {
+///         Ok(readable) => println!("{url}: {}", readable.title),
+///         Err(err) => eprintln!("{url}: {err}"),
+///     }
+/// }
+/// ```
+pub struct WarcReader<'a, R> {
+    reader: R,
+    options: ExtractOptions<'a>,
+    done: bool,
+}
+
+/// The WARC headers and the content block of a record.
+struct Record {
+    warc_type: String,
+    target_uri: String,
+    content_type: String,
+    block: Vec<u8>,
+}
+
+impl<'a> WarcReader<'a, Box<dyn BufRead>> {
+    /// Open a WARC file, which is decompressed if its extension is `.gz`.
+    pub fn from_path(
+        path: impl AsRef<Path>,
+        options: ExtractOptions<'a>,
+    ) -> Result<Self, ReadabilityError> {
+        let path = path.as_ref();
+        let file = BufReader::new(File::open(path)?);
+        let is_gzip = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("gz"));
+        let reader: Box<dyn BufRead> = if is_gzip {
+            // Each record is compressed as a separate gzip member.
+            Box::new(BufReader::new(MultiGzDecoder::new(file)))
+        } else {
+            Box::new(file)
+        };
+
+        Ok(Self::new(reader, options))
+    }
+}
+
+impl<'a, R: BufRead> WarcReader<'a, R> {
+    /// Read an uncompressed WARC file.
+    pub fn new(reader: R, options: ExtractOptions<'a>) -> Self {
+        Self {
+            reader,
+            options,
+            done: false,
+        }
+    }
+
+    /// Read the next record, or `None` at the end of the file.
+    fn read_record(&mut self) -> io::Result<Option<Record>> {
+        let mut line = String::new();
+
+        // Skip the blank lines between records.
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+
+        if !line.starts_with("WARC/") {
+            return Err(invalid_data("missing WARC version"));
+        }
+
+        let mut warc_type = String::new();
+        let mut target_uri = String::new();
+        let mut content_type = String::new();
+        let mut content_length = None;
+
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(invalid_data("unexpected end of WARC headers"));
+            }
+            let Some((name, value)) = line.split_once(':') else {
+                break;
+            };
+            let value = value.trim();
+
+            match name.trim().to_ascii_lowercase().as_str() {
+                "warc-type" => warc_type = value.to_ascii_lowercase(),
+                // WARC 1.0 wraps the uri in angle brackets.
+                "warc-target-uri" => {
+                    target_uri = value
+                        .trim_start_matches('<')
+                        .trim_end_matches('>')
+                        .to_owned()
+                }
+                "content-type" => content_type = value.to_ascii_lowercase(),
+                "content-length" => content_length = value.parse::<u64>().ok(),
+                _ => (),
+            }
+        }
+
+        let content_length =
+            content_length.ok_or_else(|| invalid_data("missing or invalid Content-Length"))?;
+        let mut block = vec![];
+        (&mut self.reader)
+            .take(content_length)
+            .read_to_end(&mut block)?;
+
+        if (block.len() as u64) < content_length {
+            return Err(invalid_data("truncated WARC record"));
+        }
+
+        Ok(Some(Record {
+            warc_type,
+            target_uri,
+            content_type,
+            block,
+        }))
+    }
+}
+
+impl<R: BufRead> Iterator for WarcReader<'_, R> {
+    type Item = (String, Result<Readable, ReadabilityError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let record = match self.read_record() {
+                Ok(Some(record)) => record,
+                Ok(None) => {
+                    self.done = true;
+                    return None;
+                }
+                Err(err) => {
+                    // The position of the next record is unknown.
+                    self.done = true;
+                    return Some((String::new(), Err(err.into())));
+                }
+            };
+
+            let html = match record.warc_type.as_str() {
+                "response" if record.content_type.starts_with("application/http") => {
+                    http_body(&record.block)
+                }
+                "resource" if is_html(&record.content_type) => Some(record.block),
+                _ => None,
+            };
+
+            if let Some(html) = html {
+                let result = Url::parse(&record.target_uri)
+                    .map_err(ReadabilityError::from)
+                    .and_then(|url| extract_from_bytes(&html, &url, self.options.clone()));
+
+                return Some((record.target_uri, result));
+            }
+        }
+
+        None
+    }
+}
+
+/// Get the decoded body of an HTTP response, or `None` if it's not HTML.
+fn http_body(response: &[u8]) -> Option<Vec<u8>> {
+    let (header_end, separator_len) = find(response, b"\r\n\r\n")
+        .map(|index| (index, 4))
+        .or_else(|| find(response, b"\n\n").map(|index| (index, 2)))?;
+    let headers = String::from_utf8_lossy(&response[..header_end]);
+    let body = &response[header_end + separator_len..];
+
+    let header = |name: &str| {
+        headers.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case(name)
+                .then(|| value.trim().to_ascii_lowercase())
+        })
+    };
+
+    if header("content-type").is_some_and(|content_type| !is_html(&content_type)) {
+        return None;
+    }
+
+    let body = match header("transfer-encoding") {
+        Some(encoding) if encoding.contains("chunked") => dechunk(body),
+        _ => body.to_vec(),
+    };
+
+    match header("content-encoding").as_deref() {
+        Some("gzip" | "x-gzip") => {
+            let mut decoded = vec![];
+            GzDecoder::new(body.as_slice())
+                .read_to_end(&mut decoded)
+                .ok()?;
+            Some(decoded)
+        }
+        _ => Some(body),
+    }
+}
+
+/// Decode a body with chunked transfer encoding, keeping a truncated last
+/// chunk.
+fn dechunk(mut body: &[u8]) -> Vec<u8> {
+    let mut decoded = vec![];
+
+    while let Some(line_end) = find(body, b"\r\n") {
+        let size = String::from_utf8_lossy(&body[..line_end]);
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let Ok(size) = usize::from_str_radix(size, 16) else {
+            break;
+        };
+        if size == 0 {
+            break;
+        }
+
+        let chunk = &body[line_end + 2..];
+        let size = size.min(chunk.len());
+        decoded.extend_from_slice(&chunk[..size]);
+        body = chunk[size..]
+            .strip_prefix(b"\r\n")
+            .unwrap_or(&chunk[size..]);
+    }
+
+    decoded
+}
+
+fn is_html(content_type: &str) -> bool {
+    content_type.contains("text/html") || content_type.contains("application/xhtml+xml")
+}
+
+fn find(bytes: &[u8], pattern: &[u8]) -> Option<usize> {
+    bytes
+        .windows(pattern.len())
+        .position(|window| window == pattern)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(warc_type: &str, uri: &str, content_type: &str, block: &str) -> String {
+        format!(
+            "WARC/1.0\r\nWARC-Type: {warc_type}\r\nWARC-Target-URI: {uri}\r\n\
+             Content-Type: {content_type}\r\nContent-Length: {}\r\n\r\n{block}\r\n\r\n",
+            block.len()
+        )
+    }
+
+    #[test]
+    fn test_warc_reader() {
+        let html = "<html><head><title>First</title></head><body>\
+                    <p>This is a paragraph, with more than 25 characters.</p></body></html>";
+        let chunked = format!(
+            "{:x}\r\n{}\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+            10,
+            &html[..10],
+            html.len() - 10,
+            &html[10..]
+        );
+        let warc = [
+            record(
+                "warcinfo",
+                "",
+                "application/warc-fields",
+                "software: wget\r\n",
+            ),
+            record(
+                "request",
+                "https://example.com/first",
+                "application/http; msgtype=request",
+                "GET /first HTTP/1.1\r\n\r\n",
+            ),
+            record(
+                "response",
+                "https://example.com/first",
+                "application/http; msgtype=response",
+                &format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\
+                     Transfer-Encoding: chunked\r\n\r\n{chunked}"
+                ),
+            ),
+            record(
+                "response",
+                "https://example.com/image.png",
+                "application/http; msgtype=response",
+                "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\n\r\nPNG",
+            ),
+            record(
+                "resource",
+                "<https://example.com/second>",
+                "text/html",
+                &html.replace("First", "Second"),
+            ),
+        ]
+        .concat();
+
+        let records = WarcReader::new(warc.as_bytes(), Default::default())
+            .map(|(url, result)| (url, result.unwrap().title))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            records,
+            [
+                ("https://example.com/first".to_owned(), "First".to_owned()),
+                ("https://example.com/second".to_owned(), "Second".to_owned())
+            ]
+        );
+
+        let mut records = WarcReader::new(
+            "WARC/1.0\r\nWARC-Type: resource\r\n".as_bytes(),
+            Default::default(),
+        );
+        assert!(matches!(
+            records.next(),
+            Some((_, Err(ReadabilityError::ReadWriteHtml(_))))
+        ));
+        assert!(records.next().is_none());
+    }
+}
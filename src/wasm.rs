@@ -0,0 +1,33 @@
+//! WebAssembly bindings via `wasm-bindgen`.
+//!
+//! Build with `cargo rustc --release --target wasm32-unknown-unknown --features wasm
+//! --crate-type cdylib`, and generate the JavaScript glue with `wasm-bindgen`.
+
+use crate::{
+    bindings::{BindingOptions, BindingReadable},
+    extractor,
+};
+use url::Url;
+use wasm_bindgen::prelude::*;
+
+/// Extract the readable content from an HTML string.
+///
+/// Returns an object with exactly the fields `title`, `content`, `text`,
+/// `byline`, `lang`, and `detected_language`. The `options` can be
+/// `undefined` or an object with `strict`, `errorOnNoContent`,
+/// `minCandidateLength`, and `maxCandidateParents`.
+#[wasm_bindgen]
+pub fn extract(html: &str, url: &str, options: JsValue) -> Result<JsValue, JsError> {
+    let url = Url::parse(url)?;
+    let options: BindingOptions = if options.is_undefined() || options.is_null() {
+        BindingOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)?
+    };
+
+    let readable = extractor::extract_from_str(html, &url, options.to_extract_options())?;
+
+    let readable = BindingReadable::from(&readable);
+
+    Ok(serde_wasm_bindgen::to_value(&readable)?)
+}
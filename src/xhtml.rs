@@ -0,0 +1,139 @@
+//! Serialize the DOM as XHTML, e.g. for EPUB or other formats which require
+//! well-formed XML.
+
+use crate::{
+    dom::{Handle, NodeData, RcDom},
+    html,
+};
+use html5ever::{
+    local_name, namespace_url, ns, parse_fragment, tendril::TendrilSink, Namespace, QualName,
+};
+
+/// The void elements, which are self-closed.
+const VOID_ELEMENTS: [&str; 13] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track",
+];
+
+/// Replace the `src` of an image by a path, e.g. of an image in an EPUB.
+pub(crate) type ReplaceImage<'a> = &'a mut dyn FnMut(&str) -> Option<String>;
+
+/// Convert an HTML fragment to XHTML, see [`write_xhtml`].
+pub(crate) fn to_xhtml(content: &str, mut replace_image: Option<ReplaceImage>) -> String {
+    let dom = parse_fragment(
+        RcDom::default(),
+        Default::default(),
+        QualName::new(None, ns!(html), local_name!("body")),
+        vec![],
+    )
+    .from_utf8()
+    .one(content.as_bytes());
+
+    let mut xhtml = String::with_capacity(content.len());
+
+    // The fragment is parsed into a `html` element.
+    if let Some(root) = dom.document.children.borrow().first() {
+        for child in root.children.borrow().iter() {
+            write_xhtml(child, &ns!(html), &mut xhtml, &mut replace_image);
+        }
+    }
+
+    xhtml
+}
+
+/// Write a node as XHTML, i.e. with self-closed void elements, escaped text,
+/// and the namespace of foreign elements.
+///
+/// If `replace_image` is set, the `src` of images is replaced by the returned
+/// path, and their `srcset` is dropped.
+pub(crate) fn write_xhtml(
+    handle: &Handle,
+    parent_ns: &Namespace,
+    xhtml: &mut String,
+    replace_image: &mut Option<ReplaceImage>,
+) {
+    match handle.data {
+        NodeData::Text { ref contents } => xhtml.push_str(&escape_xml(&contents.borrow())),
+        NodeData::Element {
+            ref name,
+            ref attrs,
+            ..
+        } => {
+            let tag_name: &str = &name.local;
+            let is_img = name.local == local_name!("img");
+
+            xhtml.push('<');
+            xhtml.push_str(tag_name);
+            if name.ns != *parent_ns {
+                xhtml.push_str(&format!(r#" xmlns="{}""#, escape_xml(&name.ns)));
+            }
+            for attr in attrs.borrow().iter() {
+                let attr_name: &str = &attr.name.local;
+
+                if !is_xml_name(attr_name)
+                    || (is_img && attr_name == "srcset" && replace_image.is_some())
+                {
+                    continue;
+                }
+
+                let value = match (attr_name, replace_image.as_mut()) {
+                    ("src", Some(replace_image)) if is_img => {
+                        replace_image(&attr.value).unwrap_or_else(|| attr.value.to_string())
+                    }
+                    _ => attr.value.to_string(),
+                };
+
+                xhtml.push_str(&format!(r#" {attr_name}="{}""#, escape_xml(&value)));
+            }
+
+            if VOID_ELEMENTS.contains(&tag_name) {
+                xhtml.push_str("/>");
+            } else {
+                xhtml.push('>');
+                for child in handle.children.borrow().iter() {
+                    write_xhtml(child, &name.ns, xhtml, replace_image);
+                }
+                xhtml.push_str(&format!("</{tag_name}>"));
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Check if an attribute name is a valid XML name without a namespace prefix.
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    // XHTML without a DTD doesn't know the named `&nbsp;` entity.
+    html::escape(text).replace("&nbsp;", "\u{a0}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_xhtml() {
+        let xhtml = to_xhtml(
+            r#"<p>A&nbsp;&amp;<br>B</p><img src="a.png" srcset="b.png 2x"><svg><path d="M0"></path></svg>"#,
+            Some(&mut |src| Some(format!("images/{src}"))),
+        );
+
+        assert_eq!(
+            xhtml,
+            "<p>A\u{a0}&amp;<br/>B</p><img src=\"images/a.png\"/><svg xmlns=\"http://www.w3.org/2000/svg\"><path d=\"M0\"></path></svg>"
+        );
+
+        assert_eq!(
+            to_xhtml(r#"<img src="a.png" srcset="b.png 2x"><hr>"#, None),
+            r#"<img src="a.png" srcset="b.png 2x"/><hr/>"#
+        );
+    }
+}
@@ -1,5 +1,11 @@
 use log::LevelFilter;
-use readability::{extract, ExtractOptions, ParseOptions};
+use readability::{
+    extract, extract_from_bytes, extract_from_str, extract_owned, extract_text, extract_with_node,
+    run_corpus, AuthorBio, CandidateScore, CleanDecision, CleanOptions, ExtractOptions, Extractor,
+    FallbackPolicy, NodeData, NodeFeatures, NodeView, OutputSet, ParseOptions, Preset,
+    ReadabilityError, Readable, ScoredDocument, ScorerOptions, Severity, TextOptions,
+    TruncationReason, UrlKind,
+};
 use regex::Regex;
 use rstest::rstest;
 use std::{
@@ -51,6 +57,7 @@ fn test_extract_with_options(
     let mut title = String::new();
     file.read_to_string(&mut title).unwrap();
     assert_eq!(product.title, title);
+    assert!(product.confidence > 0.0 && product.confidence < 1.0);
 }
 
 #[rstest]
@@ -96,7 +103,30 @@ fn test_extract_with_scorer(#[case] test_name: &str, #[case] url: &str) {
         negative_candidates: &Regex::new("combx|contact|foot|footer|footnote|masthead|media|meta|outbrain|promo|related|scroll|shoutbox|sidebar|sponsor|shopping|tags|tool|widget|form|textfield|uiScale|hidden").unwrap(),
         positive_candidates: &Regex::new("article|body|content|entry|hentry|main|page|pagination|post|blog|story").unwrap(),
         ..Default::default()
-    }};
+    }, ..Default::default()};
+
+    test_extract_with_options(
+        options,
+        url,
+        &input_path,
+        &expected_content_path,
+        &expected_text_path,
+        &expected_title_path,
+    );
+}
+
+#[rstest]
+#[case::comments("comments", "https://example.com")]
+#[case::comment("comment", "https://example.com")]
+fn test_extract_with_preset(#[case] test_name: &str, #[case] url: &str) {
+    init_logger();
+
+    let data_path = Path::new("./data").join(test_name);
+    let input_path = data_path.join("input.html");
+    let expected_content_path = data_path.join("expected_with_scorer.html");
+    let expected_text_path = data_path.join("expected_with_scorer.txt");
+    let expected_title_path = data_path.join("expected_title.txt");
+    let options = ExtractOptions::preset(Preset::Forum);
 
     test_extract_with_options(
         options,
@@ -108,6 +138,41 @@ fn test_extract_with_scorer(#[case] test_name: &str, #[case] url: &str) {
     );
 }
 
+#[rstest]
+#[case::default(ExtractOptions::default(), "expected")]
+#[case::news(ExtractOptions::preset(Preset::News), "expected")]
+#[case::docs(ExtractOptions::preset(Preset::Docs), "expected")]
+#[case::legacy(
+    ExtractOptions {
+        scorer_options: ScorerOptions {
+            legacy_candidate_matching: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    },
+    "expected_legacy"
+)]
+fn test_extract_word_start(#[case] options: ExtractOptions<'static>, #[case] expected: &str) {
+    init_logger();
+
+    // The negative candidate `media` only matches `multimedia-block` with
+    // legacy matching, also if the negative candidates are composed by a preset.
+    let data_path = Path::new("./data").join("multimedia");
+    let input_path = data_path.join("input.html");
+    let expected_content_path = data_path.join(format!("{expected}.html"));
+    let expected_text_path = data_path.join(format!("{expected}.txt"));
+    let expected_title_path = data_path.join("expected_title.txt");
+
+    test_extract_with_options(
+        options,
+        "https://example.com",
+        &input_path,
+        &expected_content_path,
+        &expected_text_path,
+        &expected_title_path,
+    );
+}
+
 #[test]
 fn test_extract_malformed() {
     let html = r#"
@@ -122,7 +187,10 @@ fn test_extract_malformed() {
     let url = Url::parse("https://example.com").unwrap();
     let mut input = Cursor::new(html);
     let options = ExtractOptions {
-        parse_options: ParseOptions { strict: true },
+        parse_options: ParseOptions {
+            strict: true,
+            ..Default::default()
+        },
         ..Default::default()
     };
 
@@ -130,18 +198,519 @@ fn test_extract_malformed() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_extract_malformed_diagnostics() {
+    let html = r#"<!DOCTYPE html>
+        <html>
+            <head><title>Malformed HTML</title></head>
+            <body>
+                <p>Paragraph with &#x0; <b>bold</p>
+            </body>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+    let options = ExtractOptions {
+        parse_options: ParseOptions {
+            strict: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let result = extract(&mut Cursor::new(html), &url, options);
+    let Err(ReadabilityError::ParseHtml(errors)) = result else {
+        panic!("Unexpected result: {result:?}");
+    };
+    assert!(errors.iter().any(|error| error.severity == Severity::Minor));
+    assert!(errors.iter().any(|error| error.severity == Severity::Major));
+    assert_eq!(errors[0].line, Some(5));
+
+    let options = ExtractOptions {
+        parse_options: ParseOptions {
+            strict: true,
+            min_severity: Severity::Major,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract(&mut Cursor::new(html), &url, options);
+    let Err(ReadabilityError::ParseHtml(errors)) = result else {
+        panic!("Unexpected result: {result:?}");
+    };
+    assert!(errors.iter().all(|error| error.severity == Severity::Major));
+
+    let options = ExtractOptions {
+        parse_options: ParseOptions {
+            strict: true,
+            max_errors: errors.len() + 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract(&mut Cursor::new(html), &url, options);
+    assert!(result.is_ok());
+
+    let options = ExtractOptions {
+        parse_options: ParseOptions {
+            strict: true,
+            min_severity: Severity::Major,
+            allowed_errors: errors
+                .iter()
+                .map(|error| error.message.to_string())
+                .collect(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract(&mut Cursor::new(html), &url, options);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_extract_exact_errors() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Malformed HTML</title></head>
+            <body>
+                <p>Paragraph with <b>bold</p>
+            </body>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+    let options = ExtractOptions {
+        parse_options: ParseOptions {
+            strict: true,
+            exact_errors: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let result = extract(&mut Cursor::new(html), &url, options.clone());
+    let Err(ReadabilityError::ParseHtml(errors)) = result else {
+        panic!("Unexpected result: {result:?}");
+    };
+    assert!(errors
+        .iter()
+        .any(|error| error.message.contains("while closing")));
+    // A bad character in an attribute is minor, but a bogus end tag is read as
+    // a comment.
+    let html = r#"<!DOCTYPE html><html><head><title>Title</title></head>
+        <body><p class="a"b>Paragraph</ p></p></body></html>"#;
+    let result = extract(&mut Cursor::new(html), &url, options);
+    let Err(ReadabilityError::ParseHtml(errors)) = result else {
+        panic!("Unexpected result: {result:?}");
+    };
+    let severity = |state: &str| {
+        errors
+            .iter()
+            .find(|error| error.message.starts_with("Saw ") && error.message.contains(state))
+            .map(|error| error.severity)
+    };
+    assert_eq!(severity("AfterAttributeValueQuoted"), Some(Severity::Minor));
+    assert_eq!(severity("EndTagOpen"), Some(Severity::Major));
+}
+
 #[test]
 fn test_extract_empty() {
     let html: &str = "";
     let url = Url::parse("https://example.com").unwrap();
     let mut input = Cursor::new(html);
     let options = ExtractOptions {
-        parse_options: ParseOptions { strict: true },
+        parse_options: ParseOptions {
+            strict: true,
+            ..Default::default()
+        },
         ..Default::default()
     };
 
     let result = extract(&mut input, &url, options);
-    assert!(result.is_err());
+    assert!(matches!(result, Err(ReadabilityError::EmptyInput)));
+}
+
+#[test]
+fn test_extract_not_html() {
+    let url = Url::parse("https://example.com").unwrap();
+
+    let mut input = Cursor::new("Just some plain text.");
+    let result = extract(&mut input, &url, Default::default());
+    assert!(matches!(result, Err(ReadabilityError::NotHtml)));
+
+    let mut input = Cursor::new(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR<".to_vec());
+    let result = extract(&mut input, &url, Default::default());
+    assert!(matches!(result, Err(ReadabilityError::NotHtml)));
+}
+
+#[test]
+fn test_extract_stray_nul_byte() {
+    let url = Url::parse("https://example.com").unwrap();
+    let html = format!(
+        "<html><head><title>Test Title</title></head><body>{}\
+        <p>This is a test paragraph with a stray \0 byte and more than 25 characters.</p>\
+        </body></html>",
+        "<!-- padding -->".repeat(100)
+    );
+
+    let result = extract(&mut Cursor::new(html), &url, Default::default()).unwrap();
+    assert!(result.text.contains("This is a test paragraph"));
+}
+
+#[test]
+fn test_extract_no_content() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>No Content</title></head>
+            <body><h1>Short</h1><p>Too short.</p></body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let mut input = Cursor::new(html);
+    let result = extract(&mut input, &url, Default::default()).unwrap();
+    assert_eq!(result.confidence, 0.0);
+    assert!(result.content.contains("<p>Too short.</p>"));
+    assert!(!result.content.contains("<title>"));
+
+    let mut input = Cursor::new(html);
+    let options = ExtractOptions {
+        fallback_policy: FallbackPolicy::Error,
+        ..Default::default()
+    };
+    let result = extract(&mut input, &url, options);
+    assert!(matches!(result, Err(ReadabilityError::NoContent)));
+
+    let mut input = Cursor::new(html);
+    let options = ExtractOptions {
+        fallback_policy: FallbackPolicy::Empty,
+        ..Default::default()
+    };
+    let result = extract(&mut input, &url, options).unwrap();
+    assert_eq!(result.title, "No Content");
+    assert_eq!(result.content, "");
+    assert_eq!(result.text, "");
+}
+
+#[test]
+fn test_extract_line_breaks() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Poem</title></head>
+            <body><article>
+                <p>This is the introduction of the poem, with a comma, and more text.</p>
+                <p>Roses are red,<br>violets are blue,<br>sugar is sweet,<br>and so are you.</p>
+                <div>Jane Doe<br>Main Street 1<br><br>A paragraph after a double break.</div>
+            </article></body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let mut input = Cursor::new(html);
+    let result = extract(&mut input, &url, Default::default()).unwrap();
+    assert!(result.content.contains(
+        "<p>Roses are red,<br>violets are blue,<br>sugar is sweet,<br>and so are you.</p>"
+    ));
+    assert!(result
+        .content
+        .contains("<p>A paragraph after a double break.</p>"));
+    assert!(result
+        .text
+        .contains("Roses are red,\nviolets are blue,\nsugar is sweet,\nand so are you."));
+    assert!(result.text.contains("Jane Doe\nMain Street 1\n"));
+
+    let mut input = Cursor::new(html);
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            legacy_line_breaks: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract(&mut input, &url, options).unwrap();
+    assert!(result.content.contains("<p>sugar is sweet,</p>"));
+    assert!(result.text.contains("Roses are red,violets are blue,"));
+}
+
+#[test]
+fn test_extract_blockquote() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Quotes</title></head>
+            <body><article>
+                <p>This is the first paragraph, with a comma, and more commas, and text.</p>
+                <div class="media-quote">
+                    <blockquote cite="/speeches/1">
+                        <p>Quoted words, with a comma, and more words to quote.</p>
+                        <footer><cite>Jane Doe</cite></footer>
+                    </blockquote>
+                </div>
+                <p>This is the second paragraph, with a comma, and more commas, and text.</p>
+            </article></body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com/articles/1").unwrap();
+
+    let mut input = Cursor::new(html);
+    let options = ExtractOptions {
+        text_options: Some(TextOptions::default()),
+        ..Default::default()
+    };
+    let result = extract(&mut input, &url, options).unwrap();
+    assert!(result
+        .content
+        .contains(r#"<blockquote cite="https://example.com/speeches/1">"#));
+    assert!(result.content.contains("<cite>Jane Doe</cite>"));
+    assert!(result
+        .text
+        .contains("> Quoted words, with a comma, and more words to quote."));
+}
+
+#[test]
+fn test_extract_definition_list() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Glossary</title></head>
+            <body>
+                <div class="intro"><p>A short introduction to the glossary, with a comma.</p></div>
+                <div class="glossary"><dl>
+                    <dt>Candidate</dt>
+                    <dd>An element whose text is scored, with a comma, and more commas.</dd>
+                    <dt>Content score</dt>
+                    <dd>The score of a candidate, based on commas, length, and class names.</dd>
+                    <dt>Link density</dt>
+                    <dd>The share of link text, which reduces the score, of a candidate.</dd>
+                </dl></div>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let mut input = Cursor::new(html);
+    let result = extract(&mut input, &url, Default::default()).unwrap();
+    assert!(result.content.starts_with("<dl>"));
+    assert!(!result.content.contains("introduction"));
+}
+
+#[test]
+fn test_extract_custom_elements() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Web Components</title></head>
+            <body>
+                <div class="teaser"><p>A teaser of another article, with a comma.</p></div>
+                <article-content>
+                    <article-text>The first paragraph of the article, with a comma, and more commas.</article-text>
+                    <article-text>The second paragraph of the article, with a comma, and more commas.</article-text>
+                </article-content>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let top_candidate_tag = |scorer_options| {
+        let options = ExtractOptions {
+            scorer_options,
+            ..Default::default()
+        };
+        let document = ScoredDocument::from_str(html, &url, options).unwrap();
+        let tag = document.candidates().next().unwrap().tag.to_owned();
+        tag
+    };
+
+    assert_ne!(top_candidate_tag(Default::default()), "article-content");
+    assert_eq!(
+        top_candidate_tag(ScorerOptions {
+            custom_elements_as_divs: true,
+            ..Default::default()
+        }),
+        "article-content"
+    );
+
+    let mut input = Cursor::new(html);
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            custom_elements_as_divs: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract(&mut input, &url, options).unwrap();
+    assert!(result
+        .content
+        .contains("<article-text>The second paragraph"));
+}
+
+#[cfg(feature = "tuning")]
+#[test]
+fn test_tune_thresholds() {
+    use readability::{tune_thresholds, TuningGrid};
+
+    let grid = TuningGrid {
+        positive_candidate_weights: vec![25.0],
+        negative_candidate_weights: vec![0.0, 25.0],
+        min_candidate_lengths: vec![20],
+        hash_link_coefficients: vec![0.3],
+        related_link_densities: vec![0.5, 0.7],
+    };
+    let report = tune_thresholds("./data", &ExtractOptions::default(), &grid).unwrap();
+
+    assert_eq!(report.evaluated, 4);
+    assert!(report.mean_similarity >= report.baseline_similarity);
+    assert!(report.mean_similarity <= 1.0);
+
+    let result = tune_thresholds("./data/url", &ExtractOptions::default(), &grid);
+    assert!(matches!(result, Err(ReadabilityError::EmptyInput)));
+}
+
+#[test]
+fn test_run_corpus() {
+    let report = run_corpus("./data", &ExtractOptions::default()).unwrap();
+
+    let names = report
+        .pages
+        .iter()
+        .map(|page| page.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["comment", "comments", "multimedia", "url"]);
+    assert_eq!(report.pages[3].similarity, Some(1.0));
+    assert_eq!(report.pages[3].title_matches, Some(true));
+    assert!(report.to_string().starts_with("name\ttitle_matches"));
+    assert!(report.diff(&report).is_empty());
+
+    let mut baseline = report.clone();
+    baseline.pages[3].similarity = Some(1.5);
+    let regressions = report.regressions(&baseline, 0.1);
+    assert_eq!(regressions.len(), 1);
+    assert_eq!(regressions[0].name, "url");
+    assert!(report.regressions(&baseline, 0.6).is_empty());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_run_readability_js_corpus() {
+    use readability::{run_readability_js_corpus, ReadabilityJsOptions};
+
+    let options = ReadabilityJsOptions::default().to_extract_options();
+    let report = run_readability_js_corpus("./data/readability-js", &options).unwrap();
+
+    assert_eq!(report.pages.len(), 1);
+    assert_eq!(report.pages[0].name, "basic");
+    assert_eq!(report.pages[0].title_matches, Some(true));
+    assert_eq!(report.pages[0].similarity, Some(1.0));
+}
+
+#[test]
+fn test_extract_url_rewriter() {
+    let html = r#"
+        <html><body><article>
+            <p>This is a test paragraph with an <img src="/a.png"> image, and a comma.</p>
+            <p>This is a test paragraph with a <a href="https://ads.example.com/">link</a>,
+            and <a href="/about">another link</a>.</p>
+        </article></body></html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+    let rewrite = |url: Url, kind: UrlKind| match kind {
+        UrlKind::Image => Url::parse(&format!("https://proxy.example.com/?url={url}")).ok(),
+        UrlKind::Link => (url.host_str() != Some("ads.example.com")).then_some(url),
+    };
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            clean_options: CleanOptions {
+                url_rewriter: Some(&rewrite),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let readable = extract(&mut html.as_bytes(), &url, options).unwrap();
+    assert!(readable
+        .content
+        .contains(r#"<img src="https://proxy.example.com/?url=https://example.com/a.png">"#));
+    assert!(readable.content.contains("a <a>link</a>,"));
+    assert!(readable
+        .content
+        .contains(r#"<a href="https://example.com/about">another link</a>"#));
+}
+
+#[test]
+fn test_extract_upgrade_insecure() {
+    let html = r#"
+        <html><body><article>
+            <p>This is a test paragraph with an <img src="//cdn.example.com/a.png"> image.</p>
+            <p>This is a test paragraph with an <img src="http://cdn.example.com/b.png"> image,
+            and a <a href="//example.org/">link</a>.</p>
+        </article></body></html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let readable = extract(&mut html.as_bytes(), &url, Default::default()).unwrap();
+    assert!(readable
+        .content
+        .contains(r#"<img src="https://cdn.example.com/a.png">"#));
+    assert!(readable
+        .content
+        .contains(r#"<img src="http://cdn.example.com/b.png">"#));
+    assert!(readable
+        .content
+        .contains(r#"<a href="https://example.org/">link</a>"#));
+
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            clean_options: CleanOptions {
+                upgrade_insecure: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let readable = extract(&mut html.as_bytes(), &url, options).unwrap();
+    assert!(readable
+        .content
+        .contains(r#"<img src="https://cdn.example.com/b.png">"#));
+}
+
+#[test]
+fn test_extract_min_content_length() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <p>This is a test paragraph with more than 25 characters.</p>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    for outputs in [OutputSet::ALL, OutputSet::HTML] {
+        let mut input = Cursor::new(html);
+        let options = ExtractOptions {
+            min_content_length: 100,
+            outputs,
+            ..Default::default()
+        };
+        let result = extract(&mut input, &url, options);
+        assert!(matches!(
+            result,
+            Err(ReadabilityError::ContentTooShort {
+                actual: 54,
+                required: 100
+            })
+        ));
+    }
+
+    let mut input = Cursor::new(html);
+    let options = ExtractOptions {
+        min_content_length: 54,
+        ..Default::default()
+    };
+    assert!(extract(&mut input, &url, options).is_ok());
 }
 
 #[test]
@@ -171,6 +740,889 @@ fn test_extract_basic() {
     );
 }
 
+#[test]
+fn test_extract_byline() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <article>
+                    <p class="byline">By  Jane Doe</p>
+                    <p>This is a test paragraph with more than 25 characters.</p>
+                    <p>This is another test paragraph with more than 25 characters.</p>
+                </article>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert_eq!(result.byline.as_deref(), Some("By Jane Doe"));
+    assert!(!result.content.contains("Jane Doe"));
+}
+
+#[test]
+fn test_extract_fragment_links() {
+    let html = r##"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <article>
+                    <p>This is a test paragraph with a footnote<a href="#note-1">1</a>.</p>
+                    <p id="note-1" class="note">This is the footnote with more than 25 characters.</p>
+                    <p id="other">This is another paragraph with more than 25 characters.</p>
+                </article>
+            </body>
+        </html>
+        "##;
+    let url = Url::parse("https://example.com/article").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert!(result.content.contains(r##"<a href="#note-1">1</a>"##));
+    assert!(result.content.contains(r#"<p id="note-1">"#));
+    assert!(!result.content.contains(r#"id="other""#));
+
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            clean_options: CleanOptions {
+                keep_fragment_links: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract_from_str(html, &url, options).unwrap();
+    assert!(result
+        .content
+        .contains(r#"<a href="https://example.com/article#note-1">1</a>"#));
+    assert!(!result.content.contains(r#"id="note-1""#));
+}
+
+#[test]
+fn test_extract_svg_and_math() {
+    let html = r##"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <article>
+                    <p>This is a test paragraph with more than 25 characters.</p>
+                    <div><svg viewBox="0 0 10 10"><style>.bar { fill: red; }</style>
+                        <rect class="bar" width="5" height="10"></rect></svg></div>
+                    <p>This is a paragraph with a formula
+                        <math><mi class="var">x</mi><mo>=</mo><mn>1</mn></math>.</p>
+                </article>
+            </body>
+        </html>
+        "##;
+    let url = Url::parse("https://example.com/article").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert!(result
+        .content
+        .contains(r#"<rect class="bar" width="5" height="10"></rect>"#));
+    assert!(result
+        .content
+        .contains("<style>.bar { fill: red; }</style>"));
+    assert!(result.content.contains(r#"<mi class="var">x</mi>"#));
+
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            clean_options: CleanOptions {
+                remove_svg_and_math: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract_from_str(html, &url, options).unwrap();
+    assert!(!result.content.contains("<svg"));
+    assert!(!result.content.contains("<math"));
+}
+
+#[test]
+fn test_extract_lang() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html lang="de">
+            <head><title>Test Title</title></head>
+            <body>
+                <p>This is a longer test paragraph, which is written in English although
+                the document declares German as its language.</p>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert_eq!(result.lang.as_deref(), Some("de"));
+
+    #[cfg(feature = "lang-detect")]
+    assert_eq!(result.detected_language.as_deref(), Some("eng"));
+    #[cfg(not(feature = "lang-detect"))]
+    assert_eq!(result.detected_language, None);
+}
+
+#[test]
+fn test_extract_amp() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html amp>
+            <head>
+                <title>Test Title</title>
+                <link rel="canonical" href="/article">
+                <link rel="amphtml" href="https://example.com/amp/article">
+            </head>
+            <body>
+                <p>This is a test paragraph with more than 25 characters.
+                <amp-img src="/cat.jpg" width="640" height="480" layout="responsive">
+                    <noscript><img src="/cat.jpg"></noscript>
+                </amp-img></p>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com/amp/article").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert_eq!(
+        result.amp_url.as_deref(),
+        Some("https://example.com/amp/article")
+    );
+    assert_eq!(
+        result.canonical_url.as_deref(),
+        Some("https://example.com/article")
+    );
+    assert!(result
+        .content
+        .contains(r#"<img src="https://example.com/cat.jpg" width="640" height="480">"#));
+    assert!(!result.content.contains("amp-img"));
+}
+
+#[test]
+fn test_extract_author_bio() {
+    let bio = "Jane Doe is a science reporter who writes about physics and space.";
+    let html = format!(
+        r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <article>
+                    <p>This is a test paragraph with more than 25 characters.</p>
+                    <p>This is another paragraph, with more than 25 characters.</p>
+                </article>
+                <div class="author-box"><img src="/jane.png"><p>{bio}</p></div>
+            </body>
+        </html>
+        "#
+    );
+    let url = Url::parse("https://example.com").unwrap();
+
+    let options = ExtractOptions {
+        author_bio: AuthorBio::Extract,
+        ..Default::default()
+    };
+    let result = extract_from_str(&html, &url, options).unwrap();
+    assert_eq!(result.author_bio.as_deref(), Some(bio));
+    assert!(!result.text.contains(bio));
+
+    let options = ExtractOptions {
+        author_bio: AuthorBio::Preserve,
+        ..Default::default()
+    };
+    let result = extract_from_str(&html, &url, options).unwrap();
+    assert_eq!(result.author_bio.as_deref(), Some(bio));
+    assert!(result.text.ends_with(bio));
+    assert!(result
+        .content
+        .contains(r#"<img src="https://example.com/jane.png">"#));
+}
+
+#[test]
+fn test_extract_microformats() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <article class="h-entry">
+                    <h1 class="p-name">The title</h1>
+                    <a class="p-author h-card" href="/">Jane Doe</a>
+                    <time class="dt-published" datetime="2024-05-01">May 1</time>
+                    <div class="e-content">
+                        <p>This is a short paragraph, with a comma.</p>
+                        <p>This is another paragraph, with a comma, which is long enough to
+                        reach the minimal length of content hints.</p>
+                    </div>
+                    <div class="sidebar-entry">
+                        <p>This is a sidebar, with a lot of commas, commas, commas, commas,
+                        commas, commas, commas, commas, commas, commas, and more commas.</p>
+                    </div>
+                </article>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert_eq!(result.metadata.title.as_deref(), Some("The title"));
+    assert_eq!(result.metadata.author.as_deref(), Some("Jane Doe"));
+    assert_eq!(
+        result.metadata.published_time.as_deref(),
+        Some("2024-05-01")
+    );
+    assert!(result.text.starts_with("This is a short paragraph"));
+    assert!(!result.text.contains("sidebar"));
+}
+
+#[test]
+fn test_extract_microdata() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <article itemscope itemtype="https://schema.org/BlogPosting">
+                    <h1 itemprop="headline">The title</h1>
+                    <span itemprop="author" itemscope itemtype="https://schema.org/Person">
+                        <span itemprop="name">Jane Doe</span>
+                    </span>
+                    <meta itemprop="datePublished" content="2024-05-01">
+                    <div itemprop="articleBody">
+                        <p>This is a short paragraph, with a comma.</p>
+                        <p>This is another paragraph, with a comma, which is long enough to
+                        reach the minimal length of content hints.</p>
+                    </div>
+                    <div class="related">
+                        <p>This is a teaser, with a lot of commas, commas, commas, commas,
+                        commas, commas, commas, commas, commas, commas, and more commas.</p>
+                    </div>
+                </article>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert_eq!(result.metadata.title.as_deref(), Some("The title"));
+    assert_eq!(result.metadata.author.as_deref(), Some("Jane Doe"));
+    assert_eq!(
+        result.metadata.published_time.as_deref(),
+        Some("2024-05-01")
+    );
+    assert!(result.text.starts_with("This is a short paragraph"));
+    assert!(!result.text.contains("teaser"));
+}
+
+#[test]
+fn test_extract_paywall() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <article>
+                    <p>This is a test paragraph with more than 25 characters.</p>
+                    <p>Subscribe to continue reading.</p>
+                </article>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert!(result.truncated);
+    assert_eq!(
+        result.truncation_reason,
+        Some(TruncationReason::TruncationMarker)
+    );
+
+    let html = html.replace("Subscribe to continue reading.", "The end.");
+    let result = extract_from_str(&html, &url, Default::default()).unwrap();
+    assert!(!result.truncated);
+    assert_eq!(result.truncation_reason, None);
+}
+
+#[test]
+fn test_extract_iframe_srcdoc() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <iframe srcdoc="<p>This is a test paragraph with more than 25 characters.</p>
+                    <script>alert(1)</script>"></iframe>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            inline_iframe_srcdoc: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let result = extract_from_str(html, &url, options).unwrap();
+    assert_eq!(
+        result.text,
+        "This is a test paragraph with more than 25 characters."
+    );
+    assert!(!result.content.contains("iframe"));
+    assert!(!result.content.contains("script"));
+}
+
+#[test]
+fn test_extract_shadow_roots() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <article-body>
+                    <template shadowrootmode="closed">
+                        <p>This is a test paragraph with more than 25 characters.</p>
+                    </template>
+                </article-body>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert!(!result.text.contains("test paragraph"));
+
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            hoist_shadow_roots: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract_from_str(html, &url, options).unwrap();
+    assert_eq!(
+        result.text,
+        "This is a test paragraph with more than 25 characters."
+    );
+    assert!(!result.content.contains("template"));
+}
+
+#[test]
+fn test_extract_overlays() {
+    let consent = "We and our partners use cookies to store and access information on \
+        your device, to personalise content and ads, and to analyse our traffic, \
+        which requires your consent.";
+    let html = format!(
+        r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <div id="onetrust-consent-sdk"><p>{consent}</p><p>{consent}</p></div>
+                <div data-cmp-host="true"><p>{consent}</p><p>{consent}</p></div>
+                <div style="position: fixed; inset: 0"><p>{consent}</p><p>{consent}</p></div>
+                <div role="dialog" aria-modal="true"><p>{consent}</p><p>{consent}</p></div>
+                <div><p>This is a test paragraph with more than 25 characters.</p></div>
+            </body>
+        </html>
+        "#
+    );
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(&html, &url, Default::default()).unwrap();
+    assert_eq!(
+        result.text,
+        "This is a test paragraph with more than 25 characters."
+    );
+
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            remove_overlays: false,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract_from_str(&html, &url, options).unwrap();
+    assert!(result.text.contains("cookies"));
+}
+
+#[test]
+fn test_extract_ad_slots() {
+    let ad = "Discover the best offers of the season, with discounts of up to fifty \
+        percent, free shipping, and easy returns on all orders, only this week.";
+    let html = format!(
+        r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <div>
+                    <p>This is a test paragraph with more than 25 characters.</p>
+                    <div class="x7f2a" data-google-query-id="CK3x"><p>{ad}</p></div>
+                    <div id="div-gpt-ad-1234-0"><p>{ad}</p></div>
+                    <div aria-label="Advertisement"><p>{ad}</p></div>
+                    <ins class="adsbygoogle" data-ad-client="ca-pub-1">{ad}</ins>
+                </div>
+            </body>
+        </html>
+        "#
+    );
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(&html, &url, Default::default()).unwrap();
+    assert_eq!(
+        result.text,
+        "This is a test paragraph with more than 25 characters."
+    );
+
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            remove_ad_slots: false,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract_from_str(&html, &url, options).unwrap();
+    assert!(result.text.contains("best offers"));
+}
+
+#[test]
+fn test_extract_app_shell() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <noscript>You need to enable JavaScript to run this app.</noscript>
+                <div id="root"></div>
+                <script src="/static/js/main.js"></script>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    assert!(extract_from_str(html, &url, Default::default()).is_ok());
+
+    let options = ExtractOptions {
+        detect_app_shell: true,
+        ..Default::default()
+    };
+    let result = extract_from_str(html, &url, options);
+    assert!(matches!(result, Err(ReadabilityError::RequiresJavaScript)));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_extract_embedded_article() {
+    let paragraph = "This is a paragraph of the full article, which is rendered by JavaScript.";
+    let html = format!(
+        r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <div id="__next"><p>This is a teaser with more than 25 characters.</p></div>
+                <script id="__NEXT_DATA__" type="application/json">
+                    {{"props": {{"article": {{"articleBody": "{paragraph}\n{paragraph}\n{paragraph}"}}}}}}
+                </script>
+            </body>
+        </html>
+        "#
+    );
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(&html, &url, Default::default()).unwrap();
+    assert_eq!(
+        result.text,
+        "This is a teaser with more than 25 characters."
+    );
+
+    let options = ExtractOptions {
+        embedded_article: true,
+        ..Default::default()
+    };
+    let result = extract_from_str(&html, &url, options).unwrap();
+    assert_eq!(result.title, "Test Title");
+    assert_eq!(result.text.matches(paragraph).count(), 3);
+    assert!(!result.text.contains("teaser"));
+}
+
+#[test]
+fn test_extract_classifier() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <div id="text">
+                    <p>This is a test paragraph, with commas, and more than 25 characters.</p>
+                    <p>This is another paragraph, with commas, and more than 25 characters.</p>
+                </div>
+                <section id="short"><p>Short notes.</p><p>More notes.</p></section>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert!(result.text.contains("test paragraph"));
+
+    // Prefer short paragraphs, which are ignored by the heuristics.
+    let classifier = |features: &NodeFeatures| {
+        (features.tag == "p" && features.text_length < 20).then_some(10.0)
+    };
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            classifier: Some(&classifier),
+            candidate_score: CandidateScore::LevelWeight,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract_from_str(html, &url, options).unwrap();
+    assert!(result.text.contains("Short notes."));
+    assert!(!result.text.contains("test paragraph"));
+}
+
+#[test]
+fn test_extract_content_selector() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <div class="author-box"><p>Jane Doe is a science reporter who writes about physics and space.</p></div>
+                <nav>Menu</nav>
+                <main>
+                    <h1>Heading</h1>
+                    <article>
+                        <p>This is a test paragraph, with commas, and more than 25 characters.</p>
+                        <p>This is another paragraph, with commas, and more than 25 characters.</p>
+                    </article>
+                </main>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    // The author bio is removed before scoring, but counts as a sibling.
+    let options = ExtractOptions {
+        author_bio: AuthorBio::Extract,
+        outputs: OutputSet {
+            selectors: true,
+            ..OutputSet::ALL
+        },
+        ..Default::default()
+    };
+    let result = extract_from_str(html, &url, options.clone()).unwrap();
+    assert!(result.author_bio.is_some());
+    assert_eq!(
+        result.content_selector,
+        "html > body:nth-child(2) > main:nth-child(3) > article:nth-child(2)"
+    );
+
+    let document = ScoredDocument::from_str(html, &url, options).unwrap();
+    let content = document.render(&CleanOptions::default()).unwrap();
+    assert_eq!(content.selector, result.content_selector);
+
+    // The content isn't located unless requested.
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert_eq!(result.content_selector, "");
+}
+
+#[test]
+fn test_extract_clean_predicates() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <article>
+                    <p>This is a test paragraph, with commas, and more than 25 characters.</p>
+                    <div data-testid="promo">Subscribe to our premium plan, with discounts.</div>
+                    <p>This is another paragraph, with commas, and more than 25 characters.</p>
+                    <aside class="pullquote">A quote from the article</aside>
+                </article>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert!(result.text.contains("premium plan"));
+    assert!(!result.text.contains("A quote"));
+
+    let remove_promos = |node: &NodeView| match node.attr("data-testid").as_deref() {
+        Some("promo") => CleanDecision::Remove,
+        _ => CleanDecision::Continue,
+    };
+    let keep_pullquotes = |node: &NodeView| {
+        if node.tag == "aside" && node.attr("class").as_deref() == Some("pullquote") {
+            CleanDecision::Keep
+        } else {
+            CleanDecision::Continue
+        }
+    };
+    let options = ExtractOptions {
+        scorer_options: ScorerOptions {
+            clean_options: CleanOptions {
+                clean_predicates: &[&remove_promos, &keep_pullquotes],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = extract_from_str(html, &url, options).unwrap();
+    assert!(!result.text.contains("premium plan"));
+    assert!(result.text.contains("A quote"));
+    assert!(result.text.contains("another paragraph"));
+}
+
+#[test]
+fn test_extract_from_str() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <p>This is a test paragraph with more than 25 characters.</p>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert_eq!(result.title, "Test Title");
+    assert_eq!(
+        result.text,
+        "This is a test paragraph with more than 25 characters."
+    );
+
+    let result = Readable::try_from((html, &url)).unwrap();
+    assert_eq!(result.title, "Test Title");
+}
+
+#[test]
+fn test_extract_from_bytes() {
+    let html = b"
+        <!DOCTYPE html>
+        <html>
+            <head><meta charset=\"iso-8859-1\"><title>Caf\xe9</title></head>
+            <body>
+                <p>This is a test paragraph about the caf\xe9 around the corner.</p>
+            </body>
+        </html>
+        ";
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_bytes(html, &url, Default::default()).unwrap();
+    assert_eq!(result.title, "Café");
+    assert_eq!(
+        result.text,
+        "This is a test paragraph about the café around the corner."
+    );
+
+    let result = Readable::try_from((&html[..], &url)).unwrap();
+    assert_eq!(result.title, "Café");
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_extract_compressed() {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let html = b"<html><head><meta charset=\"iso-8859-1\"><title>Caf\xe9</title></head>\
+        <body><p>This is a test paragraph about the caf\xe9 around the corner.</p></body></html>";
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(html).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_bytes(&compressed, &url, Default::default()).unwrap();
+    assert_eq!(result.title, "Café");
+
+    let document = ScoredDocument::from_bytes(&compressed, &url, Default::default()).unwrap();
+    assert_eq!(document.title(), "Caf\u{fffd}");
+
+    // The input is only decompressed once.
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(&compressed).unwrap();
+    let compressed = encoder.finish().unwrap();
+    assert!(matches!(
+        extract_from_bytes(&compressed, &url, Default::default()),
+        Err(ReadabilityError::NotHtml)
+    ));
+}
+
+#[test]
+fn test_extract_with_node() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <h1>Welcome</h1>
+                <p>This is a test paragraph with more than 25 characters.</p>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+    let mut input = Cursor::new(html);
+
+    let (result, node) = extract_with_node(&mut input, &url, Default::default()).unwrap();
+    assert_eq!(result.title, "Test Title");
+
+    let NodeData::Element { ref name, .. } = node.data else {
+        panic!("Unexpected node: {node:?}");
+    };
+    assert_eq!(name.local.as_ref(), "body");
+
+    let mut text = String::new();
+    extract_text(&node, &mut text, true);
+    assert_eq!(text, result.text);
+}
+
+#[test]
+fn test_extract_owned() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <h1>Welcome</h1>
+                <p>This is a test paragraph with <b>more</b> than 25 characters.</p>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+    let mut input = Cursor::new(html);
+
+    let (result, content) = extract_owned(&mut input, &url, Default::default()).unwrap();
+
+    let content = std::thread::spawn(move || content).join().unwrap();
+    assert_eq!(content.title, "Test Title");
+    assert_eq!(content.dom.tag_name(content.node), Some("body"));
+    assert_eq!(content.dom.text_content(content.node), result.text);
+}
+
+#[test]
+fn test_readable_render() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <h2>Welcome</h2>
+                <p>This is a test paragraph with <b>more</b> than 25 characters.</p>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let result = extract_from_str(html, &url, Default::default()).unwrap();
+    assert!(result.content_node.is_none());
+
+    let options = ExtractOptions {
+        outputs: OutputSet {
+            content_node: true,
+            ..OutputSet::ALL
+        },
+        ..Default::default()
+    };
+    let result = extract_from_str(html, &url, options).unwrap();
+
+    assert!(result.content_node.is_some());
+    assert_eq!(result.to_html(), result.content);
+    assert_eq!(result.to_text(), result.text);
+    assert_eq!(result.to_string(), result.text);
+    assert_eq!(
+        result.to_markdown(),
+        "## Welcome\n\nThis is a test paragraph with **more** than 25 characters."
+    );
+}
+
+#[test]
+fn test_extract_outputs() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <p>This is a test paragraph with more than 25 characters.</p>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+
+    let options = ExtractOptions {
+        outputs: OutputSet {
+            content_node: true,
+            ..OutputSet::TEXT
+        },
+        ..Default::default()
+    };
+    let result = extract_from_str(html, &url, options).unwrap();
+    assert_eq!(result.content, "");
+    assert_eq!(
+        result.text,
+        "This is a test paragraph with more than 25 characters."
+    );
+    assert!(result.to_html().contains("<p>This is a test paragraph"));
+
+    let options = ExtractOptions {
+        outputs: OutputSet::HTML,
+        text_options: Some(Default::default()),
+        ..Default::default()
+    };
+    let result = extract_from_str(html, &url, options).unwrap();
+    assert!(result.content.contains("<p>This is a test paragraph"));
+    assert_eq!(result.text, "");
+}
+
+#[test]
+fn test_extractor() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html>
+            <head><title>Test Title</title></head>
+            <body>
+                <p>This is a test paragraph with more than 25 characters.</p>
+            </body>
+        </html>
+        "#;
+    let url = Url::parse("https://example.com").unwrap();
+    let extractor = Extractor::new(ExtractOptions::preset(Preset::Blog)).unwrap();
+
+    let results = std::thread::scope(|scope| {
+        let handles = (0..4)
+            .map(|_| scope.spawn(|| extractor.extract_from_str(html, &url).unwrap()))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+
+    for result in results {
+        assert_eq!(result.title, "Test Title");
+        assert_eq!(
+            result.text,
+            "This is a test paragraph with more than 25 characters."
+        );
+    }
+
+    let mut input = Cursor::new(html);
+    let result = extractor.extract(&mut input, &url).unwrap();
+    assert_eq!(result.title, "Test Title");
+}
+
 #[test]
 fn test_extract_large_html() {
     let html = format!(